@@ -0,0 +1,28 @@
+// A full-screen pass that splits the color channels towards the edges and darkens the corners.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct PostProcessingSettings {
+    vignette_strength: f32,
+    aberration_offset: f32,
+}
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var texture_sampler: sampler;
+@group(0) @binding(2) var<uniform> settings: PostProcessingSettings;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    // The split grows towards the edges, so the center stays sharp.
+    let from_center = in.uv - vec2(0.5);
+    let offset = from_center * settings.aberration_offset;
+    let center = textureSample(screen_texture, texture_sampler, in.uv);
+    let red = textureSample(screen_texture, texture_sampler, in.uv + offset).r;
+    let blue = textureSample(screen_texture, texture_sampler, in.uv - offset).b;
+
+    // 1 in the corners.
+    let distance = length(from_center) * 1.4142135;
+    let vignette = 1.0 - settings.vignette_strength * smoothstep(0.4, 1.0, distance);
+
+    return vec4(vec3(red, center.g, blue) * vignette, center.a);
+}
@@ -0,0 +1,174 @@
+//! # Boids
+//! A 3D flocking simulation. Every boid steers away from the boids too close to it, towards the
+//! average heading of its neighbors and towards their center, which together make flocks emerge.
+//! Neighbors are found with a grid of cells as big as the neighbor radius, so each boid only
+//! looks at the boids in the cells around it.
+//! The weights of the three rules can be tweaked in the inspector window.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use bevy_inspector_egui::{bevy_egui::EguiPlugin, quick::ResourceInspectorPlugin};
+use creative_bevy::plugins::esc_exit_plugin::EscExitPlugin;
+
+const BOID_COUNT: usize = 400;
+/// The boids turn back when they leave a cube this far from the origin.
+const BOUNDS: f32 = 20.0;
+
+#[derive(Component)]
+struct Boid {
+    velocity: Vec3,
+}
+
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+struct BoidsConfig {
+    separation_weight: f32,
+    alignment_weight: f32,
+    cohesion_weight: f32,
+    max_speed: f32,
+    neighbor_radius: f32,
+}
+
+impl Default for BoidsConfig {
+    fn default() -> Self {
+        Self {
+            separation_weight: 1.5,
+            alignment_weight: 1.0,
+            cohesion_weight: 1.0,
+            max_speed: 8.0,
+            neighbor_radius: 2.5,
+        }
+    }
+}
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::BLACK))
+        .init_resource::<BoidsConfig>()
+        .register_type::<BoidsConfig>()
+        .add_plugins((
+            DefaultPlugins,
+            EguiPlugin {
+                enable_multipass_for_primary_context: true,
+            },
+            ResourceInspectorPlugin::<BoidsConfig>::default(),
+            EscExitPlugin,
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (flocking, move_boids).chain())
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 15.0, 55.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(3.0, 10.0, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    let mesh = meshes.add(Cone::new(0.2, 0.6));
+    let material = materials.add(Color::linear_rgb(0.2, 0.7, 1.0));
+
+    // Spread the boids on a spiral so no random number generator is needed.
+    for i in 0..BOID_COUNT {
+        let t = i as f32 / BOID_COUNT as f32;
+        let angle = i as f32 * 2.4;
+        let position = Vec3::new(
+            angle.cos() * BOUNDS * 0.6 * t,
+            (t - 0.5) * BOUNDS,
+            angle.sin() * BOUNDS * 0.6 * t,
+        );
+        let velocity = Vec3::new(-angle.sin(), (angle * 0.3).sin(), angle.cos()) * 3.0;
+
+        commands.spawn((
+            Boid { velocity },
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            Transform::from_translation(position),
+        ));
+    }
+}
+
+/// Returns the grid cell containing the position.
+fn cell(position: Vec3, cell_size: f32) -> IVec3 {
+    (position / cell_size).floor().as_ivec3()
+}
+
+fn flocking(time: Res<Time>, config: Res<BoidsConfig>, mut boids: Query<(&mut Boid, &Transform)>) {
+    let radius = config.neighbor_radius.max(0.1);
+
+    let mut grid = HashMap::<IVec3, Vec<(Vec3, Vec3)>>::default();
+    for (boid, transform) in boids.iter() {
+        grid.entry(cell(transform.translation, radius))
+            .or_default()
+            .push((transform.translation, boid.velocity));
+    }
+
+    let dt = time.delta_secs();
+    for (mut boid, transform) in boids.iter_mut() {
+        let position = transform.translation;
+        let center_cell = cell(position, radius);
+
+        let mut separation = Vec3::ZERO;
+        let mut average_velocity = Vec3::ZERO;
+        let mut center = Vec3::ZERO;
+        let mut count = 0;
+
+        // The cell is as big as the radius, so all the neighbors are in the surrounding cells.
+        for x in -1..=1 {
+            for y in -1..=1 {
+                for z in -1..=1 {
+                    let Some(others) = grid.get(&(center_cell + IVec3::new(x, y, z))) else {
+                        continue;
+                    };
+
+                    for &(other_position, other_velocity) in others {
+                        let offset = position - other_position;
+                        let distance = offset.length();
+                        // Skip the boid itself.
+                        if distance == 0.0 || distance > radius {
+                            continue;
+                        }
+
+                        // Closer boids push harder.
+                        separation += offset / (distance * distance);
+                        average_velocity += other_velocity;
+                        center += other_position;
+                        count += 1;
+                    }
+                }
+            }
+        }
+
+        let mut acceleration = Vec3::ZERO;
+        if count > 0 {
+            let count = count as f32;
+            acceleration += separation * config.separation_weight;
+            acceleration += (average_velocity / count - boid.velocity) * config.alignment_weight;
+            acceleration += (center / count - position) * config.cohesion_weight;
+        }
+
+        // Steer back towards the origin when leaving the bounds.
+        let outside = position.abs() - Vec3::splat(BOUNDS);
+        acceleration -= position.signum() * outside.max(Vec3::ZERO) * 2.0;
+
+        boid.velocity = (boid.velocity + acceleration * dt).clamp_length_max(config.max_speed);
+    }
+}
+
+fn move_boids(time: Res<Time>, mut boids: Query<(&Boid, &mut Transform)>) {
+    for (boid, mut transform) in boids.iter_mut() {
+        transform.translation += boid.velocity * time.delta_secs();
+
+        // The cone mesh points up, so turn its up axis to the velocity.
+        if let Some(direction) = boid.velocity.try_normalize() {
+            transform.rotation = Quat::from_rotation_arc(Vec3::Y, direction);
+        }
+    }
+}
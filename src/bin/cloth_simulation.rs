@@ -0,0 +1,227 @@
+//! # Cloth Simulation
+//! A piece of cloth hanging from its top edge and blowing in the wind.
+//! The cloth is a grid of particles moved with Verlet integration, and each particle is kept at
+//! a fixed distance from its neighbors by constraints that are solved a few times every frame.
+//! The rendered mesh is regenerated from the particle positions every frame.
+
+use bevy::{
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+    },
+};
+use creative_bevy::plugins::esc_exit_plugin::EscExitPlugin;
+
+const COLUMNS: usize = 24;
+const ROWS: usize = 18;
+const SPACING: f32 = 0.2;
+const GRAVITY: Vec3 = Vec3::new(0.0, -9.81, 0.0);
+/// How much of the velocity is kept every step, so the cloth settles down.
+const DAMPING: f32 = 0.99;
+const CONSTRAINT_ITERATIONS: usize = 10;
+
+#[derive(Component)]
+struct ClothParticle {
+    position: Vec3,
+    prev_position: Vec3,
+    /// Pinned particles never move.
+    pinned: bool,
+}
+
+/// Keeps two particles `rest_length` apart.
+#[derive(Component)]
+struct ClothConstraint {
+    a: Entity,
+    b: Entity,
+    rest_length: f32,
+}
+
+/// The rendered cloth, with its particles in row-major order.
+#[derive(Component)]
+struct ClothMesh {
+    particles: Vec<Entity>,
+}
+
+/// The wind blowing on the cloth, as an acceleration.
+#[derive(Resource)]
+struct WindForce(Vec3);
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::BLACK))
+        .insert_resource(WindForce(Vec3::new(0.0, 0.0, 4.0)))
+        .add_plugins((DefaultPlugins, EscExitPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (simulate_cloth, update_cloth_mesh).chain())
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 1.0, 7.0).looking_at(Vec3::new(0.0, 0.5, 0.0), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(2.0, 4.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    // The top row hangs from a horizontal bar.
+    let origin = Vec3::new(-((COLUMNS - 1) as f32) * SPACING / 2.0, 2.5, 0.0);
+    let mut particles = Vec::with_capacity(COLUMNS * ROWS);
+    for row in 0..ROWS {
+        for column in 0..COLUMNS {
+            let position = origin + Vec3::new(column as f32, -(row as f32), 0.0) * SPACING;
+            let particle = commands
+                .spawn(ClothParticle {
+                    position,
+                    prev_position: position,
+                    pinned: row == 0,
+                })
+                .id();
+            particles.push(particle);
+        }
+    }
+
+    for row in 0..ROWS {
+        for column in 0..COLUMNS {
+            let particle = particles[row * COLUMNS + column];
+            if column + 1 < COLUMNS {
+                commands.spawn(ClothConstraint {
+                    a: particle,
+                    b: particles[row * COLUMNS + column + 1],
+                    rest_length: SPACING,
+                });
+            }
+            if row + 1 < ROWS {
+                commands.spawn(ClothConstraint {
+                    a: particle,
+                    b: particles[(row + 1) * COLUMNS + column],
+                    rest_length: SPACING,
+                });
+            }
+        }
+    }
+
+    let material = materials.add(StandardMaterial {
+        base_color: Color::linear_rgb(0.8, 0.1, 0.1),
+        // Show both sides of the cloth.
+        cull_mode: None,
+        double_sided: true,
+        ..default()
+    });
+    commands.spawn((
+        Mesh3d(meshes.add(cloth_mesh())),
+        MeshMaterial3d(material),
+        ClothMesh { particles },
+    ));
+}
+
+/// Creates the cloth mesh with the triangles of the grid. The positions and normals are filled in
+/// by [`update_cloth_mesh`].
+fn cloth_mesh() -> Mesh {
+    let mut indices = Vec::with_capacity((COLUMNS - 1) * (ROWS - 1) * 6);
+    for row in 0..ROWS - 1 {
+        for column in 0..COLUMNS - 1 {
+            let top_left = (row * COLUMNS + column) as u32;
+            let top_right = top_left + 1;
+            let bottom_left = top_left + COLUMNS as u32;
+            let bottom_right = bottom_left + 1;
+            indices.extend([
+                top_left,
+                bottom_left,
+                top_right,
+                top_right,
+                bottom_left,
+                bottom_right,
+            ]);
+        }
+    }
+
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(
+        Mesh::ATTRIBUTE_POSITION,
+        vec![[0.0, 0.0, 0.0]; COLUMNS * ROWS],
+    )
+    .with_inserted_indices(Indices::U32(indices))
+}
+
+fn simulate_cloth(
+    time: Res<Time>,
+    wind: Res<WindForce>,
+    mut particles: Query<&mut ClothParticle>,
+    constraints: Query<&ClothConstraint>,
+) {
+    // A long frame would make the cloth explode.
+    let dt = time.delta_secs().min(1.0 / 30.0);
+    let elapsed = time.elapsed_secs();
+
+    for mut particle in particles.iter_mut() {
+        if particle.pinned {
+            continue;
+        }
+
+        // Gusts that travel along the cloth.
+        let gust = 1.0 + 0.8 * (elapsed * 2.0 + particle.position.x * 1.5).sin();
+        let acceleration = GRAVITY + wind.0 * gust;
+        let velocity = (particle.position - particle.prev_position) * DAMPING;
+        particle.prev_position = particle.position;
+        particle.position += velocity + acceleration * dt * dt;
+    }
+
+    for _ in 0..CONSTRAINT_ITERATIONS {
+        for constraint in constraints.iter() {
+            let Ok([mut a, mut b]) = particles.get_many_mut([constraint.a, constraint.b]) else {
+                continue;
+            };
+
+            let delta = b.position - a.position;
+            let distance = delta.length();
+            if distance == 0.0 {
+                continue;
+            }
+
+            // Move both particles halfway to the rest length, or only the free one if the other
+            // is pinned.
+            let correction = delta * (distance - constraint.rest_length) / distance;
+            match (a.pinned, b.pinned) {
+                (false, false) => {
+                    a.position += correction / 2.0;
+                    b.position -= correction / 2.0;
+                }
+                (false, true) => a.position += correction,
+                (true, false) => b.position -= correction,
+                (true, true) => {}
+            }
+        }
+    }
+}
+
+fn update_cloth_mesh(
+    mut meshes: ResMut<Assets<Mesh>>,
+    cloths: Query<(&Mesh3d, &ClothMesh)>,
+    particles: Query<&ClothParticle>,
+) {
+    for (mesh3d, cloth) in cloths.iter() {
+        let Some(mesh) = meshes.get_mut(&mesh3d.0) else {
+            continue;
+        };
+
+        let positions: Vec<[f32; 3]> = cloth
+            .particles
+            .iter()
+            .filter_map(|particle| particles.get(*particle).ok())
+            .map(|particle| particle.position.to_array())
+            .collect();
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.compute_smooth_normals();
+    }
+}
@@ -0,0 +1,233 @@
+//! # Fluid
+//! A 2D fluid simulated with smoothed particle hydrodynamics (SPH).
+//! Every particle's density is estimated from the particles within the kernel radius, the
+//! density gives the pressure pushing the particles apart, and viscosity evens out the
+//! velocities of neighbors. The particles bounce off the edges of the window.
+//! The kernels are the ones from Müller et al., "Particle-Based Fluid Simulation for Interactive
+//! Applications", in their 2D form.
+
+use std::f32::consts::PI;
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use creative_bevy::plugins::esc_exit_plugin::EscExitPlugin;
+
+const PARTICLE_COLUMNS: usize = 30;
+const PARTICLE_ROWS: usize = 30;
+
+#[derive(Component)]
+struct FluidParticle {
+    position: Vec2,
+    velocity: Vec2,
+    density: f32,
+    pressure: f32,
+}
+
+#[derive(Resource)]
+struct SphConfig {
+    /// The distance within which particles affect each other, in pixels.
+    kernel_radius: f32,
+    particle_mass: f32,
+    /// The density the fluid settles at.
+    rest_density: f32,
+    /// How strongly the pressure pushes the particles back towards the rest density.
+    stiffness: f32,
+    viscosity: f32,
+    gravity: Vec2,
+    /// The length of one simulation step. The simulation is only stable with small steps, so
+    /// several of them are taken every frame.
+    time_step: f32,
+    steps_per_frame: usize,
+    /// How much of the velocity is kept when bouncing off an edge.
+    bounce: f32,
+}
+
+impl Default for SphConfig {
+    fn default() -> Self {
+        Self {
+            kernel_radius: 16.0,
+            particle_mass: 2.5,
+            rest_density: 300.0,
+            stiffness: 2000.0,
+            viscosity: 200.0,
+            // Scaled up to work with the pixel units and the tiny time step.
+            gravity: Vec2::new(0.0, -9.8 * 12000.0),
+            time_step: 0.0007,
+            steps_per_frame: 8,
+            bounce: 0.5,
+        }
+    }
+}
+
+/// The particles bucketed into square cells as big as the kernel radius, so only the particles
+/// in the surrounding cells need to be checked for neighbors.
+#[derive(Resource, Default)]
+struct SpatialGrid {
+    cell_size: f32,
+    /// Indices into the particles of the current step.
+    cells: HashMap<IVec2, Vec<usize>>,
+}
+
+impl SpatialGrid {
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    fn rebuild(&mut self, cell_size: f32, positions: impl Iterator<Item = Vec2>) {
+        self.cell_size = cell_size;
+        for cell in self.cells.values_mut() {
+            cell.clear();
+        }
+        for (i, position) in positions.enumerate() {
+            let cell = self.cell(position);
+            self.cells.entry(cell).or_default().push(i);
+        }
+    }
+
+    /// Returns the indices of the particles that may be within a cell of the position.
+    fn neighbors(&self, position: Vec2) -> impl Iterator<Item = usize> + '_ {
+        let center = self.cell(position);
+        (-1..=1)
+            .flat_map(move |x| (-1..=1).map(move |y| center + IVec2::new(x, y)))
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+    }
+}
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::BLACK))
+        .init_resource::<SphConfig>()
+        .init_resource::<SpatialGrid>()
+        .add_plugins((DefaultPlugins, EscExitPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (sph_simulate, update_particle_transforms).chain())
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    config: Res<SphConfig>,
+) {
+    commands.spawn(Camera2d);
+
+    let mesh = meshes.add(Circle::new(config.kernel_radius / 2.0));
+    let material = materials.add(Color::linear_rgb(0.1, 0.4, 1.0));
+
+    // A block of fluid in the upper left, which collapses when the simulation starts.
+    let spacing = config.kernel_radius * 0.9;
+    let origin = Vec2::new(-300.0, 0.0);
+    for row in 0..PARTICLE_ROWS {
+        for column in 0..PARTICLE_COLUMNS {
+            // A little offset on every other row so the block doesn't stay stacked in columns.
+            let jitter = if row % 2 == 0 { 0.0 } else { spacing * 0.1 };
+            let position =
+                origin + Vec2::new(column as f32 * spacing + jitter, row as f32 * spacing);
+
+            commands.spawn((
+                FluidParticle {
+                    position,
+                    velocity: Vec2::ZERO,
+                    density: 0.0,
+                    pressure: 0.0,
+                },
+                Mesh2d(mesh.clone()),
+                MeshMaterial2d(material.clone()),
+                Transform::from_translation(position.extend(0.0)),
+            ));
+        }
+    }
+}
+
+fn sph_simulate(
+    config: Res<SphConfig>,
+    mut grid: ResMut<SpatialGrid>,
+    window: Single<&Window>,
+    mut query: Query<&mut FluidParticle>,
+) {
+    let h = config.kernel_radius;
+    let h2 = h * h;
+    let poly6 = 4.0 / (PI * h.powi(8));
+    let spiky_gradient = -10.0 / (PI * h.powi(5));
+    let viscosity_laplacian = 40.0 / (PI * h.powi(5));
+    let half_size = window.size() / 2.0;
+
+    let mut particles: Vec<_> = query.iter_mut().collect();
+    for _ in 0..config.steps_per_frame {
+        grid.rebuild(h, particles.iter().map(|particle| particle.position));
+
+        for i in 0..particles.len() {
+            let position = particles[i].position;
+            let density: f32 = grid
+                .neighbors(position)
+                .map(|j| position.distance_squared(particles[j].position))
+                .filter(|&r2| r2 < h2)
+                .map(|r2| config.particle_mass * poly6 * (h2 - r2).powi(3))
+                .sum();
+
+            let particle = &mut particles[i];
+            particle.density = density;
+            particle.pressure = config.stiffness * (density - config.rest_density);
+        }
+
+        let forces: Vec<Vec2> = (0..particles.len())
+            .map(|i| {
+                let particle = &particles[i];
+                let mut pressure_force = Vec2::ZERO;
+                let mut viscosity_force = Vec2::ZERO;
+
+                for j in grid.neighbors(particle.position) {
+                    if i == j {
+                        continue;
+                    }
+                    let other = &particles[j];
+                    let offset = other.position - particle.position;
+                    let r = offset.length();
+                    if r >= h || r == 0.0 {
+                        continue;
+                    }
+
+                    pressure_force +=
+                        -offset / r * config.particle_mass * (particle.pressure + other.pressure)
+                            / (2.0 * other.density)
+                            * spiky_gradient
+                            * (h - r).powi(3);
+                    viscosity_force += config.viscosity
+                        * config.particle_mass
+                        * (other.velocity - particle.velocity)
+                        / other.density
+                        * viscosity_laplacian
+                        * (h - r);
+                }
+
+                let gravity_force = config.gravity * config.particle_mass / particle.density;
+                pressure_force + viscosity_force + gravity_force
+            })
+            .collect();
+
+        for (particle, force) in particles.iter_mut().zip(forces) {
+            // Every particle is at least its own neighbor, so the density is never zero.
+            let acceleration = force / particle.density;
+            particle.velocity += acceleration * config.time_step;
+            let velocity = particle.velocity;
+            particle.position += velocity * config.time_step;
+
+            // Reflect the velocity on the edges of the window.
+            for axis in 0..2 {
+                let limit = half_size[axis] - h / 2.0;
+                if particle.position[axis].abs() > limit {
+                    particle.position[axis] = particle.position[axis].clamp(-limit, limit);
+                    particle.velocity[axis] *= -config.bounce;
+                }
+            }
+        }
+    }
+}
+
+fn update_particle_transforms(mut query: Query<(&FluidParticle, &mut Transform)>) {
+    for (particle, mut transform) in query.iter_mut() {
+        transform.translation = particle.position.extend(0.0);
+    }
+}
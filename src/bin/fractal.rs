@@ -0,0 +1,465 @@
+//! # Fractal Tree
+//! Grows a tree from a Lindenmayer system stored in `assets/lsystems/tree.json`.
+//! The axiom is rewritten with the rules a number of times, and the resulting string is drawn
+//! with 3D turtle graphics:
+//! - `F` draws a branch forward, `f` moves forward without drawing.
+//! - `+` and `-` turn left and right, `&` and `^` pitch down and up, `\` and `/` roll, and `|`
+//!   turns around, all by the angle of the system.
+//! - `[` and `]` save and restore the turtle, which starts a side branch.
+//!
+//! Every branch is an entity sharing the same cylinder mesh and material, so Bevy draws them
+//! with instancing instead of one draw call each.
+//!
+//! Controls:
+//! - `W`, `A`, `S`, `D`, `Space` and `Shift` fly the camera, the arrow keys turn it.
+//! - `=` and `-` change the number of iterations, `]` and `[` change the segment length.
+//!
+//! With Bevy's `file_watcher` feature, edits to the JSON file show up while the program runs.
+
+use std::{error::Error, fmt};
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    platform::collections::HashMap,
+    prelude::*,
+};
+use creative_bevy::plugins::esc_exit_plugin::EscExitPlugin;
+
+/// The rewritten string grows exponentially, so it's capped to keep the program responsive.
+const MAX_SYMBOLS: usize = 2_000_000;
+const MAX_ITERATIONS: usize = 8;
+const CAMERA_SPEED: f32 = 10.0;
+const CAMERA_TURN_SPEED: f32 = 1.5;
+
+/// A Lindenmayer system loaded from JSON.
+#[derive(Asset, TypePath)]
+struct LSystem {
+    axiom: String,
+    rules: HashMap<char, String>,
+    /// The turning angle in degrees.
+    angle: f32,
+    /// The default number of iterations.
+    iterations: usize,
+}
+
+impl LSystem {
+    /// Rewrites the axiom `iterations` times, stopping early if the string gets too long.
+    fn expand(&self, iterations: usize) -> String {
+        let mut current = self.axiom.clone();
+        for i in 0..iterations {
+            let mut next = String::with_capacity(current.len() * 2);
+            for symbol in current.chars() {
+                match self.rules.get(&symbol) {
+                    Some(replacement) => next.push_str(replacement),
+                    None => next.push(symbol),
+                }
+            }
+
+            if next.len() > MAX_SYMBOLS {
+                warn!(
+                    "The L-system is too long after {} iterations, stopping at {i}.",
+                    i + 1
+                );
+                break;
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+#[derive(Default)]
+struct LSystemLoader;
+
+#[derive(Debug)]
+enum LSystemLoaderError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Invalid(String),
+}
+
+impl fmt::Display for LSystemLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read the L-system: {err}"),
+            Self::Json(err) => write!(f, "failed to parse the L-system: {err}"),
+            Self::Invalid(message) => write!(f, "invalid L-system: {message}"),
+        }
+    }
+}
+
+impl Error for LSystemLoaderError {}
+
+impl AssetLoader for LSystemLoader {
+    type Asset = LSystem;
+    type Settings = ();
+    type Error = LSystemLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<LSystem, LSystemLoaderError> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(LSystemLoaderError::Io)?;
+        parse_lsystem(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["json"]
+    }
+}
+
+/// Parses the JSON of an L-system.
+fn parse_lsystem(bytes: &[u8]) -> Result<LSystem, LSystemLoaderError> {
+    let value: serde_json::Value =
+        serde_json::from_slice(bytes).map_err(LSystemLoaderError::Json)?;
+    let invalid = |message: &str| LSystemLoaderError::Invalid(message.to_string());
+
+    let axiom = value["axiom"]
+        .as_str()
+        .ok_or_else(|| invalid("`axiom` should be a string"))?
+        .to_string();
+    let angle = value["angle"]
+        .as_f64()
+        .ok_or_else(|| invalid("`angle` should be a number"))? as f32;
+    let iterations = value["iterations"]
+        .as_u64()
+        .ok_or_else(|| invalid("`iterations` should be a non-negative integer"))?
+        as usize;
+
+    let mut rules = HashMap::default();
+    let rule_values = value["rules"]
+        .as_object()
+        .ok_or_else(|| invalid("`rules` should be an object"))?;
+    for (symbol, replacement) in rule_values {
+        let mut chars = symbol.chars();
+        let (Some(symbol), None) = (chars.next(), chars.next()) else {
+            return Err(invalid("the keys of `rules` should be single symbols"));
+        };
+        let replacement = replacement
+            .as_str()
+            .ok_or_else(|| invalid("the values of `rules` should be strings"))?;
+        rules.insert(symbol, replacement.to_string());
+    }
+
+    Ok(LSystem {
+        axiom,
+        rules,
+        angle,
+        iterations,
+    })
+}
+
+/// The tree being shown.
+#[derive(Resource)]
+struct Tree {
+    system: Handle<LSystem>,
+    /// `None` until the system is loaded and its default is used.
+    iterations: Option<usize>,
+    segment_length: f32,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+    /// Rebuild the branches in the next frame.
+    dirty: bool,
+}
+
+#[derive(Component)]
+struct Branch;
+
+/// The turtle drawing the branches.
+#[derive(Clone, Copy)]
+struct Turtle {
+    position: Vec3,
+    /// Forward is the local `Y` axis, like the cylinder mesh.
+    rotation: Quat,
+    /// The branch thickness, which gets smaller in side branches.
+    radius: f32,
+}
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::BLACK))
+        .add_plugins((DefaultPlugins, EscExitPlugin))
+        .init_asset::<LSystem>()
+        .init_asset_loader::<LSystemLoader>()
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (mark_tree_dirty, adjust_tree, build_tree, fly_camera).chain(),
+        )
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 12.0, 40.0).looking_at(Vec3::new(0.0, 12.0, 0.0), Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(5.0, 20.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    commands.insert_resource(Tree {
+        system: asset_server.load("lsystems/tree.json"),
+        iterations: None,
+        segment_length: 0.3,
+        // A unit cylinder scaled to the size of each branch.
+        mesh: meshes.add(Cylinder::new(1.0, 1.0)),
+        material: materials.add(Color::linear_rgb(0.45, 0.3, 0.15)),
+        dirty: false,
+    });
+}
+
+fn mark_tree_dirty(mut tree: ResMut<Tree>, mut events: EventReader<AssetEvent<LSystem>>) {
+    for event in events.read() {
+        if event.is_loaded_with_dependencies(&tree.system) || event.is_modified(&tree.system) {
+            tree.dirty = true;
+        }
+    }
+}
+
+fn adjust_tree(keyboard: Res<ButtonInput<KeyCode>>, mut tree: ResMut<Tree>) {
+    let Some(iterations) = tree.iterations else {
+        return;
+    };
+
+    if keyboard.just_pressed(KeyCode::Equal) && iterations < MAX_ITERATIONS {
+        tree.iterations = Some(iterations + 1);
+        tree.dirty = true;
+    }
+    if keyboard.just_pressed(KeyCode::Minus) && iterations > 0 {
+        tree.iterations = Some(iterations - 1);
+        tree.dirty = true;
+    }
+    if keyboard.just_pressed(KeyCode::BracketRight) {
+        tree.segment_length *= 1.25;
+        tree.dirty = true;
+    }
+    if keyboard.just_pressed(KeyCode::BracketLeft) {
+        tree.segment_length /= 1.25;
+        tree.dirty = true;
+    }
+}
+
+fn build_tree(
+    mut commands: Commands,
+    mut tree: ResMut<Tree>,
+    systems: Res<Assets<LSystem>>,
+    branches: Query<Entity, With<Branch>>,
+) {
+    if !tree.dirty {
+        return;
+    }
+    let Some(system) = systems.get(&tree.system) else {
+        return;
+    };
+    tree.dirty = false;
+
+    for branch in branches.iter() {
+        commands.entity(branch).despawn();
+    }
+
+    let iterations = *tree.iterations.get_or_insert(system.iterations);
+    let branches = draw_branches(
+        &system.expand(iterations),
+        system.angle.to_radians(),
+        tree.segment_length,
+    );
+    let count = branches.len();
+    let (mesh, material) = (tree.mesh.clone(), tree.material.clone());
+    commands.spawn_batch(branches.into_iter().map(move |transform| {
+        (
+            Branch,
+            Mesh3d(mesh.clone()),
+            MeshMaterial3d(material.clone()),
+            transform,
+        )
+    }));
+
+    info!(
+        "Built the tree with {iterations} iterations and {count} branches of length {}.",
+        tree.segment_length
+    );
+}
+
+/// Walks the turtle along the expanded string and returns the transforms of the unit cylinders
+/// for its branches. The angle is in radians.
+fn draw_branches(expanded: &str, angle: f32, segment_length: f32) -> Vec<Transform> {
+    let mut turtle = Turtle {
+        position: Vec3::ZERO,
+        rotation: Quat::IDENTITY,
+        radius: segment_length * 0.3,
+    };
+    let mut stack = Vec::new();
+    let mut branches = Vec::new();
+
+    for symbol in expanded.chars() {
+        match symbol {
+            'F' | 'f' => {
+                let end = turtle.position + turtle.rotation * Vec3::Y * segment_length;
+                if symbol == 'F' {
+                    branches.push(Transform {
+                        translation: (turtle.position + end) / 2.0,
+                        rotation: turtle.rotation,
+                        scale: Vec3::new(turtle.radius, segment_length, turtle.radius),
+                    });
+                }
+                turtle.position = end;
+            }
+            '+' => turtle.rotation *= Quat::from_rotation_z(angle),
+            '-' => turtle.rotation *= Quat::from_rotation_z(-angle),
+            '&' => turtle.rotation *= Quat::from_rotation_x(angle),
+            '^' => turtle.rotation *= Quat::from_rotation_x(-angle),
+            '\\' => turtle.rotation *= Quat::from_rotation_y(angle),
+            '/' => turtle.rotation *= Quat::from_rotation_y(-angle),
+            '|' => turtle.rotation *= Quat::from_rotation_z(std::f32::consts::PI),
+            '[' => {
+                stack.push(turtle);
+                turtle.radius *= 0.7;
+            }
+            ']' => {
+                if let Some(saved) = stack.pop() {
+                    turtle = saved;
+                }
+            }
+            // Other symbols only drive the rewriting.
+            _ => {}
+        }
+    }
+    branches
+}
+
+fn fly_camera(
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut camera: Single<&mut Transform, With<Camera3d>>,
+) {
+    let dt = time.delta_secs();
+
+    let mut yaw = 0.0;
+    let mut pitch = 0.0;
+    for (key, yaw_direction, pitch_direction) in [
+        (KeyCode::ArrowLeft, 1.0, 0.0),
+        (KeyCode::ArrowRight, -1.0, 0.0),
+        (KeyCode::ArrowUp, 0.0, 1.0),
+        (KeyCode::ArrowDown, 0.0, -1.0),
+    ] {
+        if keyboard.pressed(key) {
+            yaw += yaw_direction * CAMERA_TURN_SPEED * dt;
+            pitch += pitch_direction * CAMERA_TURN_SPEED * dt;
+        }
+    }
+    camera.rotate_y(yaw);
+    camera.rotate_local_x(pitch);
+
+    let forward = camera.forward();
+    let right = camera.right();
+    let mut direction = Vec3::ZERO;
+    for (key, key_direction) in [
+        (KeyCode::KeyW, *forward),
+        (KeyCode::KeyS, -*forward),
+        (KeyCode::KeyD, *right),
+        (KeyCode::KeyA, -*right),
+        (KeyCode::Space, Vec3::Y),
+        (KeyCode::ShiftLeft, -Vec3::Y),
+    ] {
+        if keyboard.pressed(key) {
+            direction += key_direction;
+        }
+    }
+    camera.translation += direction.normalize_or_zero() * CAMERA_SPEED * dt;
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f32::consts::FRAC_PI_2;
+
+    use super::*;
+
+    #[test]
+    fn expands_the_axiom_with_the_rules() {
+        // Lindenmayer's algae, whose lengths are the Fibonacci numbers.
+        let algae = LSystem {
+            axiom: "A".to_string(),
+            rules: HashMap::from_iter([('A', "AB".to_string()), ('B', "A".to_string())]),
+            angle: 0.0,
+            iterations: 0,
+        };
+        assert_eq!(algae.expand(0), "A");
+        assert_eq!(algae.expand(4), "ABAABABA");
+        assert_eq!(algae.expand(10).len(), 144);
+    }
+
+    #[test]
+    fn stops_expanding_at_the_symbol_cap() {
+        let doubling = LSystem {
+            axiom: "F".to_string(),
+            rules: HashMap::from_iter([('F', "FF".to_string())]),
+            angle: 0.0,
+            iterations: 0,
+        };
+        let expanded = doubling.expand(100);
+        assert!(expanded.len() <= MAX_SYMBOLS);
+        assert!(expanded.len() * 2 > MAX_SYMBOLS);
+    }
+
+    #[test]
+    fn parses_the_tree_asset() {
+        let tree = parse_lsystem(include_bytes!("../../assets/lsystems/tree.json")).unwrap();
+        assert_eq!(tree.axiom, "X");
+        assert_eq!(tree.rules.get(&'F').map(String::as_str), Some("FF"));
+        assert_eq!(tree.angle, 25.0);
+        assert_eq!(tree.iterations, 5);
+    }
+
+    #[test]
+    fn rejects_malformed_lsystems() {
+        for json in [
+            r#"{"axiom": 1, "rules": {}, "angle": 25, "iterations": 5}"#,
+            r#"{"axiom": "X", "rules": {"XY": "F"}, "angle": 25, "iterations": 5}"#,
+            r#"{"axiom": "X", "rules": {"X": 1}, "angle": 25, "iterations": 5}"#,
+            r#"{"axiom": "X", "rules": {}, "angle": "wide", "iterations": 5}"#,
+            r#"{"axiom": "X", "rules": {}, "angle": 25, "iterations": -1}"#,
+            "{",
+        ] {
+            assert!(parse_lsystem(json.as_bytes()).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn draws_branches_with_the_turtle() {
+        let branches = draw_branches("F+fF[-F]F", FRAC_PI_2, 2.0);
+        let ends: Vec<_> = branches
+            .iter()
+            .map(|branch| branch.translation + branch.rotation * Vec3::Y * 1.0)
+            .collect();
+
+        // Up, then left after skipping a segment, then up in the side branch, then left again
+        // from where the branch started.
+        let expected = [
+            Vec3::new(0.0, 2.0, 0.0),
+            Vec3::new(-4.0, 2.0, 0.0),
+            Vec3::new(-4.0, 4.0, 0.0),
+            Vec3::new(-6.0, 2.0, 0.0),
+        ];
+        assert_eq!(ends.len(), expected.len());
+        for (end, expected) in ends.iter().zip(expected) {
+            assert!(end.distance(expected) < 1e-5, "{end} != {expected}");
+        }
+
+        // Side branches are thinner, and the trunk is back to its thickness after them.
+        assert!(branches[2].scale.x < branches[1].scale.x);
+        assert_eq!(branches[3].scale, branches[1].scale);
+    }
+}
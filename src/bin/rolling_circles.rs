@@ -4,11 +4,26 @@
 //! The angular velocities and circle radii are hard-coded, calculated with a numerical equations solver.
 //! I actually got two sets of solutions, but only one is used here.
 //! This program is added the `PanCamPlugin`, so users can zoom or drag the camera around.
+//! Press `R` to restart the animation, and `V` to show the tangential velocity of the circles
+//! as arrows.
 
 use bevy::prelude::*;
 use bevy_pancam::{PanCam, PanCamPlugin};
 use creative_bevy::plugins::esc_exit_plugin::EscExitPlugin;
 
+/// How long an arrow is per unit of speed.
+const ARROW_SCALE: f32 = 2.0;
+
+/// The time the animation has been running, which can be reset unlike the app's elapsed time.
+#[derive(Resource, Default)]
+struct SimClock {
+    elapsed: f32,
+}
+
+/// Whether the velocity arrows are drawn.
+#[derive(Resource, Default)]
+struct ShowVelocityArrows(bool);
+
 #[derive(Component)]
 struct AngularVelocity(f32);
 
@@ -31,12 +46,24 @@ struct CircleInfo {
 fn main() {
     App::new()
         .insert_resource(ClearColor(Color::BLACK))
+        .init_resource::<SimClock>()
+        .init_resource::<ShowVelocityArrows>()
         .add_plugins((DefaultPlugins, PanCamPlugin, EscExitPlugin))
         .add_systems(Startup, setup)
-        .add_systems(Update, (rotate_bodies, move_bodies))
+        .add_systems(
+            Update,
+            (
+                (reset_sim_clock, advance_sim_clock).chain(),
+                (rotate_bodies, move_bodies),
+                (toggle_velocity_arrows, draw_velocity_arrows).chain(),
+            )
+                .chain(),
+        )
         .run();
 }
 
+// The angular velocities are kept at the precision they were computed with.
+#[allow(clippy::excessive_precision)]
 fn setup(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -94,33 +121,69 @@ fn setup(
     );
 }
 
+fn advance_sim_clock(time: Res<Time>, mut clock: ResMut<SimClock>) {
+    clock.elapsed += time.delta_secs();
+}
+
+fn reset_sim_clock(keyboard: Res<ButtonInput<KeyCode>>, mut clock: ResMut<SimClock>) {
+    if keyboard.just_pressed(KeyCode::KeyR) {
+        clock.elapsed = 0.0;
+    }
+}
+
 fn rotate_bodies(
-    time: Res<Time>,
+    clock: Res<SimClock>,
     mut query: Query<(&AngularVelocity, &mut Transform), With<Mesh2d>>,
 ) {
     for (angular_velocity, mut transform) in query.iter_mut() {
         let translation = transform.translation;
 
-        *transform = Transform::from_rotation(Quat::from_rotation_z(
-            angular_velocity.0 * time.elapsed_secs(),
-        ));
+        *transform =
+            Transform::from_rotation(Quat::from_rotation_z(angular_velocity.0 * clock.elapsed));
 
         transform.translation = translation;
     }
 }
 
 fn move_bodies(
-    time: Res<Time>,
+    clock: Res<SimClock>,
     mut query: Query<(&Distance, &OrbitAngularVelocity, &mut Transform), With<Mesh2d>>,
 ) {
     for (distance_to_origin, orbit_angular_velocity, mut transform) in query.iter_mut() {
-        let theta = orbit_angular_velocity.0 * time.elapsed_secs();
+        let theta = orbit_angular_velocity.0 * clock.elapsed;
         let x = distance_to_origin.0 * theta.cos();
         let y = distance_to_origin.0 * theta.sin();
         transform.translation = Vec3::new(x, y, 0.0);
     }
 }
 
+fn toggle_velocity_arrows(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut show: ResMut<ShowVelocityArrows>,
+) {
+    if keyboard.just_pressed(KeyCode::KeyV) {
+        show.0 = !show.0;
+    }
+}
+
+fn draw_velocity_arrows(
+    mut gizmos: Gizmos,
+    show: Res<ShowVelocityArrows>,
+    query: Query<(&OrbitAngularVelocity, &Transform), With<Distance>>,
+) {
+    if !show.0 {
+        return;
+    }
+
+    for (orbit_angular_velocity, transform) in query.iter() {
+        // v = omega * r along the perpendicular of the position, which is the position rotated
+        // by 90 degrees, so its length is already the radius.
+        let center = transform.translation.truncate();
+        let velocity = orbit_angular_velocity.0 * center.perp();
+        gizmos.arrow_2d(center, center + velocity * ARROW_SCALE, Color::WHITE);
+    }
+}
+
 fn spawn_circle(
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
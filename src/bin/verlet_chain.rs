@@ -0,0 +1,217 @@
+//! # Verlet Chain
+//! A chain hanging from an anchor that can be dragged around with the mouse.
+//! The links are moved with Verlet integration, then the distance between each pair of
+//! neighboring links is corrected a few times, starting from the anchor and going down, so the
+//! chain keeps its length.
+//!
+//! Controls:
+//! - Hold the left mouse button to drag the anchor.
+//! - Press `+` to add a link to the end of the chain.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use creative_bevy::plugins::esc_exit_plugin::EscExitPlugin;
+
+const INITIAL_LINKS: usize = 12;
+const LINK_LENGTH: f32 = 0.4;
+const LINK_RADIUS: f32 = 0.08;
+const GRAVITY: Vec3 = Vec3::new(0.0, -9.81, 0.0);
+/// How much of the velocity is kept every step.
+const DAMPING: f32 = 0.995;
+const CONSTRAINT_ITERATIONS: usize = 15;
+
+#[derive(Component)]
+struct ChainLink {
+    position: Vec3,
+    prev_position: Vec3,
+}
+
+/// The links from the anchor to the end.
+#[derive(Resource)]
+struct Chain {
+    links: Vec<Entity>,
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+/// Where the first link is pinned.
+#[derive(Resource)]
+struct Anchor(Vec3);
+
+fn main() {
+    App::new()
+        .insert_resource(ClearColor(Color::BLACK))
+        .insert_resource(Anchor(Vec3::new(0.0, 3.0, 0.0)))
+        .add_plugins((DefaultPlugins, EscExitPlugin))
+        .add_systems(Startup, setup)
+        .add_systems(
+            Update,
+            (
+                drag_anchor,
+                add_link,
+                simulate_chain,
+                update_link_transforms,
+            )
+                .chain(),
+        )
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    anchor: Res<Anchor>,
+) {
+    commands.spawn((
+        Camera3d::default(),
+        Transform::from_xyz(0.0, 0.0, 12.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+    commands.spawn((
+        DirectionalLight::default(),
+        Transform::from_xyz(3.0, 5.0, 8.0).looking_at(Vec3::ZERO, Vec3::Y),
+    ));
+
+    let mut chain = Chain {
+        links: Vec::new(),
+        // The capsule spans a whole link, from the previous link to this one.
+        mesh: meshes.add(Capsule3d::new(LINK_RADIUS, LINK_LENGTH - LINK_RADIUS * 2.0)),
+        material: materials.add(Color::linear_rgb(0.7, 0.7, 0.75)),
+    };
+    for i in 0..INITIAL_LINKS {
+        let position = anchor.0 - Vec3::Y * LINK_LENGTH * i as f32;
+        spawn_link(&mut commands, &mut chain, position);
+    }
+    commands.insert_resource(chain);
+}
+
+fn spawn_link(commands: &mut Commands, chain: &mut Chain, position: Vec3) {
+    let link = commands
+        .spawn((
+            ChainLink {
+                position,
+                prev_position: position,
+            },
+            Mesh3d(chain.mesh.clone()),
+            MeshMaterial3d(chain.material.clone()),
+            Transform::from_translation(position),
+        ))
+        .id();
+    chain.links.push(link);
+}
+
+/// Moves the anchor to the point under the cursor on the `z = 0` plane.
+fn drag_anchor(
+    mouse: Res<ButtonInput<MouseButton>>,
+    window: Single<&Window, With<PrimaryWindow>>,
+    camera: Single<(&Camera, &GlobalTransform)>,
+    mut anchor: ResMut<Anchor>,
+) {
+    if !mouse.pressed(MouseButton::Left) {
+        return;
+    }
+    let Some(cursor) = window.cursor_position() else {
+        return;
+    };
+
+    let (camera, camera_transform) = *camera;
+    let Ok(ray) = camera.viewport_to_world(camera_transform, cursor) else {
+        return;
+    };
+    if let Some(distance) = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Z)) {
+        anchor.0 = ray.get_point(distance);
+    }
+}
+
+fn add_link(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut chain: ResMut<Chain>,
+    links: Query<&ChainLink>,
+) {
+    if !keyboard.just_pressed(KeyCode::NumpadAdd) && !keyboard.just_pressed(KeyCode::Equal) {
+        return;
+    }
+
+    // Continue the chain in the direction of its last link.
+    let last_positions: Vec<Vec3> = chain
+        .links
+        .iter()
+        .rev()
+        .take(2)
+        .filter_map(|link| links.get(*link).ok())
+        .map(|link| link.position)
+        .collect();
+    let position = match last_positions[..] {
+        [last, before] => last + (last - before).normalize_or(Vec3::NEG_Y) * LINK_LENGTH,
+        [last] => last - Vec3::Y * LINK_LENGTH,
+        _ => return,
+    };
+    spawn_link(&mut commands, &mut chain, position);
+}
+
+fn simulate_chain(
+    time: Res<Time>,
+    anchor: Res<Anchor>,
+    chain: Res<Chain>,
+    mut links: Query<&mut ChainLink>,
+) {
+    // A long frame would make the chain explode.
+    let dt = time.delta_secs().min(1.0 / 30.0);
+
+    for mut link in links.iter_mut() {
+        let velocity = (link.position - link.prev_position) * DAMPING;
+        link.prev_position = link.position;
+        link.position += velocity + GRAVITY * dt * dt;
+    }
+
+    let Ok(mut first) = links.get_mut(chain.links[0]) else {
+        return;
+    };
+    first.position = anchor.0;
+
+    for _ in 0..CONSTRAINT_ITERATIONS {
+        for pair in chain.links.windows(2) {
+            let Ok([mut upper, mut lower]) = links.get_many_mut([pair[0], pair[1]]) else {
+                continue;
+            };
+
+            let delta = lower.position - upper.position;
+            let distance = delta.length();
+            if distance == 0.0 {
+                continue;
+            }
+
+            // The anchor doesn't move, so the link below it takes the whole correction.
+            let correction = delta * (distance - LINK_LENGTH) / distance;
+            if pair[0] == chain.links[0] {
+                lower.position -= correction;
+            } else {
+                upper.position += correction / 2.0;
+                lower.position -= correction / 2.0;
+            }
+        }
+    }
+}
+
+fn update_link_transforms(
+    chain: Res<Chain>,
+    links: Query<&ChainLink>,
+    mut transforms: Query<&mut Transform, With<ChainLink>>,
+) {
+    let mut previous = None;
+    for entity in &chain.links {
+        let Ok(link) = links.get(*entity) else {
+            continue;
+        };
+        let Ok(mut transform) = transforms.get_mut(*entity) else {
+            continue;
+        };
+
+        // Each capsule spans from the previous link to this one, and the anchor's hangs down.
+        let start = previous.unwrap_or(link.position + Vec3::Y * LINK_LENGTH);
+        let direction = (link.position - start).normalize_or(Vec3::NEG_Y);
+        transform.translation = (start + link.position) / 2.0;
+        transform.rotation = Quat::from_rotation_arc(Vec3::NEG_Y, direction);
+        previous = Some(link.position);
+    }
+}
@@ -0,0 +1,218 @@
+//! Unlocks achievements from a JSON file when their conditions are met, with a toast for each,
+//! e.g.
+//!
+//! ```json
+//! [
+//!     {
+//!         "id": "first_goal",
+//!         "name": "Finish Line",
+//!         "description": "Reach a goal.",
+//!         "condition": { "GoalsReached": 1 }
+//!     },
+//!     {
+//!         "id": "clean_run",
+//!         "name": "Steady Hands",
+//!         "description": "Reach a goal without falling.",
+//!         "condition": "ZeroFalls"
+//!     }
+//! ]
+//! ```
+//!
+//! A goal is reached with a [`GoalReached`] event, when a ball enters an unlocked goal.
+//! [`AchievementCondition::TimerUnder`] and [`AchievementCondition::ZeroFalls`] are about the goal
+//! reached last, and the combo is the goals reached in a row without falling.
+//!
+//! The IDs of the unlocked achievements are kept in the [`SaveState`], so they're saved by the
+//! [`SaveGamePlugin`](crate::plugins::save_game_plugin::SaveGamePlugin).
+
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::plugins::{
+    goal_plugin::{GoalPlugin, GoalReached},
+    kill_volume_plugin::BallFell,
+    level_manifest_plugin::UnloadLevel,
+    lives_plugin::PlayTime,
+    notification_plugin::ShowNotification,
+    save_game_plugin::{SaveEvent, SaveState},
+};
+
+pub struct AchievementPlugin {
+    pub path: PathBuf,
+}
+
+impl Default for AchievementPlugin {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("assets/achievements.json"),
+        }
+    }
+}
+
+impl Plugin for AchievementPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<GoalPlugin>() {
+            app.add_plugins(GoalPlugin::default());
+        }
+        app.insert_resource(Achievements(load_achievements(&self.path)))
+            .init_resource::<AchievementStats>()
+            .init_resource::<SaveState>()
+            .add_event::<AchievementUnlockedEvent>()
+            .add_event::<ShowNotification>()
+            .add_event::<SaveEvent>()
+            .add_event::<BallFell>()
+            .add_event::<UnloadLevel>()
+            .add_systems(
+                Update,
+                (track_achievement_stats, check_achievements, notify_unlocks).chain(),
+            );
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Achievement {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub condition: AchievementCondition,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum AchievementCondition {
+    /// Goals reached in total.
+    GoalsReached(u32),
+    /// The last goal was reached in less than these seconds.
+    TimerUnder(f32),
+    /// The last goal was reached without falling in the level.
+    ZeroFalls,
+    /// Goals reached in a row without falling.
+    ComboOf(u32),
+}
+
+/// All the achievements, in the order of the file.
+#[derive(Resource)]
+pub struct Achievements(pub Vec<Achievement>);
+
+#[derive(Resource, Default)]
+pub struct AchievementStats {
+    pub goals_reached: u32,
+    /// The falls since the level started.
+    pub falls: u32,
+    pub combo: u32,
+    pub last_goal: Option<GoalRun>,
+}
+
+/// How the last goal was reached.
+#[derive(Clone, Copy, Debug)]
+pub struct GoalRun {
+    /// The play time, if it's counted.
+    pub time: Option<f32>,
+    pub falls: u32,
+}
+
+impl AchievementCondition {
+    pub fn is_met(&self, stats: &AchievementStats) -> bool {
+        match *self {
+            Self::GoalsReached(goals) => stats.goals_reached >= goals,
+            Self::TimerUnder(seconds) => stats
+                .last_goal
+                .and_then(|run| run.time)
+                .is_some_and(|time| time < seconds),
+            Self::ZeroFalls => stats.last_goal.is_some_and(|run| run.falls == 0),
+            Self::ComboOf(combo) => stats.combo >= combo,
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct AchievementUnlockedEvent {
+    pub id: String,
+    pub name: String,
+}
+
+fn load_achievements(path: &Path) -> Vec<Achievement> {
+    let json = match std::fs::read_to_string(path) {
+        Ok(json) => json,
+        Err(err) => {
+            warn!(
+                "Failed to read the achievements from {}: {err}",
+                path.display()
+            );
+            return Vec::new();
+        }
+    };
+    serde_json::from_str(&json).unwrap_or_else(|err| {
+        error!(
+            "Failed to parse the achievements in {}: {err}",
+            path.display()
+        );
+        Vec::new()
+    })
+}
+
+fn track_achievement_stats(
+    mut goal_reached: EventReader<GoalReached>,
+    mut ball_fell: EventReader<BallFell>,
+    mut unload: EventReader<UnloadLevel>,
+    mut stats: ResMut<AchievementStats>,
+    play_time: Option<Res<PlayTime>>,
+) {
+    if unload.read().count() > 0 {
+        stats.falls = 0;
+    }
+
+    let falls = ball_fell.read().count() as u32;
+    if falls > 0 {
+        stats.falls += falls;
+        stats.combo = 0;
+    }
+
+    for _ in goal_reached.read() {
+        stats.goals_reached += 1;
+        stats.combo += 1;
+        stats.last_goal = Some(GoalRun {
+            time: play_time.as_ref().map(|play_time| play_time.0),
+            falls: stats.falls,
+        });
+    }
+}
+
+fn check_achievements(
+    achievements: Res<Achievements>,
+    stats: Res<AchievementStats>,
+    mut save_state: ResMut<SaveState>,
+    mut unlocked: EventWriter<AchievementUnlockedEvent>,
+    mut save: EventWriter<SaveEvent>,
+) {
+    for achievement in achievements.0.iter() {
+        if save_state.unlocked_achievements.contains(&achievement.id)
+            || !achievement.condition.is_met(&stats)
+        {
+            continue;
+        }
+
+        save_state
+            .unlocked_achievements
+            .insert(achievement.id.clone());
+        unlocked.write(AchievementUnlockedEvent {
+            id: achievement.id.clone(),
+            name: achievement.name.clone(),
+        });
+        save.write(SaveEvent);
+    }
+}
+
+fn notify_unlocks(
+    mut unlocked: EventReader<AchievementUnlockedEvent>,
+    mut notifications: EventWriter<ShowNotification>,
+) {
+    for event in unlocked.read() {
+        info!("Unlocked achievement `{}`.", event.id);
+        notifications.write(ShowNotification(format!(
+            "Achievement unlocked: {}",
+            event.name
+        )));
+    }
+}
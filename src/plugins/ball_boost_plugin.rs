@@ -0,0 +1,288 @@
+//! Lets the player boost the balls with a [`BallBoost`] by pressing [`BallBoostConfig::key`] while
+//! they touch something solid. The boost is an impulse of [`BallBoost::force`] toward where the
+//! camera looks, flattened onto the ground, and can't be used again until [`BallBoost::cooldown`]
+//! seconds have passed.
+//!
+//! A bar in the bottom left corner fills up as the cooldown runs out, if there's a single ball
+//! with a [`BallBoost`].
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::game_state_plugin::playing;
+
+const BAR_WIDTH: f32 = 160.0;
+
+#[derive(Default)]
+pub struct BallBoostPlugin {
+    pub config: BallBoostConfig,
+}
+
+impl Plugin for BallBoostPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone())
+            .add_systems(Startup, spawn_boost_bar)
+            .add_systems(
+                Update,
+                (
+                    boost_balls.run_if(playing),
+                    tick_boost_cooldown,
+                    update_boost_bar,
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct BallBoostConfig {
+    pub key: KeyCode,
+}
+
+impl Default for BallBoostConfig {
+    fn default() -> Self {
+        Self {
+            key: KeyCode::Space,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct BallBoost {
+    /// The impulse of a boost.
+    pub force: f32,
+    /// The seconds between boosts.
+    pub cooldown: f32,
+    /// The seconds left until the next boost, which is available at zero.
+    pub remaining_cooldown: f32,
+}
+
+impl BallBoost {
+    pub fn new(force: f32, cooldown: f32) -> Self {
+        Self {
+            force,
+            cooldown,
+            remaining_cooldown: 0.0,
+        }
+    }
+}
+
+impl Default for BallBoost {
+    fn default() -> Self {
+        Self::new(5.0, 2.0)
+    }
+}
+
+/// The fill of the cooldown bar.
+#[derive(Component)]
+struct BoostBar;
+
+fn boost_balls(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    config: Res<BallBoostConfig>,
+    rapier_context: ReadRapierContext,
+    camera: Option<Single<&GlobalTransform, With<Camera3d>>>,
+    mut balls: Query<(Entity, &mut BallBoost, Option<&mut ExternalImpulse>)>,
+) {
+    if !keyboard.just_pressed(config.key) {
+        return;
+    }
+    let Ok(context) = rapier_context.single() else {
+        return;
+    };
+    // Looking straight down has no forward to boost along.
+    let Some(direction) = camera.and_then(|camera| camera.forward().with_y(0.0).try_normalize())
+    else {
+        return;
+    };
+
+    for (ball, mut boost, external_impulse) in balls.iter_mut() {
+        // Sensors only make intersection pairs, so any active contact is with something solid.
+        let grounded = context
+            .contact_pairs_with(ball)
+            .any(|pair| pair.has_any_active_contact());
+        if boost.remaining_cooldown > 0.0 || !grounded {
+            continue;
+        }
+
+        let impulse = direction * boost.force;
+        match external_impulse {
+            Some(mut external_impulse) => external_impulse.impulse += impulse,
+            None => {
+                commands.entity(ball).insert(ExternalImpulse {
+                    impulse,
+                    ..default()
+                });
+            }
+        }
+        boost.remaining_cooldown = boost.cooldown;
+    }
+}
+
+fn tick_boost_cooldown(time: Res<Time>, mut boosts: Query<&mut BallBoost>) {
+    for mut boost in boosts.iter_mut() {
+        if boost.remaining_cooldown > 0.0 {
+            boost.remaining_cooldown = (boost.remaining_cooldown - time.delta_secs()).max(0.0);
+        }
+    }
+}
+
+fn spawn_boost_bar(mut commands: Commands) {
+    commands
+        .spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(12.0),
+                left: Val::Px(12.0),
+                width: Val::Px(BAR_WIDTH),
+                height: Val::Px(10.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.15, 0.15, 0.2)),
+            Pickable::IGNORE,
+        ))
+        .with_child((
+            BoostBar,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(1.0, 0.6, 0.2)),
+        ));
+}
+
+fn update_boost_bar(boost: Single<&BallBoost>, mut bar: Single<&mut Node, With<BoostBar>>) {
+    let ready = if boost.cooldown > 0.0 {
+        1.0 - boost.remaining_cooldown / boost.cooldown
+    } else {
+        1.0
+    };
+    bar.width = Val::Percent(ready * 100.0);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{scene::ScenePlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+
+    /// Spawns a ball at the height, a floor below it and a camera looking along `-Z`.
+    fn app(height: f32) -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin::default(),
+            ScenePlugin,
+            RapierPhysicsPlugin::<NoUserData>::default(),
+            BallBoostPlugin::default(),
+        ))
+        .init_asset::<Mesh>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            1.0 / 60.0,
+        )));
+
+        app.world_mut().spawn((
+            Transform::from_xyz(0.0, -0.1, 0.0),
+            RigidBody::Fixed,
+            Collider::cuboid(5.0, 0.1, 5.0),
+        ));
+        app.world_mut().spawn((
+            Camera3d::default(),
+            Transform::from_xyz(0.0, 5.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ));
+        let ball = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(0.0, height, 0.0),
+                RigidBody::Dynamic,
+                Collider::ball(0.5),
+                Velocity::zero(),
+                BallBoost::default(),
+            ))
+            .id();
+        // Lets the ball settle on the floor.
+        for _ in 0..10 {
+            app.update();
+        }
+        (app, ball)
+    }
+
+    fn boost(app: &mut App) {
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::Space);
+        app.update();
+        let mut keyboard = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keyboard.release(KeyCode::Space);
+        keyboard.clear();
+        app.update();
+    }
+
+    fn velocity(app: &App, ball: Entity) -> Vec3 {
+        app.world().get::<Velocity>(ball).unwrap().linvel
+    }
+
+    #[test]
+    fn boosts_a_grounded_ball_where_the_camera_looks() {
+        let (mut app, ball) = app(0.5);
+
+        boost(&mut app);
+        let boosted = velocity(&app, ball);
+        assert!(boosted.z < -1.0, "{boosted}");
+        assert!(boosted.x.abs() < 1e-3, "{boosted}");
+        assert!(
+            app.world()
+                .get::<BallBoost>(ball)
+                .unwrap()
+                .remaining_cooldown
+                > 0.0
+        );
+
+        // It's still cooling down.
+        boost(&mut app);
+        assert!(velocity(&app, ball).z >= boosted.z - 1e-3);
+    }
+
+    #[test]
+    fn doesnt_boost_in_the_air() {
+        let (mut app, ball) = app(20.0);
+
+        boost(&mut app);
+        assert!(velocity(&app, ball).z.abs() < 1e-3);
+        assert_eq!(
+            app.world()
+                .get::<BallBoost>(ball)
+                .unwrap()
+                .remaining_cooldown,
+            0.0
+        );
+    }
+
+    #[test]
+    fn boosts_again_after_the_cooldown() {
+        let (mut app, ball) = app(0.5);
+        app.world_mut().get_mut::<BallBoost>(ball).unwrap().cooldown = 0.1;
+
+        boost(&mut app);
+        let boosted = velocity(&app, ball);
+        for _ in 0..10 {
+            app.update();
+        }
+        assert_eq!(
+            app.world()
+                .get::<BallBoost>(ball)
+                .unwrap()
+                .remaining_cooldown,
+            0.0
+        );
+
+        boost(&mut app);
+        assert!(velocity(&app, ball).z < boosted.z - 1.0);
+    }
+}
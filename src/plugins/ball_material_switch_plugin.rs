@@ -0,0 +1,134 @@
+//! Changes the material of a [`Ball`] while it's in a [`SurfaceZone`], e.g. to make it look on
+//! fire, frozen or ghostly.
+//! glTF meshes named `surface_*` become zones with the mesh's own material, and the mesh is
+//! hidden, with a sensor built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline. Zones
+//! can also be spawned with a [`SurfaceZone`], a collider and the
+//! [`sensor_components`](crate::plugins::mesh_physics_plugin::sensor_components).
+//!
+//! The ball's material is kept in an [`OriginalMaterial`] and put back when the ball leaves the
+//! last zone it's in. In overlapping zones, the one entered last wins.
+
+use bevy::{prelude::*, scene::SceneInstanceReady};
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    mesh_physics_plugin::{ObjectCollider, SensorTriggered, register_object_collider},
+};
+
+const PREFIX: &str = "surface_";
+
+pub struct BallMaterialSwitchPlugin;
+
+impl Plugin for BallMaterialSwitchPlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(app, ObjectCollider::sensor(PREFIX));
+        app.add_systems(
+            Update,
+            (track_surface_zone_contacts, switch_ball_materials).chain(),
+        )
+        .add_observer(insert_surface_zones);
+    }
+}
+
+/// A sensor that gives the balls inside it its material.
+#[derive(Component)]
+pub struct SurfaceZone {
+    pub zone_material: Handle<StandardMaterial>,
+}
+
+/// The material of a ball from before it entered a [`SurfaceZone`].
+#[derive(Component)]
+pub struct OriginalMaterial(pub Handle<StandardMaterial>);
+
+/// The zones a ball is in, in the order they were entered.
+#[derive(Component, Default)]
+struct InSurfaceZones(Vec<Entity>);
+
+#[allow(clippy::type_complexity)]
+fn insert_surface_zones(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf, Option<&MeshMaterial3d<StandardMaterial>>), With<Mesh3d>>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of, material)) = query.get(entity) else {
+            continue;
+        };
+        if !name.starts_with(PREFIX) {
+            continue;
+        }
+        let Some(material) = material else {
+            warn!("`{name}` has no material to give the ball, it won't be a surface zone.");
+            continue;
+        };
+
+        commands.entity(child_of.parent()).insert(SurfaceZone {
+            zone_material: material.0.clone(),
+        });
+    }
+}
+
+fn track_surface_zone_contacts(
+    mut commands: Commands,
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    zones: Query<(), With<SurfaceZone>>,
+    mut balls: Query<Option<&mut InSurfaceZones>, With<Ball>>,
+) {
+    for event in sensor_triggered.read() {
+        let zone = event.sensor;
+        if !zones.contains(zone) {
+            continue;
+        }
+        let Ok(in_zones) = balls.get_mut(event.other) else {
+            continue;
+        };
+
+        match (in_zones, event.started) {
+            (Some(mut in_zones), true) => in_zones.0.push(zone),
+            (Some(mut in_zones), false) => in_zones.0.retain(|&other| other != zone),
+            (None, true) => {
+                commands
+                    .entity(event.other)
+                    .insert(InSurfaceZones(vec![zone]));
+            }
+            (None, false) => {}
+        }
+    }
+}
+
+fn switch_ball_materials(
+    mut commands: Commands,
+    zones: Query<&SurfaceZone>,
+    mut balls: Query<(
+        Entity,
+        &mut InSurfaceZones,
+        &mut MeshMaterial3d<StandardMaterial>,
+        Option<&OriginalMaterial>,
+    )>,
+) {
+    for (ball, mut in_zones, mut material, original) in balls.iter_mut() {
+        // Zones that were despawned don't send a stop event.
+        in_zones.0.retain(|&zone| zones.contains(zone));
+
+        match in_zones.0.last().and_then(|&zone| zones.get(zone).ok()) {
+            Some(zone) => {
+                if original.is_none() {
+                    commands
+                        .entity(ball)
+                        .insert(OriginalMaterial(material.0.clone()));
+                }
+                if material.0 != zone.zone_material {
+                    material.0 = zone.zone_material.clone();
+                }
+            }
+            None => {
+                if let Some(original) = original {
+                    material.0 = original.0.clone();
+                    commands.entity(ball).remove::<OriginalMaterial>();
+                }
+            }
+        }
+    }
+}
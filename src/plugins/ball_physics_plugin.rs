@@ -0,0 +1,205 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{mesh_physics_plugin::MeshPhysicsSet, physics_layer_plugin::ball_groups};
+
+#[derive(Default)]
+pub struct BallPhysicsPlugin {
+    pub config: BallPhysicsConfig,
+    pub physics: PhysicsConfig,
+}
+
+impl Plugin for BallPhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone())
+            .insert_resource(self.physics.clone())
+            // After the colliders of the scenes, so the dynamic bodies they insert get their
+            // sleep thresholds in the same frame.
+            .configure_sets(Update, BallPhysicsSet.after(MeshPhysicsSet))
+            .add_systems(
+                Update,
+                (
+                    insert_ball_physics,
+                    insert_sleep_thresholds,
+                    wake_bodies_near_balls,
+                )
+                    .in_set(BallPhysicsSet),
+            );
+    }
+}
+
+/// The systems of the [`BallPhysicsPlugin`].
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BallPhysicsSet;
+
+#[derive(Resource, Clone)]
+pub struct BallPhysicsConfig {
+    /// Continuous collision detection, so a fast ball doesn't tunnel through thin colliders.
+    /// It can be turned off to save performance.
+    pub ccd: bool,
+}
+
+impl Default for BallPhysicsConfig {
+    fn default() -> Self {
+        Self { ccd: true }
+    }
+}
+
+/// How the dynamic bodies other than the balls fall asleep once they come to rest, so loose
+/// objects lying around don't cost anything to simulate.
+#[derive(Resource, Clone)]
+pub struct PhysicsConfig {
+    /// The linear speed below which a body can sleep, relative to its size.
+    pub sleep_linear_threshold: f32,
+    /// The angular speed below which a body can sleep, in radians per second.
+    pub sleep_angular_threshold: f32,
+    /// How close a ball gets to a sleeping body to wake it up.
+    pub wake_radius: f32,
+}
+
+impl Default for PhysicsConfig {
+    /// Rapier's thresholds.
+    fn default() -> Self {
+        Self {
+            sleep_linear_threshold: 0.4,
+            sleep_angular_threshold: 0.5,
+            wake_radius: 3.0,
+        }
+    }
+}
+
+/// A ball rolled by the player. Its physics components are inserted by [`BallPhysicsPlugin`].
+#[derive(Component)]
+pub struct Ball {
+    pub radius: f32,
+}
+
+fn insert_ball_physics(
+    mut commands: Commands,
+    config: Res<BallPhysicsConfig>,
+    query: Query<(Entity, &Ball), Without<Collider>>,
+) {
+    for (entity, ball) in query.iter() {
+        let mut entity = commands.entity(entity);
+        entity.insert((
+            RigidBody::Dynamic,
+            Collider::ball(ball.radius),
+            ExternalForce::default(),
+            Damping {
+                linear_damping: 0.5,
+                angular_damping: 1.0,
+            },
+            Velocity::default(),
+            ActiveEvents::COLLISION_EVENTS,
+            ball_groups(),
+        ));
+        if config.ccd {
+            entity.insert(Ccd::enabled());
+        }
+    }
+}
+
+/// Sets the sleep thresholds of the dynamic bodies that don't have them, or of all of them when
+/// the [`PhysicsConfig`] changes. The balls never sleep, so they always respond to the player.
+fn insert_sleep_thresholds(
+    mut commands: Commands,
+    physics: Res<PhysicsConfig>,
+    query: Query<(Entity, &RigidBody, Has<Ball>, Has<Sleeping>)>,
+) {
+    for (entity, body, is_ball, has_sleeping) in query.iter() {
+        if *body != RigidBody::Dynamic || (has_sleeping && !physics.is_changed()) {
+            continue;
+        }
+
+        let sleeping = if is_ball {
+            Sleeping::disabled()
+        } else {
+            Sleeping {
+                normalized_linear_threshold: physics.sleep_linear_threshold,
+                angular_threshold: physics.sleep_angular_threshold,
+                sleeping: false,
+            }
+        };
+        commands.entity(entity).insert(sleeping);
+    }
+}
+
+fn wake_bodies_near_balls(
+    physics: Res<PhysicsConfig>,
+    balls: Query<&GlobalTransform, With<Ball>>,
+    mut bodies: Query<(&GlobalTransform, &mut Sleeping), Without<Ball>>,
+) {
+    let wake_radius_squared = physics.wake_radius * physics.wake_radius;
+    for (transform, mut sleeping) in bodies.iter_mut() {
+        if !sleeping.sleeping {
+            continue;
+        }
+
+        let position = transform.translation();
+        if balls
+            .iter()
+            .any(|ball| ball.translation().distance_squared(position) < wake_radius_squared)
+        {
+            sleeping.sleeping = false;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{scene::ScenePlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+
+    /// Shoots a ball at a wall thinner than the distance it travels in a step, and returns how
+    /// far past the wall it ends up.
+    fn shoot_ball_at_thin_wall(ccd: bool) -> f32 {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin::default(),
+            ScenePlugin,
+            RapierPhysicsPlugin::<NoUserData>::default(),
+            BallPhysicsPlugin {
+                config: BallPhysicsConfig { ccd },
+                ..default()
+            },
+        ))
+        .init_asset::<Mesh>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            1.0 / 60.0,
+        )));
+
+        app.world_mut().spawn((
+            Transform::default(),
+            RigidBody::Fixed,
+            Collider::cuboid(5.0, 5.0, 0.01),
+        ));
+        let ball = app
+            .world_mut()
+            .spawn((Transform::from_xyz(0.0, 0.0, -2.0), Ball { radius: 0.1 }))
+            .id();
+
+        // The velocity is set once the ball has its physics.
+        app.update();
+        app.world_mut().get_mut::<Velocity>(ball).unwrap().linvel = Vec3::Z * 300.0;
+        for _ in 0..10 {
+            app.update();
+        }
+
+        app.world().get::<Transform>(ball).unwrap().translation.z
+    }
+
+    #[test]
+    fn ccd_stops_a_fast_ball_at_a_thin_wall() {
+        assert!(shoot_ball_at_thin_wall(true) < 0.0);
+    }
+
+    #[test]
+    fn a_fast_ball_tunnels_through_a_thin_wall_without_ccd() {
+        assert!(shoot_ball_at_thin_wall(false) > 0.0);
+    }
+}
@@ -0,0 +1,191 @@
+//! Plays a rolling sound while a [`Ball`] touches something solid, e.g. the floor.
+//!
+//! Every ball gets one looping [`AudioPlayer`] on a child when it's spawned, muted until the ball
+//! touches a collider and muted again once it touches nothing. The player stays for the ball's
+//! whole life, so skimming over surfaces only mutes and unmutes it instead of spawning and
+//! despawning players, which makes the sound pop.
+//!
+//! The touched colliders are tracked from the ball's rapier [`CollisionEvent`]s, which the
+//! [`BallPhysicsPlugin`](crate::plugins::ball_physics_plugin::BallPhysicsPlugin) turns on.
+//! Sensors don't count.
+
+use bevy::{platform::collections::HashSet, prelude::*};
+use bevy_rapier3d::{prelude::*, rapier::geometry::CollisionEventFlags};
+
+use crate::plugins::ball_physics_plugin::Ball;
+
+#[derive(Default)]
+pub struct BallSoundPlugin {
+    pub config: BallSoundConfig,
+}
+
+impl Plugin for BallSoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone()).add_systems(
+            Update,
+            (
+                spawn_rolling_sounds,
+                track_ball_contacts,
+                mute_rolling_sounds,
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct BallSoundConfig {
+    /// The asset path of the looping rolling sound.
+    pub rolling_sound: String,
+}
+
+impl Default for BallSoundConfig {
+    fn default() -> Self {
+        Self {
+            rolling_sound: "sounds/rolling.ogg".to_string(),
+        }
+    }
+}
+
+/// The rolling sound of a ball, on the ball.
+#[derive(Component, Debug)]
+pub struct RollingSound {
+    /// The entity of the looping [`AudioPlayer`].
+    pub player: Entity,
+    /// The colliders the ball touches.
+    pub touching: HashSet<Entity>,
+}
+
+fn spawn_rolling_sounds(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<BallSoundConfig>,
+    balls: Query<Entity, (With<Ball>, Without<RollingSound>)>,
+) {
+    for ball in balls.iter() {
+        let player = commands
+            .spawn((
+                AudioPlayer::new(asset_server.load(config.rolling_sound.clone())),
+                PlaybackSettings::LOOP.muted(),
+                ChildOf(ball),
+            ))
+            .id();
+        commands.entity(ball).insert(RollingSound {
+            player,
+            touching: HashSet::new(),
+        });
+    }
+}
+
+fn track_ball_contacts(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut sounds: Query<&mut RollingSound>,
+) {
+    for event in collision_events.read() {
+        let (entity1, entity2, started) = match *event {
+            CollisionEvent::Started(entity1, entity2, flags) => {
+                if flags.contains(CollisionEventFlags::SENSOR) {
+                    continue;
+                }
+                (entity1, entity2, true)
+            }
+            CollisionEvent::Stopped(entity1, entity2, _) => (entity1, entity2, false),
+        };
+
+        for (ball, other) in [(entity1, entity2), (entity2, entity1)] {
+            let Ok(mut sound) = sounds.get_mut(ball) else {
+                continue;
+            };
+            if started {
+                sound.touching.insert(other);
+            } else {
+                sound.touching.remove(&other);
+            }
+        }
+    }
+}
+
+fn mute_rolling_sounds(sounds: Query<&RollingSound>, mut sinks: Query<&mut AudioSink>) {
+    for sound in sounds.iter() {
+        // The sink is only there once the sound is loaded.
+        let Ok(mut sink) = sinks.get_mut(sound.player) else {
+            continue;
+        };
+        let rolling = !sound.touching.is_empty();
+        if rolling && sink.is_muted() {
+            sink.unmute();
+        } else if !rolling && !sink.is_muted() {
+            sink.mute();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{scene::ScenePlugin, time::TimeUpdateStrategy};
+
+    use super::*;
+    use crate::plugins::ball_physics_plugin::BallPhysicsPlugin;
+
+    #[test]
+    fn keeps_one_player_while_the_ball_lands_and_jumps() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin::default(),
+            ScenePlugin,
+            RapierPhysicsPlugin::<NoUserData>::default(),
+            BallPhysicsPlugin::default(),
+            BallSoundPlugin::default(),
+        ))
+        .init_asset::<Mesh>()
+        .init_asset::<AudioSource>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            1.0 / 60.0,
+        )));
+
+        let floor = app
+            .world_mut()
+            .spawn((
+                Transform::from_xyz(0.0, -0.1, 0.0),
+                RigidBody::Fixed,
+                Collider::cuboid(5.0, 0.1, 5.0),
+            ))
+            .id();
+        let ball = app
+            .world_mut()
+            .spawn((Transform::from_xyz(0.0, 1.0, 0.0), Ball { radius: 0.5 }))
+            .id();
+        let touching = |app: &App| {
+            app.world()
+                .get::<RollingSound>(ball)
+                .unwrap()
+                .touching
+                .clone()
+        };
+        let players = |app: &mut App| {
+            app.world_mut()
+                .query::<(&AudioPlayer, &ChildOf)>()
+                .iter(app.world())
+                .map(|(_, child_of)| child_of.parent())
+                .collect::<Vec<_>>()
+        };
+
+        app.update();
+        assert!(touching(&app).is_empty());
+        for _ in 0..60 {
+            app.update();
+        }
+        assert_eq!(touching(&app), HashSet::from([floor]));
+
+        app.world_mut().get_mut::<Velocity>(ball).unwrap().linvel = Vec3::Y * 10.0;
+        for _ in 0..10 {
+            app.update();
+        }
+        assert!(touching(&app).is_empty());
+        assert_eq!(players(&mut app), [ball]);
+    }
+}
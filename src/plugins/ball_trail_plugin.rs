@@ -0,0 +1,186 @@
+//! Draws an afterimage trail behind any entity with a [`BallTrail`].
+//! The trail is made of spheres placed at the entity's past positions, getting smaller and more
+//! transparent towards the end, so a fast ball leaves a long trail and a slow one a short trail.
+//! The position of the entity is recorded every frame, and the spheres are placed at evenly
+//! spaced times in the past, interpolated between the recorded positions, so the spacing doesn't
+//! depend on the frame rate.
+
+use std::collections::VecDeque;
+
+use bevy::{prelude::*, render::mesh::MeshAabb};
+
+pub struct BallTrailPlugin;
+
+impl Plugin for BallTrailPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (spawn_trail_segments, update_trails, despawn_orphan_segments).chain(),
+        );
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct BallTrail {
+    pub segment_count: usize,
+    /// Seconds it takes a segment to reach the end of the trail.
+    pub fade_time: f32,
+    pub color: Color,
+}
+
+/// The past positions of an entity with a [`BallTrail`] and the elapsed seconds they were
+/// recorded at, newest first.
+#[derive(Component)]
+struct TrailHistory {
+    positions: VecDeque<(f32, Vec3)>,
+    radius: f32,
+    segments: Vec<Entity>,
+}
+
+#[derive(Component)]
+struct TrailSegment {
+    owner: Entity,
+}
+
+fn spawn_trail_segments(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    query: Query<(Entity, &BallTrail, &GlobalTransform, Option<&Mesh3d>), Added<BallTrail>>,
+) {
+    for (entity, trail, transform, mesh3d) in query.iter() {
+        // Use the size of the entity's own mesh so the trail starts as big as the ball.
+        let radius = mesh3d
+            .and_then(|mesh3d| meshes.get(&mesh3d.0))
+            .and_then(|mesh| mesh.compute_aabb())
+            .map_or(0.5, |aabb| aabb.half_extents.max_element())
+            * transform.scale().max_element();
+
+        let sphere = meshes.add(Sphere::new(1.0));
+        let segments = (0..trail.segment_count)
+            .map(|i| {
+                let remaining = 1.0 - i as f32 / trail.segment_count as f32;
+                let material = materials.add(StandardMaterial {
+                    base_color: trail
+                        .color
+                        .with_alpha(trail.color.alpha() * remaining * 0.5),
+                    alpha_mode: AlphaMode::Blend,
+                    unlit: true,
+                    ..default()
+                });
+
+                commands
+                    .spawn((
+                        TrailSegment { owner: entity },
+                        Mesh3d(sphere.clone()),
+                        MeshMaterial3d(material),
+                        Transform::from_scale(Vec3::splat(radius * remaining)),
+                        Visibility::Hidden,
+                    ))
+                    .id()
+            })
+            .collect();
+
+        commands.entity(entity).insert(TrailHistory {
+            positions: VecDeque::new(),
+            radius,
+            segments,
+        });
+    }
+}
+
+fn update_trails(
+    time: Res<Time>,
+    mut query: Query<(&BallTrail, &GlobalTransform, &mut TrailHistory)>,
+    mut segments: Query<(&mut Transform, &mut Visibility), With<TrailSegment>>,
+) {
+    let now = time.elapsed_secs();
+    for (trail, transform, mut history) in query.iter_mut() {
+        let current = transform.translation();
+        // While paused, the position of the same time is replaced instead of piling up.
+        if history
+            .positions
+            .front()
+            .is_some_and(|&(recorded, _)| recorded >= now)
+        {
+            history.positions.pop_front();
+        }
+        history.positions.push_front((now, current));
+        // Keeps one position older than the trail to interpolate its end from.
+        while history
+            .positions
+            .get(history.positions.len().saturating_sub(2))
+            .is_some_and(|&(recorded, _)| now - recorded > trail.fade_time)
+        {
+            history.positions.pop_back();
+        }
+
+        // Spaced so the whole trail spans `fade_time` seconds.
+        let interval = trail.fade_time / trail.segment_count.max(1) as f32;
+        for (i, segment) in history.segments.iter().enumerate() {
+            let Ok((mut segment_transform, mut visibility)) = segments.get_mut(*segment) else {
+                continue;
+            };
+
+            let age = (i + 1) as f32 * interval;
+            match position_at(&history.positions, now - age) {
+                // Hide the segments that would sit inside the ball.
+                Some(position) if position.distance(current) > history.radius => {
+                    segment_transform.translation = position;
+                    *visibility = Visibility::Visible;
+                }
+                _ => *visibility = Visibility::Hidden,
+            }
+        }
+    }
+}
+
+/// The position at the elapsed seconds, interpolated between the positions recorded around it,
+/// or `None` if it's older than all of them.
+fn position_at(positions: &VecDeque<(f32, Vec3)>, time: f32) -> Option<Vec3> {
+    let older = positions
+        .iter()
+        .position(|&(recorded, _)| recorded <= time)?;
+    let (older_time, older_position) = positions[older];
+    let Some(&(newer_time, newer_position)) = older.checked_sub(1).map(|newer| &positions[newer])
+    else {
+        return Some(older_position);
+    };
+
+    let t = (time - older_time) / (newer_time - older_time);
+    Some(older_position.lerp(newer_position, t))
+}
+
+fn despawn_orphan_segments(
+    mut commands: Commands,
+    segments: Query<(Entity, &TrailSegment)>,
+    owners: Query<(), With<BallTrail>>,
+) {
+    for (entity, segment) in segments.iter() {
+        if !owners.contains(segment.owner) {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interpolates_between_the_recorded_positions() {
+        let positions = VecDeque::from([
+            (2.0, Vec3::new(4.0, 0.0, 0.0)),
+            (1.0, Vec3::new(2.0, 0.0, 0.0)),
+            (0.0, Vec3::ZERO),
+        ]);
+
+        assert_eq!(position_at(&positions, 1.5), Some(Vec3::new(3.0, 0.0, 0.0)));
+        assert_eq!(
+            position_at(&positions, 0.25),
+            Some(Vec3::new(0.5, 0.0, 0.0))
+        );
+        assert_eq!(position_at(&positions, 3.0), Some(Vec3::new(4.0, 0.0, 0.0)));
+        assert_eq!(position_at(&positions, -1.0), None);
+    }
+}
@@ -0,0 +1,236 @@
+//! Turns glTF meshes named `boost_*` into pads that launch the ball, e.g. `boost_12.0_Pad` or
+//! `boost_up_8.0_Pad`.
+//! When a [`Ball`] enters a pad, it gets an impulse in the pad's forward direction, or its up
+//! direction if the name has an `up` token. The forward direction is the pad's local `-Z`, which
+//! is `+Y` in Blender.
+//!
+//! The impulse's strength is the number in the name, or the `boost_strength` number in the glTF
+//! extras of the mesh or its parent, or [`BoostPadConfig::strength`]. The pad flashes and plays
+//! an optional sound, and can't trigger again until its cooldown is over. Only entering the pad
+//! triggers it, so a ball resting on an upward pad isn't launched again and again. The pads'
+//! sensors are built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline.
+
+use bevy::{gltf::GltfExtras, prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    level_manifest_plugin::LevelEntity,
+    mesh_physics_plugin::{ObjectCollider, SensorTriggered, register_object_collider},
+};
+
+const PREFIX: &str = "boost_";
+
+/// The emissive color of a pad at the start of its flash.
+const FLASH_EMISSIVE: LinearRgba = LinearRgba::rgb(4.0, 3.0, 1.0);
+
+#[derive(Default)]
+pub struct BoostPadPlugin {
+    pub config: BoostPadConfig,
+}
+
+impl Plugin for BoostPadPlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                hide_mesh: false,
+                ..ObjectCollider::sensor(PREFIX)
+            },
+        );
+        app.insert_resource(self.config.clone())
+            .add_systems(Update, (boost_balls, flash_boost_pads).chain())
+            .add_observer(insert_boost_pads);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct BoostPadConfig {
+    /// The impulse of the pads that don't set their own strength.
+    pub strength: f32,
+    /// How long a pad flashes and can't trigger after launching the ball, in seconds.
+    pub cooldown: f32,
+    /// The asset path of the sound played when a pad launches the ball.
+    pub sound: Option<String>,
+}
+
+impl Default for BoostPadConfig {
+    fn default() -> Self {
+        Self {
+            strength: 10.0,
+            cooldown: 0.5,
+            sound: None,
+        }
+    }
+}
+
+/// A pad created from a `boost_` mesh, on the mesh's parent.
+#[derive(Component)]
+pub struct BoostPad {
+    pub strength: f32,
+    /// Whether it launches along its up instead of its forward.
+    pub up: bool,
+}
+
+/// The seconds left until a [`BoostPad`] can trigger again.
+#[derive(Component)]
+pub struct BoostCooldown(pub f32);
+
+/// The material of a pad, which is its own copy so only this pad flashes.
+#[derive(Component)]
+struct PadMaterial {
+    handle: Handle<StandardMaterial>,
+    emissive: LinearRgba,
+}
+
+/// Returns the strength and whether there's an `up` token, in a mesh name like
+/// `boost_up_8.0_Pad`.
+pub fn parse_boost_name(name: &str) -> Option<(Option<f32>, bool)> {
+    let rest = name.strip_prefix(PREFIX)?;
+    let mut strength = None;
+    let mut up = false;
+    for token in rest.split('_') {
+        if token == "up" {
+            up = true;
+        } else if let Ok(value) = token.parse() {
+            strength.get_or_insert(value);
+        }
+    }
+    Some((strength, up))
+}
+
+/// Parses the `boost_strength` from the extras JSON.
+fn parse_boost_strength(json: &str) -> Result<Option<f32>, String> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+    value
+        .get("boost_strength")
+        .map(|strength| {
+            strength
+                .as_f64()
+                .map(|strength| strength as f32)
+                .ok_or_else(|| format!("`boost_strength` should be a number, got {strength}"))
+        })
+        .transpose()
+}
+
+#[allow(clippy::type_complexity)]
+fn insert_boost_pads(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    config: Res<BoostPadConfig>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf, Option<&MeshMaterial3d<StandardMaterial>>), With<Mesh3d>>,
+    extras_query: Query<&GltfExtras>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of, material)) = query.get(entity) else {
+            continue;
+        };
+        let Some((name_strength, up)) = parse_boost_name(name) else {
+            continue;
+        };
+
+        let extras_strength = extras_query
+            .get(entity)
+            .or_else(|_| extras_query.get(child_of.parent()))
+            .map_or(Ok(None), |extras| parse_boost_strength(&extras.value))
+            .unwrap_or_else(|err| {
+                warn!("Skipping the extras of `{name}`: {err}");
+                None
+            });
+        let strength = name_strength.or(extras_strength).unwrap_or(config.strength);
+
+        commands
+            .entity(child_of.parent())
+            .insert(BoostPad { strength, up });
+        if let Some(own_material) = material
+            .and_then(|material| materials.get(&material.0))
+            .cloned()
+        {
+            let emissive = own_material.emissive;
+            let handle = materials.add(own_material);
+            commands
+                .entity(entity)
+                .insert(MeshMaterial3d(handle.clone()));
+            commands
+                .entity(child_of.parent())
+                .insert(PadMaterial { handle, emissive });
+        }
+    }
+}
+
+fn boost_balls(
+    mut commands: Commands,
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    config: Res<BoostPadConfig>,
+    asset_server: Res<AssetServer>,
+    pads: Query<(&BoostPad, &GlobalTransform), Without<BoostCooldown>>,
+    mut balls: Query<Option<&mut ExternalImpulse>, With<Ball>>,
+) {
+    for event in sensor_triggered.read() {
+        if !event.started {
+            continue;
+        }
+        let (pad_entity, ball) = (event.sensor, event.other);
+        let Ok((pad, pad_transform)) = pads.get(pad_entity) else {
+            continue;
+        };
+        let Ok(external_impulse) = balls.get_mut(ball) else {
+            continue;
+        };
+
+        let direction = if pad.up {
+            pad_transform.up()
+        } else {
+            pad_transform.forward()
+        };
+        let impulse = direction * pad.strength;
+        match external_impulse {
+            Some(mut external_impulse) => external_impulse.impulse += impulse,
+            None => {
+                commands.entity(ball).insert(ExternalImpulse {
+                    impulse,
+                    ..default()
+                });
+            }
+        }
+        commands
+            .entity(pad_entity)
+            .insert(BoostCooldown(config.cooldown));
+
+        if let Some(sound) = &config.sound {
+            commands.spawn((
+                LevelEntity,
+                AudioPlayer::new(asset_server.load(sound.clone())),
+                PlaybackSettings::DESPAWN,
+            ));
+        }
+    }
+}
+
+fn flash_boost_pads(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<BoostPadConfig>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut pads: Query<(Entity, &mut BoostCooldown, Option<&PadMaterial>)>,
+) {
+    for (entity, mut cooldown, pad_material) in pads.iter_mut() {
+        cooldown.0 -= time.delta_secs();
+        if cooldown.0 <= 0.0 {
+            commands.entity(entity).remove::<BoostCooldown>();
+        }
+        let Some(pad_material) = pad_material else {
+            continue;
+        };
+        let Some(material) = materials.get_mut(&pad_material.handle) else {
+            continue;
+        };
+
+        // Fades from the flash back to the pad's own emissive over the cooldown.
+        let flash = (cooldown.0 / config.cooldown).clamp(0.0, 1.0);
+        material.emissive = pad_material.emissive.mix(&FLASH_EMISSIVE, flash);
+    }
+}
@@ -0,0 +1,146 @@
+//! Turns glTF meshes named `bounce_<magnitude>_*` into pads that launch the ball, e.g.
+//! `bounce_5.0_Pad`.
+//! When a [`Ball`] touches a pad, it gets an impulse of the magnitude in the pad's local up
+//! direction, and the pad's mesh pulses to show it was triggered. The pad can't trigger again
+//! until the pulse is over. The pads' sensors are built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline.
+//! For pads the ball bounces off instead, see the
+//! [`TrampolinePlugin`](crate::plugins::trampoline_plugin::TrampolinePlugin).
+
+use bevy::{prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    mesh_physics_plugin::{ObjectCollider, SensorTriggered, register_object_collider},
+};
+
+const PREFIX: &str = "bounce_";
+
+/// How long a pad pulses and can't trigger after launching the ball, in seconds.
+const COOLDOWN: f32 = 0.4;
+/// How much bigger the pad gets at the peak of the pulse.
+const PULSE_SCALE: f32 = 0.3;
+
+pub struct BouncePadPlugin;
+
+impl Plugin for BouncePadPlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                hide_mesh: false,
+                ..ObjectCollider::sensor(PREFIX)
+            },
+        );
+        app.add_systems(Update, (ball_bounce_pad, pulse_bounce_pads).chain())
+            .add_observer(insert_bounce_pads);
+    }
+}
+
+/// A pad created from a `bounce_` mesh, on the mesh's parent.
+#[derive(Component)]
+pub struct BouncePad {
+    pub impulse_magnitude: f32,
+}
+
+/// The seconds left until a [`BouncePad`] can trigger again.
+#[derive(Component)]
+pub struct BounceCooldown(pub f32);
+
+/// The mesh of a pad, which is scaled by the pulse instead of the pad so the collider keeps
+/// its size.
+#[derive(Component)]
+struct PadMesh {
+    entity: Entity,
+    scale: Vec3,
+}
+
+/// Returns the impulse magnitude of a mesh name like `bounce_5.0_Pad`.
+pub fn parse_bounce_magnitude(name: &str) -> Option<f32> {
+    let rest = name.strip_prefix(PREFIX)?;
+    rest.split('_').next()?.parse().ok()
+}
+
+fn insert_bounce_pads(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf, Option<&Transform>), With<Mesh3d>>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of, transform)) = query.get(entity) else {
+            continue;
+        };
+        if !name.starts_with(PREFIX) {
+            continue;
+        }
+        let Some(impulse_magnitude) = parse_bounce_magnitude(name) else {
+            warn!("`{name}` should be named like `bounce_5.0_Pad`, it won't be a bounce pad.");
+            continue;
+        };
+
+        commands.entity(child_of.parent()).insert((
+            BouncePad { impulse_magnitude },
+            PadMesh {
+                entity,
+                scale: transform.map_or(Vec3::ONE, |transform| transform.scale),
+            },
+        ));
+    }
+}
+
+fn ball_bounce_pad(
+    mut commands: Commands,
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    pads: Query<(&BouncePad, &GlobalTransform), Without<BounceCooldown>>,
+    mut balls: Query<Option<&mut ExternalImpulse>, With<Ball>>,
+) {
+    for event in sensor_triggered.read() {
+        if !event.started {
+            continue;
+        }
+        let Ok((pad, pad_transform)) = pads.get(event.sensor) else {
+            continue;
+        };
+        let Ok(external_impulse) = balls.get_mut(event.other) else {
+            continue;
+        };
+
+        let impulse = pad_transform.up() * pad.impulse_magnitude;
+        match external_impulse {
+            Some(mut external_impulse) => external_impulse.impulse += impulse,
+            None => {
+                commands.entity(event.other).insert(ExternalImpulse {
+                    impulse,
+                    ..default()
+                });
+            }
+        }
+        commands
+            .entity(event.sensor)
+            .insert(BounceCooldown(COOLDOWN));
+    }
+}
+
+fn pulse_bounce_pads(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut pads: Query<(Entity, &PadMesh, &mut BounceCooldown)>,
+    mut transforms: Query<&mut Transform>,
+) {
+    for (entity, mesh, mut cooldown) in pads.iter_mut() {
+        cooldown.0 -= time.delta_secs();
+        if cooldown.0 <= 0.0 {
+            commands.entity(entity).remove::<BounceCooldown>();
+        }
+        let Ok(mut transform) = transforms.get_mut(mesh.entity) else {
+            continue;
+        };
+
+        // Grows and shrinks back over the cooldown.
+        let progress = (1.0 - cooldown.0 / COOLDOWN).min(1.0);
+        let pulse = (progress * std::f32::consts::PI).sin();
+        transform.scale = mesh.scale * (1.0 + pulse * PULSE_SCALE);
+    }
+}
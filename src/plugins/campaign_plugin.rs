@@ -0,0 +1,322 @@
+//! Plays the levels of a `.campaign.ron` file in order, e.g.
+//!
+//! ```ron
+//! (
+//!     levels: [
+//!         (name: "The Bridge", manifest: "levels/level1/level1.level.ron"),
+//!         (name: "Up and Down", manifest: "levels/level2/level2.level.ron"),
+//!     ],
+//! )
+//! ```
+//!
+//! The game starts in [`CampaignState::LevelSelect`], where a level is picked and loaded through
+//! the [`LevelManager`], see the
+//! [`LevelSelectPlugin`](crate::plugins::level_select_plugin::LevelSelectPlugin).
+//!
+//! When a [`Ball`] reaches an unlocked goal, see [`GoalPlugin`], the win screen is shown and
+//! physics stops. After [`CampaignPlugin::win_screen_duration`] seconds or a key press, the next
+//! manifest is loaded through the [`LevelManager`] after an [`UnloadLevel`] cleans up the current
+//! level, and the ball is moved to the new level's start once its scene is ready.
+//!
+//! After the last level, the campaign complete screen is shown, and `Enter` plays the campaign
+//! again from the first level.
+//!
+//! The [`LevelManifestPlugin`](crate::plugins::level_manifest_plugin::LevelManifestPlugin)
+//! shouldn't load an initial manifest, since the campaign loads the levels.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+    scene::SceneInstanceReady,
+};
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    goal_plugin::{GoalPlugin, GoalReached},
+    level_manifest_plugin::{LevelEntity, LevelManager, RonLoaderError, UnloadLevel},
+    lives_plugin::PlayTime,
+    point_plugin::CollectedPoints,
+};
+
+pub struct CampaignPlugin {
+    /// The asset path of the campaign.
+    pub campaign: String,
+    /// How long the win screen is shown before the next level, unless a key is pressed.
+    pub win_screen_duration: f32,
+}
+
+impl Default for CampaignPlugin {
+    fn default() -> Self {
+        Self {
+            campaign: "levels/campaign.campaign.ron".to_string(),
+            win_screen_duration: 4.0,
+        }
+    }
+}
+
+impl Plugin for CampaignPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<GoalPlugin>() {
+            app.add_plugins(GoalPlugin::default());
+        }
+        let campaign = self.campaign.clone();
+        app.init_asset::<Campaign>()
+            .register_asset_loader(CampaignLoader)
+            .insert_resource(WinScreenTimer(Timer::from_seconds(
+                self.win_screen_duration,
+                TimerMode::Once,
+            )))
+            .init_state::<CampaignState>()
+            .add_systems(
+                Startup,
+                move |mut commands: Commands, asset_server: Res<AssetServer>| {
+                    commands.insert_resource(CampaignHandle(asset_server.load(campaign.clone())));
+                },
+            )
+            .add_systems(
+                OnEnter(CampaignState::LevelSelect),
+                set_physics_active::<false>,
+            )
+            .add_systems(OnEnter(CampaignState::Playing), set_physics_active::<true>)
+            .add_systems(
+                OnEnter(CampaignState::Won),
+                (set_physics_active::<false>, spawn_win_screen),
+            )
+            .add_systems(OnEnter(CampaignState::Complete), spawn_complete_screen)
+            .add_systems(OnExit(CampaignState::Won), despawn_campaign_screens)
+            .add_systems(OnExit(CampaignState::Complete), despawn_campaign_screens)
+            .add_systems(
+                Update,
+                (
+                    win_level.run_if(in_state(CampaignState::Playing)),
+                    leave_win_screen.run_if(in_state(CampaignState::Won)),
+                    restart_campaign.run_if(in_state(CampaignState::Complete)),
+                ),
+            )
+            .add_observer(finish_loading);
+    }
+}
+
+/// The levels of a campaign, in order.
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct Campaign {
+    pub levels: Vec<CampaignLevel>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct CampaignLevel {
+    pub name: String,
+    /// The asset path of the level's manifest.
+    pub manifest: String,
+}
+
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CampaignState {
+    /// A level is being picked.
+    #[default]
+    LevelSelect,
+    Playing,
+    /// The win screen of a level is shown.
+    Won,
+    /// The next level is being loaded.
+    Loading,
+    /// The last level was won.
+    Complete,
+}
+
+#[derive(Resource)]
+pub struct CampaignHandle(pub Handle<Campaign>);
+
+#[derive(Resource)]
+struct WinScreenTimer(Timer);
+
+#[derive(Component)]
+struct CampaignScreen;
+
+#[derive(Default)]
+struct CampaignLoader;
+
+impl AssetLoader for CampaignLoader {
+    type Asset = Campaign;
+    type Settings = ();
+    type Error = RonLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["campaign.ron"]
+    }
+}
+
+pub(crate) fn set_physics_active<const ACTIVE: bool>(
+    mut rapier_configs: Query<&mut RapierConfiguration>,
+) {
+    for mut config in rapier_configs.iter_mut() {
+        config.physics_pipeline_active = ACTIVE;
+    }
+}
+
+fn win_level(
+    mut goal_reached: EventReader<GoalReached>,
+    mut next_state: ResMut<NextState<CampaignState>>,
+    mut timer: ResMut<WinScreenTimer>,
+) {
+    if goal_reached.read().count() > 0 {
+        timer.0.reset();
+        next_state.set(CampaignState::Won);
+    }
+}
+
+fn spawn_win_screen(
+    mut commands: Commands,
+    play_time: Option<Res<PlayTime>>,
+    points: Option<Res<CollectedPoints>>,
+) {
+    let mut lines = vec![("You Win!".to_string(), 56.0)];
+    if let Some(play_time) = play_time {
+        lines.push((format!("Time: {:.1} s", play_time.0), 28.0));
+    }
+    if let Some(points) = points {
+        lines.push((
+            format!("Points: {} / {}", points.collected, points.total),
+            28.0,
+        ));
+    }
+    lines.push(("Press any key to continue".to_string(), 24.0));
+    spawn_campaign_screen(&mut commands, lines);
+}
+
+fn spawn_complete_screen(mut commands: Commands) {
+    spawn_campaign_screen(
+        &mut commands,
+        vec![
+            ("Campaign Complete!".to_string(), 56.0),
+            ("Thanks for playing".to_string(), 28.0),
+            ("Press Enter to play again".to_string(), 24.0),
+        ],
+    );
+}
+
+fn spawn_campaign_screen(commands: &mut Commands, lines: Vec<(String, f32)>) {
+    commands
+        .spawn((
+            CampaignScreen,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        ))
+        .with_children(|parent| {
+            for (text, font_size) in lines {
+                parent.spawn((
+                    Text::new(text),
+                    TextFont {
+                        font_size,
+                        ..default()
+                    },
+                ));
+            }
+        });
+}
+
+fn despawn_campaign_screens(mut commands: Commands, screens: Query<Entity, With<CampaignScreen>>) {
+    for screen in screens.iter() {
+        commands.entity(screen).despawn();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn leave_win_screen(
+    time: Res<Time<bevy::time::Real>>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut timer: ResMut<WinScreenTimer>,
+    mut manager: ResMut<LevelManager>,
+    asset_server: Res<AssetServer>,
+    campaigns: Res<Assets<Campaign>>,
+    campaign: Option<Res<CampaignHandle>>,
+    mut next_state: ResMut<NextState<CampaignState>>,
+    mut unload: EventWriter<UnloadLevel>,
+) {
+    // Real time, since the win screen may be shown while the game is paused.
+    timer.0.tick(time.delta());
+    if !timer.0.finished() && keyboard.get_just_pressed().next().is_none() {
+        return;
+    }
+
+    let next_level = manager.current_level + 1;
+    let Some(path) = campaign
+        .and_then(|campaign| campaigns.get(&campaign.0))
+        .and_then(|campaign| campaign.levels.get(next_level))
+        .map(|level| &level.manifest)
+    else {
+        next_state.set(CampaignState::Complete);
+        return;
+    };
+
+    manager.current_level = next_level;
+    manager.load(&asset_server, path);
+    unload.write(UnloadLevel);
+    next_state.set(CampaignState::Loading);
+}
+
+fn restart_campaign(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut manager: ResMut<LevelManager>,
+    asset_server: Res<AssetServer>,
+    campaigns: Res<Assets<Campaign>>,
+    campaign: Option<Res<CampaignHandle>>,
+    mut next_state: ResMut<NextState<CampaignState>>,
+    mut unload: EventWriter<UnloadLevel>,
+) {
+    if !keyboard.just_pressed(KeyCode::Enter) {
+        return;
+    }
+    let Some(path) = campaign
+        .and_then(|campaign| campaigns.get(&campaign.0))
+        .and_then(|campaign| campaign.levels.first())
+        .map(|level| &level.manifest)
+    else {
+        return;
+    };
+
+    manager.current_level = 0;
+    manager.load(&asset_server, path);
+    unload.write(UnloadLevel);
+    next_state.set(CampaignState::Loading);
+}
+
+/// Starts playing once the scene of the next level is ready, after the spawn point moved the
+/// ball to its start.
+fn finish_loading(
+    trigger: Trigger<SceneInstanceReady>,
+    state: Res<State<CampaignState>>,
+    mut next_state: ResMut<NextState<CampaignState>>,
+    roots: Query<(), With<LevelEntity>>,
+    mut balls: Query<&mut Velocity, With<Ball>>,
+) {
+    if *state.get() != CampaignState::Loading || !roots.contains(trigger.target()) {
+        return;
+    }
+
+    for mut velocity in balls.iter_mut() {
+        *velocity = Velocity::zero();
+    }
+    next_state.set(CampaignState::Playing);
+}
@@ -0,0 +1,375 @@
+//! Turns glTF meshes and empties named `checkpoint_*` into checkpoints.
+//! When a [`Ball`] enters a checkpoint that isn't activated yet, the ball's [`RestartPosition`]
+//! is moved to the checkpoint, a [`CheckpointActivated`] event is sent, the chime is played and
+//! the checkpoint's meshes start glowing. Activated checkpoints stay activated, also when the
+//! ball restarts from them, until a [`ResetCheckpoints`] event is sent.
+//!
+//! A mesh gets a convex hull sensor on its parent, built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline, and an
+//! empty gets a ball sensor of [`CheckpointConfig::empty_radius`].
+
+use bevy::{prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    level_manifest_plugin::LevelEntity,
+    mesh_physics_plugin::{
+        ObjectCollider, SensorTriggered, register_object_collider, sensor_components,
+    },
+};
+
+const PREFIX: &str = "checkpoint_";
+/// The label of the sensor events of the checkpoints, also used by the empties' sensors.
+const SENSOR_LABEL: &str = "checkpoint";
+
+#[derive(Default)]
+pub struct CheckpointPlugin {
+    pub config: CheckpointConfig,
+}
+
+impl Plugin for CheckpointPlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                hide_mesh: false,
+                ..ObjectCollider::sensor(PREFIX)
+            },
+        );
+        app.insert_resource(self.config.clone())
+            .add_event::<CheckpointActivated>()
+            .add_event::<ResetCheckpoints>()
+            .add_systems(Update, (activate_checkpoints, reset_checkpoints))
+            .add_observer(insert_checkpoints);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct CheckpointConfig {
+    /// Added to the checkpoint's height for the restart position, so the ball doesn't restart
+    /// inside the floor.
+    pub height_offset: f32,
+    /// The radius of the sensor of an empty.
+    pub empty_radius: f32,
+    /// The asset path of the sound played when a checkpoint is activated.
+    pub chime: Option<String>,
+    /// The emissive color of activated checkpoints.
+    pub activated_emissive: LinearRgba,
+}
+
+impl Default for CheckpointConfig {
+    fn default() -> Self {
+        Self {
+            height_offset: 0.5,
+            empty_radius: 1.0,
+            chime: None,
+            activated_emissive: LinearRgba::rgb(0.2, 2.0, 0.6),
+        }
+    }
+}
+
+/// Where the ball is put back when it's restarted, e.g. after falling off the level.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct RestartPosition(pub Vec3);
+
+/// A checkpoint created from a `checkpoint_` object.
+#[derive(Component, Default)]
+pub struct Checkpoint {
+    pub activated: bool,
+    /// The meshes that glow once the checkpoint is activated.
+    meshes: Vec<Entity>,
+    /// The materials of the meshes from before they glowed.
+    original_materials: Vec<(Entity, Handle<StandardMaterial>)>,
+}
+
+/// Sent when a ball activates a checkpoint.
+#[derive(Event)]
+pub struct CheckpointActivated {
+    pub checkpoint: Entity,
+    pub ball: Entity,
+}
+
+/// Deactivates all the checkpoints, e.g. when a level starts over.
+#[derive(Event)]
+pub struct ResetCheckpoints;
+
+fn insert_checkpoints(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    config: Res<CheckpointConfig>,
+    children: Query<&Children>,
+    names: Query<&Name>,
+    mesh_query: Query<&ChildOf, With<Mesh3d>>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok(name) = names.get(entity) else {
+            continue;
+        };
+        if !name.starts_with(PREFIX) {
+            continue;
+        }
+
+        // The object of a mesh is named like the mesh, and gets its sensor from the mesh.
+        let has_mesh_children = children.get(entity).is_ok_and(|entity_children| {
+            entity_children
+                .iter()
+                .any(|child| mesh_query.contains(child))
+        });
+        if has_mesh_children {
+            continue;
+        }
+
+        // Without a mesh, the object is an empty.
+        let Ok(child_of) = mesh_query.get(entity) else {
+            commands.entity(entity).insert((
+                Checkpoint::default(),
+                Collider::ball(config.empty_radius),
+                sensor_components(SENSOR_LABEL),
+            ));
+            continue;
+        };
+
+        commands.entity(child_of.parent()).insert(Checkpoint {
+            meshes: vec![entity],
+            ..default()
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn activate_checkpoints(
+    mut commands: Commands,
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    mut activated: EventWriter<CheckpointActivated>,
+    config: Res<CheckpointConfig>,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut checkpoints: Query<(&mut Checkpoint, &GlobalTransform)>,
+    mesh_materials: Query<&MeshMaterial3d<StandardMaterial>>,
+    mut balls: Query<Option<&mut RestartPosition>, With<Ball>>,
+) {
+    for event in sensor_triggered.read() {
+        if !event.started {
+            continue;
+        }
+        let (checkpoint_entity, ball) = (event.sensor, event.other);
+        let Ok((mut checkpoint, transform)) = checkpoints.get_mut(checkpoint_entity) else {
+            continue;
+        };
+        let Ok(restart_position) = balls.get_mut(ball) else {
+            continue;
+        };
+        if checkpoint.activated {
+            continue;
+        }
+        checkpoint.activated = true;
+
+        let position = transform.translation() + Vec3::Y * config.height_offset;
+        match restart_position {
+            Some(mut restart_position) => restart_position.0 = position,
+            None => {
+                commands.entity(ball).insert(RestartPosition(position));
+            }
+        }
+
+        if let Some(chime) = &config.chime {
+            commands.spawn((
+                LevelEntity,
+                AudioPlayer::new(asset_server.load(chime.clone())),
+                PlaybackSettings::DESPAWN,
+            ));
+        }
+
+        // Copied so other meshes sharing the material don't glow too.
+        for mesh in checkpoint.meshes.clone() {
+            let Ok(original) = mesh_materials.get(mesh) else {
+                continue;
+            };
+            let Some(material) = materials.get(&original.0) else {
+                continue;
+            };
+            checkpoint
+                .original_materials
+                .push((mesh, original.0.clone()));
+            let mut material = material.clone();
+            material.emissive = config.activated_emissive;
+            commands
+                .entity(mesh)
+                .insert(MeshMaterial3d(materials.add(material)));
+        }
+
+        activated.write(CheckpointActivated {
+            checkpoint: checkpoint_entity,
+            ball,
+        });
+    }
+}
+
+fn reset_checkpoints(
+    mut commands: Commands,
+    mut reset: EventReader<ResetCheckpoints>,
+    mut checkpoints: Query<&mut Checkpoint>,
+) {
+    if reset.read().count() == 0 {
+        return;
+    }
+
+    for mut checkpoint in checkpoints.iter_mut() {
+        checkpoint.activated = false;
+        for (mesh, material) in checkpoint.original_materials.drain(..) {
+            if let Ok(mut mesh) = commands.get_entity(mesh) {
+                mesh.insert(MeshMaterial3d(material));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::scene::ScenePlugin;
+
+    use super::*;
+    use crate::plugins::mesh_physics_plugin::SensorVolume;
+
+    #[derive(Resource, Default)]
+    struct Activated(Vec<Entity>);
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin::default(),
+            ScenePlugin,
+            CheckpointPlugin::default(),
+        ))
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>()
+        .add_event::<CollisionEvent>()
+        .init_resource::<Activated>()
+        .add_systems(
+            Last,
+            |mut events: EventReader<CheckpointActivated>, mut activated: ResMut<Activated>| {
+                activated
+                    .0
+                    .extend(events.read().map(|event| event.checkpoint));
+            },
+        );
+        app
+    }
+
+    fn enter(app: &mut App, checkpoint: Entity, ball: Entity) {
+        app.world_mut().send_event(SensorTriggered {
+            label: SENSOR_LABEL.to_string(),
+            sensor: checkpoint,
+            other: ball,
+            started: true,
+        });
+        app.update();
+    }
+
+    fn spawn_checkpoint(app: &mut App, translation: Vec3) -> Entity {
+        app.world_mut()
+            .spawn((
+                Checkpoint::default(),
+                Transform::from_translation(translation),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn gives_empties_a_ball_sensor() {
+        let mut app = test_app();
+        let mut scene_world = World::new();
+        scene_world.spawn((Name::new("checkpoint_Flag"), Transform::default()));
+        let scene = app
+            .world_mut()
+            .resource_mut::<Assets<Scene>>()
+            .add(Scene::new(scene_world));
+        app.world_mut().spawn(SceneRoot(scene));
+        for _ in 0..3 {
+            app.update();
+        }
+
+        let world = app.world_mut();
+        let (checkpoint, collider, sensor) = world
+            .query::<(&Checkpoint, &Collider, &SensorVolume)>()
+            .single(world)
+            .unwrap();
+        assert!(!checkpoint.activated);
+        assert_eq!(
+            collider.as_ball().map(|ball| ball.radius()),
+            Some(CheckpointConfig::default().empty_radius)
+        );
+        assert_eq!(sensor.label, SENSOR_LABEL);
+    }
+
+    #[test]
+    fn moves_the_restart_position_once() {
+        let mut app = test_app();
+        let checkpoint = spawn_checkpoint(&mut app, Vec3::new(1.0, 2.0, 3.0));
+        let ball = app.world_mut().spawn(Ball { radius: 0.5 }).id();
+        app.update();
+
+        enter(&mut app, checkpoint, ball);
+        let restart = Vec3::new(1.0, 2.0 + CheckpointConfig::default().height_offset, 3.0);
+        assert_eq!(
+            app.world().get::<RestartPosition>(ball),
+            Some(&RestartPosition(restart))
+        );
+        assert!(app.world().get::<Checkpoint>(checkpoint).unwrap().activated);
+
+        // Entering again, e.g. after restarting from it, changes nothing.
+        app.world_mut()
+            .entity_mut(ball)
+            .insert(RestartPosition(Vec3::ZERO));
+        enter(&mut app, checkpoint, ball);
+        assert_eq!(
+            app.world().get::<RestartPosition>(ball),
+            Some(&RestartPosition(Vec3::ZERO))
+        );
+        assert_eq!(app.world().resource::<Activated>().0, [checkpoint]);
+    }
+
+    #[test]
+    fn ignores_everything_but_balls_entering() {
+        let mut app = test_app();
+        let checkpoint = spawn_checkpoint(&mut app, Vec3::ZERO);
+        let crate_body = app.world_mut().spawn(Transform::default()).id();
+        let ball = app.world_mut().spawn(Ball { radius: 0.5 }).id();
+        app.update();
+
+        enter(&mut app, checkpoint, crate_body);
+        app.world_mut().send_event(SensorTriggered {
+            label: SENSOR_LABEL.to_string(),
+            sensor: checkpoint,
+            other: ball,
+            started: false,
+        });
+        app.update();
+
+        assert!(!app.world().get::<Checkpoint>(checkpoint).unwrap().activated);
+        assert!(app.world().get::<RestartPosition>(ball).is_none());
+        assert!(app.world().resource::<Activated>().0.is_empty());
+    }
+
+    #[test]
+    fn resetting_deactivates_the_checkpoints() {
+        let mut app = test_app();
+        let checkpoint = spawn_checkpoint(&mut app, Vec3::ZERO);
+        let ball = app.world_mut().spawn(Ball { radius: 0.5 }).id();
+        app.update();
+
+        enter(&mut app, checkpoint, ball);
+        app.world_mut().send_event(ResetCheckpoints);
+        app.update();
+        assert!(!app.world().get::<Checkpoint>(checkpoint).unwrap().activated);
+
+        enter(&mut app, checkpoint, ball);
+        assert_eq!(
+            app.world().resource::<Activated>().0,
+            [checkpoint, checkpoint]
+        );
+    }
+}
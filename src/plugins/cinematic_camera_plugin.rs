@@ -0,0 +1,150 @@
+//! Moves cameras along scripted paths, e.g. for an intro cutscene when a level loads.
+//! A camera with a [`CinematicPath`] passes through the keyframes of the path on a smooth curve
+//! made of cubic Bézier segments, and keeps looking at a point that follows a curve too.
+//!
+//! The paths only play while [`CinematicMode`] is `true`. Other camera systems, like a third
+//! person camera following the ball, should do nothing while it's `true` so they don't fight
+//! over the camera. When a path that doesn't loop ends, [`CinematicMode`] is set back to `false`.
+
+use bevy::prelude::*;
+
+pub struct CinematicCameraPlugin;
+
+impl Plugin for CinematicCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CinematicMode>().add_systems(
+            Update,
+            (start_cinematic_paths, follow_cinematic_path)
+                .chain()
+                .run_if(|mode: Res<CinematicMode>| mode.0),
+        );
+    }
+}
+
+/// Whether the cinematic paths control their cameras.
+#[derive(Resource, Default)]
+pub struct CinematicMode(pub bool);
+
+#[derive(Component, Clone)]
+pub struct CinematicPath {
+    /// Sorted by time.
+    pub keyframes: Vec<CameraKeyframe>,
+    /// Start again from the first keyframe after the last one.
+    pub looping: bool,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct CameraKeyframe {
+    pub position: Vec3,
+    pub look_at: Vec3,
+    /// Seconds since the path started.
+    pub time: f32,
+}
+
+impl CinematicPath {
+    /// Returns the camera position and the point it looks at `elapsed` seconds after the path
+    /// started, or `None` if the path has no keyframes.
+    pub fn sample(&self, elapsed: f32) -> Option<(Vec3, Vec3)> {
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+
+        let elapsed = if self.looping && last.time > first.time {
+            first.time + (elapsed - first.time).rem_euclid(last.time - first.time)
+        } else {
+            elapsed
+        };
+        if elapsed <= first.time {
+            return Some((first.position, first.look_at));
+        }
+
+        // The segment between keyframes `i` and `i + 1` that contains `elapsed`.
+        let Some(i) = self
+            .keyframes
+            .windows(2)
+            .position(|pair| elapsed < pair[1].time)
+        else {
+            return Some((last.position, last.look_at));
+        };
+
+        let (start, end) = (self.keyframes[i], self.keyframes[i + 1]);
+        let t = (elapsed - start.time) / (end.time - start.time);
+        let before = self.keyframes[i.saturating_sub(1)];
+        let after = self.keyframes[(i + 2).min(self.keyframes.len() - 1)];
+
+        Some((
+            bezier_segment(
+                before.position,
+                start.position,
+                end.position,
+                after.position,
+                t,
+            ),
+            bezier_segment(before.look_at, start.look_at, end.look_at, after.look_at, t),
+        ))
+    }
+
+    /// The time of the last keyframe.
+    pub fn duration(&self) -> f32 {
+        self.keyframes.last().map_or(0.0, |keyframe| keyframe.time)
+    }
+}
+
+/// Seconds since the path of a camera started.
+#[derive(Component, Default)]
+struct CinematicElapsed(f32);
+
+/// Evaluates the Bézier segment from `start` to `end`.
+/// The control points are placed like in a Catmull-Rom spline, so the curve goes smoothly through
+/// each keyframe, heading from the keyframe before it to the one after it.
+fn bezier_segment(before: Vec3, start: Vec3, end: Vec3, after: Vec3, t: f32) -> Vec3 {
+    let control1 = start + (end - before) / 6.0;
+    let control2 = end - (after - start) / 6.0;
+
+    let u = 1.0 - t;
+    start * (u * u * u)
+        + control1 * (3.0 * u * u * t)
+        + control2 * (3.0 * u * t * t)
+        + end * (t * t * t)
+}
+
+fn start_cinematic_paths(
+    mut commands: Commands,
+    query: Query<Entity, (With<CinematicPath>, Without<CinematicElapsed>)>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).insert(CinematicElapsed::default());
+    }
+}
+
+fn follow_cinematic_path(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut mode: ResMut<CinematicMode>,
+    mut query: Query<(
+        Entity,
+        &CinematicPath,
+        &mut CinematicElapsed,
+        &mut Transform,
+    )>,
+) {
+    let mut playing = false;
+    for (entity, path, mut elapsed, mut transform) in query.iter_mut() {
+        elapsed.0 += time.delta_secs();
+        let Some((position, look_at)) = path.sample(elapsed.0) else {
+            continue;
+        };
+
+        *transform = Transform::from_translation(position).looking_at(look_at, Vec3::Y);
+        if path.looping || elapsed.0 < path.duration() {
+            playing = true;
+        } else {
+            // Play the path from the start the next time the mode is turned on.
+            commands.entity(entity).remove::<CinematicElapsed>();
+        }
+    }
+
+    // Give the camera back to the gameplay systems once every path has ended.
+    if !query.is_empty() && !playing {
+        mode.0 = false;
+    }
+}
@@ -0,0 +1,201 @@
+//! Toggles a semi-transparent mesh over every [`Collider`] with F5, green for solid colliders and
+//! blue for sensors.
+//! Unlike `RapierDebugRenderPlugin`, which draws gizmo lines and hits a Vulkan `SYNC-HAZARD` on
+//! some backends, the overlays are plain Bevy meshes.
+//!
+//! Balls and cuboids get primitive meshes, and a trimesh collider reuses the [`Mesh3d`] of its
+//! entity if it has one. Other shapes, like the hulls and compounds built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin), are
+//! triangulated.
+
+use bevy::{
+    pbr::NotShadowCaster,
+    platform::collections::HashMap,
+    prelude::*,
+    render::{
+        mesh::{Indices, PrimitiveTopology},
+        render_asset::RenderAssetUsages,
+    },
+};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::mesh_physics_plugin::append_triangles;
+
+pub struct ColliderDebugPlugin;
+
+impl Plugin for ColliderDebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ColliderDebug>()
+            .init_resource::<OverlayMaterials>()
+            .add_systems(
+                Update,
+                (
+                    toggle_collider_debug,
+                    (update_overlays, follow_colliders)
+                        .chain()
+                        .run_if(|debug: Res<ColliderDebug>| debug.enabled),
+                )
+                    .chain(),
+            );
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct ColliderDebug {
+    pub enabled: bool,
+    /// The overlay of each collider entity.
+    overlays: HashMap<Entity, Entity>,
+}
+
+#[derive(Resource)]
+struct OverlayMaterials {
+    solid: Handle<StandardMaterial>,
+    sensor: Handle<StandardMaterial>,
+}
+
+impl FromWorld for OverlayMaterials {
+    fn from_world(world: &mut World) -> Self {
+        let mut materials = world.resource_mut::<Assets<StandardMaterial>>();
+        let mut overlay_material = |color: Color| {
+            materials.add(StandardMaterial {
+                base_color: color,
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                cull_mode: None,
+                // Draws the overlay over the surface it's built from.
+                depth_bias: 1.0,
+                ..default()
+            })
+        };
+
+        Self {
+            solid: overlay_material(Color::srgba(0.2, 1.0, 0.4, 0.3)),
+            sensor: overlay_material(Color::srgba(0.2, 0.5, 1.0, 0.3)),
+        }
+    }
+}
+
+#[derive(Component)]
+struct ColliderOverlay {
+    collider: Entity,
+    /// Whether the mesh is unscaled, like a reused [`Mesh3d`], so it takes the collider's scale.
+    /// Rapier already scales the shapes of the colliders.
+    scaled: bool,
+}
+
+fn toggle_collider_debug(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut debug: ResMut<ColliderDebug>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F5) {
+        return;
+    }
+
+    debug.enabled = !debug.enabled;
+    if !debug.enabled {
+        for (_, overlay) in debug.overlays.drain() {
+            commands.entity(overlay).despawn();
+        }
+    }
+}
+
+/// Spawns the overlays of new colliders, rebuilds the ones whose collider changed, and despawns
+/// the ones whose collider is gone.
+#[allow(clippy::type_complexity)]
+fn update_overlays(
+    mut commands: Commands,
+    mut debug: ResMut<ColliderDebug>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    materials: Res<OverlayMaterials>,
+    colliders: Query<(Entity, Ref<Collider>, Option<&Mesh3d>, Has<Sensor>)>,
+) {
+    debug.overlays.retain(|&collider, &mut overlay| {
+        let exists = colliders.contains(collider);
+        if !exists {
+            commands.entity(overlay).despawn();
+        }
+        exists
+    });
+
+    for (entity, collider, mesh3d, is_sensor) in colliders.iter() {
+        let overlay = debug.overlays.get(&entity).copied();
+        if overlay.is_some() && !collider.is_changed() {
+            continue;
+        }
+
+        let (mesh, scaled) = match mesh3d.filter(|_| collider.as_trimesh().is_some()) {
+            Some(mesh3d) => (mesh3d.0.clone(), true),
+            None => (meshes.add(collider_mesh(&collider)), false),
+        };
+        let material = if is_sensor {
+            materials.sensor.clone()
+        } else {
+            materials.solid.clone()
+        };
+        let bundle = (
+            ColliderOverlay {
+                collider: entity,
+                scaled,
+            },
+            Mesh3d(mesh),
+            MeshMaterial3d(material),
+            NotShadowCaster,
+        );
+
+        match overlay {
+            Some(overlay) => {
+                commands.entity(overlay).insert(bundle);
+            }
+            None => {
+                let overlay = commands.spawn((bundle, Transform::default())).id();
+                debug.overlays.insert(entity, overlay);
+            }
+        }
+    }
+}
+
+/// Builds a mesh of the collider's shape, which rapier has already scaled.
+fn collider_mesh(collider: &Collider) -> Mesh {
+    if let Some(ball) = collider.as_ball() {
+        return Sphere::new(ball.radius()).mesh().build();
+    }
+    if let Some(cuboid) = collider.as_cuboid() {
+        return Cuboid::from_size(cuboid.half_extents() * 2.0)
+            .mesh()
+            .build();
+    }
+
+    let mut vertices = Vec::new();
+    let mut indices = Vec::new();
+    append_triangles(
+        &collider.raw,
+        Transform::IDENTITY,
+        &mut vertices,
+        &mut indices,
+    );
+    Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    )
+    .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vertices)
+    .with_inserted_indices(Indices::U32(indices.into_iter().flatten().collect()))
+}
+
+fn follow_colliders(
+    mut overlays: Query<(&ColliderOverlay, &mut Transform)>,
+    colliders: Query<&GlobalTransform, With<Collider>>,
+) {
+    for (overlay, mut transform) in overlays.iter_mut() {
+        let Ok(global_transform) = colliders.get(overlay.collider) else {
+            continue;
+        };
+
+        let (scale, rotation, translation) = global_transform.to_scale_rotation_translation();
+        *transform = Transform {
+            translation,
+            rotation,
+            scale: if overlay.scaled { scale } else { Vec3::ONE },
+        };
+    }
+}
@@ -0,0 +1,164 @@
+//! A panel showing the entity count, render pass count and physics body count, toggled with F3.
+//! Bevy doesn't count draw calls. The render passes are the passes that reported their timings
+//! to `RenderDiagnosticsPlugin`, which is a lot fewer than the draw calls, and are labeled as
+//! such.
+//!
+//! Each metric is green below 80% of its threshold in [`DebugOverlayThresholds`], yellow from
+//! 80% up to the threshold, and red above it.
+
+use bevy::{
+    diagnostic::{DiagnosticsStore, EntityCountDiagnosticsPlugin},
+    prelude::*,
+    render::diagnostic::RenderDiagnosticsPlugin,
+};
+use bevy_rapier3d::prelude::*;
+
+/// The part of a threshold from which a metric is shown in yellow.
+const WARNING_RATIO: f32 = 0.8;
+
+#[derive(Default)]
+pub struct DebugOverlayPlugin {
+    pub thresholds: DebugOverlayThresholds,
+}
+
+impl Plugin for DebugOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<EntityCountDiagnosticsPlugin>() {
+            app.add_plugins(EntityCountDiagnosticsPlugin);
+        }
+        if !app.is_plugin_added::<RenderDiagnosticsPlugin>() {
+            app.add_plugins(RenderDiagnosticsPlugin);
+        }
+
+        app.insert_resource(self.thresholds.clone())
+            .add_systems(Startup, spawn_overlay)
+            .add_systems(Update, (toggle_overlay, update_overlay));
+    }
+}
+
+/// The values above which a metric is shown in red.
+/// A metric turns yellow at [`WARNING_RATIO`] of its threshold.
+#[derive(Resource, Clone)]
+pub struct DebugOverlayThresholds {
+    /// All the entities in the world, including the UI and the render helpers.
+    pub entities: usize,
+    /// The render passes with timings, not the draw calls, which Bevy doesn't count.
+    pub render_passes: usize,
+    /// The rigid bodies in rapier, not the colliders without a body.
+    pub bodies: usize,
+}
+
+impl Default for DebugOverlayThresholds {
+    fn default() -> Self {
+        Self {
+            entities: 10_000,
+            render_passes: 50,
+            bodies: 1_000,
+        }
+    }
+}
+
+#[derive(Component)]
+struct DebugOverlay;
+
+#[derive(Component, Clone, Copy)]
+enum Metric {
+    Entities,
+    RenderPasses,
+    Bodies,
+}
+
+fn spawn_overlay(mut commands: Commands) {
+    commands
+        .spawn((
+            DebugOverlay,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(5.0),
+                right: Val::Px(5.0),
+                padding: UiRect::all(Val::Px(5.0)),
+                flex_direction: FlexDirection::Column,
+                ..default()
+            },
+            BackgroundColor(Color::BLACK.with_alpha(0.6)),
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            for metric in [Metric::Entities, Metric::RenderPasses, Metric::Bodies] {
+                parent.spawn((
+                    metric,
+                    Text::default(),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                ));
+            }
+        });
+}
+
+fn toggle_overlay(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut query: Query<&mut Visibility, With<DebugOverlay>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::F3) {
+        return;
+    }
+
+    for mut visibility in query.iter_mut() {
+        visibility.toggle_visible_hidden();
+    }
+}
+
+fn update_overlay(
+    diagnostics: Res<DiagnosticsStore>,
+    thresholds: Res<DebugOverlayThresholds>,
+    rapier_context: ReadRapierContext,
+    overlay: Query<&Visibility, With<DebugOverlay>>,
+    mut query: Query<(&Metric, &mut Text, &mut TextColor)>,
+) {
+    if overlay
+        .iter()
+        .all(|visibility| visibility == Visibility::Hidden)
+    {
+        return;
+    }
+
+    let entities = diagnostics
+        .get(&EntityCountDiagnosticsPlugin::ENTITY_COUNT)
+        .and_then(|diagnostic| diagnostic.value())
+        .unwrap_or_default() as usize;
+    let render_passes = diagnostics
+        .iter()
+        .filter(|diagnostic| {
+            let path = diagnostic.path().as_str();
+            path.starts_with("render/") && path.ends_with("/elapsed_cpu")
+        })
+        .count();
+    let bodies = rapier_context
+        .single()
+        .map(|context| context.rigidbody_set.bodies.len())
+        .unwrap_or_default();
+
+    for (metric, mut text, mut color) in query.iter_mut() {
+        let (label, value, threshold) = match metric {
+            Metric::Entities => ("Entities", entities, thresholds.entities),
+            Metric::RenderPasses => ("Render passes", render_passes, thresholds.render_passes),
+            Metric::Bodies => ("Physics bodies", bodies, thresholds.bodies),
+        };
+
+        text.0 = format!("{label}: {value}");
+        color.0 = metric_color(value, threshold);
+    }
+}
+
+/// Green below [`WARNING_RATIO`] of the threshold, yellow up to it and red above it.
+fn metric_color(value: usize, threshold: usize) -> Color {
+    if value > threshold {
+        Color::linear_rgb(1.0, 0.0, 0.0)
+    } else if value as f32 >= threshold as f32 * WARNING_RATIO {
+        Color::linear_rgb(1.0, 1.0, 0.0)
+    } else {
+        Color::linear_rgb(0.0, 1.0, 0.0)
+    }
+}
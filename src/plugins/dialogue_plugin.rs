@@ -0,0 +1,350 @@
+//! Shows dialogue lines in a box at the bottom of the screen, e.g. for the intro of a level.
+//! A line is shown until [`DialogueConfig::advance_key`] is pressed or its duration is over, then
+//! the next one is shown. The key press is consumed, so systems running after it don't see it.
+//!
+//! Dialogues are `.dialogue.json` files with an array of lines:
+//!
+//! ```json
+//! [
+//!     { "speaker": "Guide", "text": "Roll to the goal!", "duration": 3.0 },
+//!     { "text": "Mind the gaps." }
+//! ]
+//! ```
+//!
+//! A [`StartDialogue`] event shows a dialogue. Besides that, a trigger volume named
+//! `trigger_dialogue_<DialogueName>_*` starts `<DialogueName>.dialogue.json` from
+//! [`DialogueConfig::directory`] the first time a [`Ball`] enters it, e.g. `trigger_dialogue_Intro`
+//! for `dialogues/Intro.dialogue.json`. A full restart, which sends [`ResetCheckpoints`], lets
+//! them play again, and an [`UnloadLevel`] also closes the dialogue being shown.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    prelude::*,
+    scene::SceneInstanceReady,
+};
+use serde::Deserialize;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball, checkpoint_plugin::ResetCheckpoints,
+    level_manifest_plugin::UnloadLevel, trigger_volume_plugin::TriggerEntered,
+};
+
+#[derive(Default)]
+pub struct DialoguePlugin {
+    pub config: DialogueConfig,
+}
+
+impl Plugin for DialoguePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone())
+            .init_asset::<Dialogue>()
+            .register_asset_loader(DialogueLoader)
+            .add_event::<StartDialogue>()
+            .add_event::<TriggerEntered>()
+            .add_event::<ResetCheckpoints>()
+            .add_event::<UnloadLevel>()
+            .add_systems(Startup, spawn_dialogue_box)
+            .add_systems(
+                Update,
+                (
+                    reset_dialogues,
+                    start_dialogue_from_volumes,
+                    start_dialogue,
+                    advance_dialogue,
+                    update_dialogue_box,
+                )
+                    .chain(),
+            )
+            .add_observer(insert_dialogue_volumes);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct DialogueConfig {
+    /// The asset folder of the dialogues started by trigger volumes.
+    pub directory: String,
+    pub font_size: f32,
+    /// The key showing the next line. It shouldn't be bound to a game action, e.g. `Space`
+    /// jumps.
+    pub advance_key: KeyCode,
+}
+
+impl Default for DialogueConfig {
+    fn default() -> Self {
+        Self {
+            directory: "dialogues".to_string(),
+            font_size: 22.0,
+            advance_key: KeyCode::Enter,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct DialogueLine {
+    #[serde(default)]
+    pub speaker: Option<String>,
+    pub text: String,
+    /// How long the line is shown without pressing [`DialogueConfig::advance_key`], in seconds.
+    #[serde(default = "default_line_duration")]
+    pub duration: f32,
+}
+
+fn default_line_duration() -> f32 {
+    4.0
+}
+
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct Dialogue(pub Vec<DialogueLine>);
+
+/// Shows a dialogue, replacing the one being shown.
+#[derive(Event)]
+pub struct StartDialogue(pub Handle<Dialogue>);
+
+/// A `trigger_dialogue_` volume, on the same entity as its
+/// [`TriggerVolume`](crate::plugins::trigger_volume_plugin::TriggerVolume).
+#[derive(Component)]
+pub struct DialogueVolume {
+    pub dialogue: Handle<Dialogue>,
+    /// Whether the dialogue was started already, so it isn't repeated.
+    pub played: bool,
+}
+
+/// The dialogue being shown.
+#[derive(Resource)]
+struct ActiveDialogue {
+    dialogue: Handle<Dialogue>,
+    line: usize,
+    elapsed: f32,
+}
+
+#[derive(Component)]
+struct DialogueBox;
+
+#[derive(Component)]
+struct SpeakerText;
+
+#[derive(Component)]
+struct LineText;
+
+#[derive(Debug)]
+pub enum DialogueLoaderError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for DialogueLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read the dialogue: {err}"),
+            Self::Json(err) => write!(f, "failed to parse the dialogue: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for DialogueLoaderError {}
+
+impl From<std::io::Error> for DialogueLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for DialogueLoaderError {
+    fn from(err: serde_json::Error) -> Self {
+        Self::Json(err)
+    }
+}
+
+#[derive(Default)]
+struct DialogueLoader;
+
+impl AssetLoader for DialogueLoader {
+    type Asset = Dialogue;
+    type Settings = ();
+    type Error = DialogueLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["dialogue.json"]
+    }
+}
+
+/// Returns the dialogue name of a mesh name like `trigger_dialogue_Intro_box`.
+pub fn parse_dialogue_name(name: &str) -> Option<&str> {
+    let rest = name.strip_prefix("trigger_dialogue_")?;
+    let dialogue_name = rest.split('_').next()?;
+    (!dialogue_name.is_empty()).then_some(dialogue_name)
+}
+
+fn spawn_dialogue_box(mut commands: Commands, config: Res<DialogueConfig>) {
+    commands
+        .spawn((
+            DialogueBox,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(24.0),
+                left: Val::Percent(15.0),
+                width: Val::Percent(70.0),
+                padding: UiRect::all(Val::Px(16.0)),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(6.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.75)),
+            Visibility::Hidden,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                SpeakerText,
+                Text::default(),
+                TextFont {
+                    font_size: config.font_size,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.85, 0.4)),
+            ));
+            parent.spawn((
+                LineText,
+                Text::default(),
+                TextFont {
+                    font_size: config.font_size,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn insert_dialogue_volumes(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    config: Res<DialogueConfig>,
+    asset_server: Res<AssetServer>,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf), With<Mesh3d>>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of)) = query.get(entity) else {
+            continue;
+        };
+        let Some(dialogue_name) = parse_dialogue_name(name) else {
+            continue;
+        };
+
+        let path = format!("{}/{dialogue_name}.dialogue.json", config.directory);
+        commands.entity(child_of.parent()).insert(DialogueVolume {
+            dialogue: asset_server.load(path),
+            played: false,
+        });
+    }
+}
+
+fn reset_dialogues(
+    mut commands: Commands,
+    mut reset: EventReader<ResetCheckpoints>,
+    mut unload: EventReader<UnloadLevel>,
+    mut volumes: Query<&mut DialogueVolume>,
+) {
+    if unload.read().count() > 0 {
+        commands.remove_resource::<ActiveDialogue>();
+    }
+    if reset.read().count() == 0 {
+        return;
+    }
+
+    for mut volume in volumes.iter_mut() {
+        volume.played = false;
+    }
+}
+
+fn start_dialogue_from_volumes(
+    mut trigger_entered: EventReader<TriggerEntered>,
+    mut start: EventWriter<StartDialogue>,
+    mut volumes: Query<&mut DialogueVolume>,
+    balls: Query<(), With<Ball>>,
+) {
+    for event in trigger_entered.read() {
+        let Ok(mut volume) = volumes.get_mut(event.volume) else {
+            continue;
+        };
+        if volume.played || !balls.contains(event.entity) {
+            continue;
+        }
+
+        volume.played = true;
+        start.write(StartDialogue(volume.dialogue.clone()));
+    }
+}
+
+fn start_dialogue(mut commands: Commands, mut start: EventReader<StartDialogue>) {
+    if let Some(StartDialogue(dialogue)) = start.read().last() {
+        commands.insert_resource(ActiveDialogue {
+            dialogue: dialogue.clone(),
+            line: 0,
+            elapsed: 0.0,
+        });
+    }
+}
+
+fn advance_dialogue(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<DialogueConfig>,
+    mut keyboard: ResMut<ButtonInput<KeyCode>>,
+    dialogues: Res<Assets<Dialogue>>,
+    active: Option<ResMut<ActiveDialogue>>,
+) {
+    let Some(mut active) = active else {
+        return;
+    };
+    // The lines only start once the file is loaded.
+    let Some(dialogue) = dialogues.get(&active.dialogue) else {
+        return;
+    };
+    let Some(line) = dialogue.0.get(active.line) else {
+        commands.remove_resource::<ActiveDialogue>();
+        return;
+    };
+
+    active.elapsed += time.delta_secs();
+    if active.elapsed >= line.duration || keyboard.clear_just_pressed(config.advance_key) {
+        active.line += 1;
+        active.elapsed = 0.0;
+    }
+}
+
+fn update_dialogue_box(
+    dialogues: Res<Assets<Dialogue>>,
+    active: Option<Res<ActiveDialogue>>,
+    mut dialogue_box: Single<&mut Visibility, With<DialogueBox>>,
+    mut speaker_text: Single<&mut Text, (With<SpeakerText>, Without<LineText>)>,
+    mut line_text: Single<&mut Text, (With<LineText>, Without<SpeakerText>)>,
+) {
+    let line = active.as_ref().and_then(|active| {
+        dialogues
+            .get(&active.dialogue)
+            .and_then(|dialogue| dialogue.0.get(active.line))
+    });
+    let Some(line) = line else {
+        dialogue_box.set_if_neq(Visibility::Hidden);
+        return;
+    };
+
+    dialogue_box.set_if_neq(Visibility::Inherited);
+    let speaker = line.speaker.clone().unwrap_or_default();
+    if speaker_text.0 != speaker {
+        speaker_text.0 = speaker;
+    }
+    if line_text.0 != line.text {
+        line_text.0 = line.text.clone();
+    }
+}
@@ -0,0 +1,213 @@
+//! Turns glTF meshes named `elevator_<name>` into elevators that rise to an empty named
+//! `elevatortop_<name>` when the ball rests on them, e.g. `elevator_Lift` and `elevatortop_Lift`.
+//! The mesh's parent gets a trimesh collider and a velocity based kinematic body from the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline, so the
+//! ball is carried with friction. The elevators are moved right before the physics step, by the
+//! time rapier advances in it.
+//!
+//! An elevator waits at the bottom until a [`Ball`] has touched its collider for
+//! [`ElevatorConfig::rest_time`]. Contact pairs are used instead of a sensor, so standing on the
+//! edge counts too. At the top it waits for at least [`ElevatorConfig::wait`], and returns once
+//! the ball is off. If the ball leaves while it's rising, it turns around and goes back down,
+//! so it's never left stranded at the top. A ball getting on while it's going down has to wait
+//! until it's at the bottom again.
+//!
+//! A full restart, which sends [`ResetCheckpoints`], puts the elevators back at the bottom.
+
+use bevy::{platform::collections::HashMap, prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    checkpoint_plugin::ResetCheckpoints,
+    mesh_physics_plugin::{ObjectCollider, physics_dt, register_object_collider},
+};
+
+const PREFIX: &str = "elevator_";
+
+/// How close to its destination an elevator has to be to have arrived.
+const ARRIVAL_DISTANCE: f32 = 0.01;
+
+#[derive(Default)]
+pub struct ElevatorPlugin {
+    pub config: ElevatorConfig,
+}
+
+impl Plugin for ElevatorPlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                prefix: PREFIX.to_string(),
+                body: RigidBody::KinematicVelocityBased,
+                ..default()
+            },
+        );
+        app.insert_resource(self.config.clone())
+            .add_event::<ResetCheckpoints>()
+            .add_systems(Update, reset_elevators)
+            .add_systems(PostUpdate, move_elevators.before(PhysicsSet::SyncBackend))
+            .add_observer(insert_elevators);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct ElevatorConfig {
+    /// How fast the elevators move, in meters per second.
+    pub speed: f32,
+    /// How long the ball has to be on an elevator before it rises, in seconds.
+    pub rest_time: f32,
+    /// The least time an elevator stays at the top, in seconds.
+    pub wait: f32,
+}
+
+impl Default for ElevatorConfig {
+    fn default() -> Self {
+        Self {
+            speed: 2.0,
+            rest_time: 0.5,
+            wait: 1.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ElevatorState {
+    /// At the bottom, with the seconds the ball has been on it.
+    Idle(f32),
+    Rising,
+    /// At the top, with the seconds it has been there.
+    AtTop(f32),
+    Lowering,
+}
+
+/// An elevator created from an `elevator_` mesh, on the mesh's parent.
+#[derive(Component)]
+pub struct Elevator {
+    pub state: ElevatorState,
+    /// The `elevatortop_` empty it rises to.
+    pub top: Entity,
+    /// Where the elevator was when the scene was spawned.
+    initial: Transform,
+    /// The world position of the bottom, known once the transforms are propagated.
+    bottom: Option<Vec3>,
+}
+
+fn insert_elevators(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    names: Query<&Name>,
+    query: Query<(&Name, &ChildOf), With<Mesh3d>>,
+    transforms: Query<&Transform>,
+) {
+    let tops: HashMap<&str, Entity> = children
+        .iter_descendants(trigger.target())
+        .filter_map(|entity| {
+            let name = names.get(entity).ok()?.as_str();
+            Some((name.strip_prefix("elevatortop_")?, entity))
+        })
+        .collect();
+
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of)) = query.get(entity) else {
+            continue;
+        };
+        let Some(own_name) = name.strip_prefix(PREFIX) else {
+            continue;
+        };
+        let Some(&top) = tops.get(own_name) else {
+            warn!("`{name}` has no `elevatortop_{own_name}`, so it won't move.");
+            continue;
+        };
+
+        let elevator = child_of.parent();
+        let initial = transforms.get(elevator).copied().unwrap_or_default();
+        commands.entity(elevator).insert(Elevator {
+            state: ElevatorState::Idle(0.0),
+            top,
+            initial,
+            bottom: None,
+        });
+    }
+}
+
+fn move_elevators(
+    time: Res<Time>,
+    timestep_mode: Res<TimestepMode>,
+    config: Res<ElevatorConfig>,
+    rapier_context: ReadRapierContext,
+    balls: Query<(), With<Ball>>,
+    tops: Query<&GlobalTransform>,
+    mut elevators: Query<(Entity, &mut Elevator, &mut Velocity, &GlobalTransform)>,
+) {
+    let dt = physics_dt(&timestep_mode, time.delta_secs());
+    let Ok(context) = rapier_context.single() else {
+        return;
+    };
+    if dt == 0.0 {
+        return;
+    }
+
+    for (entity, mut elevator, mut velocity, transform) in elevators.iter_mut() {
+        let position = transform.translation();
+        let bottom = *elevator.bottom.get_or_insert(position);
+        let Ok(top) = tops.get(elevator.top).map(|top| top.translation()) else {
+            continue;
+        };
+
+        let ball_on = context.contact_pairs_with(entity).any(|pair| {
+            pair.has_any_active_contact()
+                && [pair.collider1(), pair.collider2()]
+                    .into_iter()
+                    .flatten()
+                    .any(|other| other != entity && balls.contains(other))
+        });
+
+        elevator.state = match elevator.state {
+            ElevatorState::Idle(on_time) if ball_on && on_time + dt >= config.rest_time => {
+                ElevatorState::Rising
+            }
+            ElevatorState::Idle(on_time) => {
+                ElevatorState::Idle(if ball_on { on_time + dt } else { 0.0 })
+            }
+            ElevatorState::Rising if !ball_on => ElevatorState::Lowering,
+            ElevatorState::Rising if position.distance(top) <= ARRIVAL_DISTANCE => {
+                ElevatorState::AtTop(0.0)
+            }
+            ElevatorState::AtTop(waited) if !ball_on && waited >= config.wait => {
+                ElevatorState::Lowering
+            }
+            ElevatorState::AtTop(waited) => ElevatorState::AtTop(waited + dt),
+            ElevatorState::Lowering if position.distance(bottom) <= ARRIVAL_DISTANCE => {
+                ElevatorState::Idle(0.0)
+            }
+            state => state,
+        };
+
+        let destination = match elevator.state {
+            ElevatorState::Rising => top,
+            ElevatorState::Lowering => bottom,
+            ElevatorState::Idle(_) | ElevatorState::AtTop(_) => position,
+        };
+        // Slows down on the last step so it stops at the destination instead of overshooting.
+        let offset = destination - position;
+        velocity.linvel = offset.normalize_or_zero() * config.speed.min(offset.length() / dt);
+        velocity.angvel = Vec3::ZERO;
+    }
+}
+
+fn reset_elevators(
+    mut reset_checkpoints: EventReader<ResetCheckpoints>,
+    mut elevators: Query<(&mut Elevator, &mut Transform, &mut Velocity)>,
+) {
+    if reset_checkpoints.read().count() == 0 {
+        return;
+    }
+
+    for (mut elevator, mut transform, mut velocity) in elevators.iter_mut() {
+        elevator.state = ElevatorState::Idle(0.0);
+        *transform = elevator.initial;
+        *velocity = Velocity::zero();
+    }
+}
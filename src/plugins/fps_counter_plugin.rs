@@ -0,0 +1,114 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+#[derive(Default)]
+pub struct FpsCounterPlugin {
+    pub config: FpsCounterConfig,
+}
+
+impl Plugin for FpsCounterPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone())
+            .insert_resource(FrameDeltas::default())
+            .add_systems(Startup, spawn_counter)
+            .add_systems(Update, update_counter);
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum CornerPosition {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+#[derive(Resource, Clone)]
+pub struct FpsCounterConfig {
+    pub position: CornerPosition,
+    pub font_size: f32,
+    pub color: Color,
+    /// How many of the latest frames the average is taken over.
+    pub sample_window: usize,
+    /// Seconds between two text updates.
+    pub update_interval: f32,
+}
+
+impl Default for FpsCounterConfig {
+    fn default() -> Self {
+        Self {
+            position: CornerPosition::TopLeft,
+            font_size: 20.0,
+            color: Color::WHITE,
+            sample_window: 60,
+            update_interval: 0.25,
+        }
+    }
+}
+
+#[derive(Component)]
+struct FpsText;
+
+/// Ring buffer of the latest frame deltas.
+#[derive(Resource, Default)]
+struct FrameDeltas {
+    deltas: VecDeque<f32>,
+    since_update: f32,
+}
+
+fn spawn_counter(mut commands: Commands, config: Res<FpsCounterConfig>) {
+    let margin = Val::Px(5.0);
+    let mut node = Node {
+        position_type: PositionType::Absolute,
+        ..default()
+    };
+
+    match config.position {
+        CornerPosition::TopLeft => (node.top, node.left) = (margin, margin),
+        CornerPosition::TopRight => (node.top, node.right) = (margin, margin),
+        CornerPosition::BottomLeft => (node.bottom, node.left) = (margin, margin),
+        CornerPosition::BottomRight => (node.bottom, node.right) = (margin, margin),
+    }
+
+    commands.spawn((
+        FpsText,
+        Text::new("FPS: -"),
+        TextFont {
+            font_size: config.font_size,
+            ..default()
+        },
+        TextColor(config.color),
+        node,
+    ));
+}
+
+fn update_counter(
+    time: Res<Time>,
+    config: Res<FpsCounterConfig>,
+    mut frame_deltas: ResMut<FrameDeltas>,
+    mut query: Query<&mut Text, With<FpsText>>,
+) {
+    let delta = time.delta_secs();
+
+    frame_deltas.deltas.push_back(delta);
+    while frame_deltas.deltas.len() > config.sample_window.max(1) {
+        frame_deltas.deltas.pop_front();
+    }
+
+    frame_deltas.since_update += delta;
+    if frame_deltas.since_update < config.update_interval {
+        return;
+    }
+    frame_deltas.since_update = 0.0;
+
+    let total: f32 = frame_deltas.deltas.iter().sum();
+    if total <= 0.0 {
+        return;
+    }
+    let fps = frame_deltas.deltas.len() as f32 / total;
+
+    for mut text in query.iter_mut() {
+        text.0 = format!("FPS: {fps:.0}");
+    }
+}
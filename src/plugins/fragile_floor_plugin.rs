@@ -0,0 +1,280 @@
+//! Turns glTF meshes named `fragile_*` into floors that hold the ball while it rolls, but shatter
+//! when it lands on them too hard.
+//! The impulse a floor takes before breaking is the `fragile_impulse` number in the glTF extras
+//! of the mesh or its parent, or [`FragileFloorConfig::impulse_threshold`], which is about what
+//! the ball lands with after a drop of 2 m. The impulse is read from rapier's contact force
+//! events.
+//!
+//! A broken floor plays an optional sound, hides its mesh, scatters a few debris cubes and lets
+//! the ball fall through. A full restart, which sends [`ResetCheckpoints`], mends the floors.
+//!
+//! The trimesh colliders are built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline.
+
+use bevy::{gltf::GltfExtras, prelude::*, render::mesh::MeshAabb, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    checkpoint_plugin::ResetCheckpoints,
+    level_manifest_plugin::LevelEntity,
+    mesh_physics_plugin::{ObjectCollider, physics_dt, register_object_collider},
+};
+
+const PREFIX: &str = "fragile_";
+
+/// The size of the debris cubes.
+const DEBRIS_SIZE: f32 = 0.25;
+/// The longest a physics step is assumed to take, so the contact force events are only sent
+/// when the impulse could be over the threshold.
+const MAX_STEP: f32 = 1.0 / 30.0;
+
+#[derive(Default)]
+pub struct FragileFloorPlugin {
+    pub config: FragileFloorConfig,
+}
+
+impl Plugin for FragileFloorPlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                prefix: PREFIX.to_string(),
+                ..default()
+            },
+        );
+        app.insert_resource(self.config.clone())
+            .init_resource::<DebrisAssets>()
+            .add_event::<ResetCheckpoints>()
+            .add_systems(Update, (break_floors, mend_floors, despawn_debris).chain())
+            .add_observer(insert_fragile_floors);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct FragileFloorConfig {
+    /// The impulse that breaks the floors that don't set their own, in newton seconds.
+    pub impulse_threshold: f32,
+    /// The debris cubes scattered by a broken floor.
+    pub debris_count: usize,
+    /// How long the debris lasts, in seconds.
+    pub debris_lifetime: f32,
+    /// The color of the debris cubes.
+    pub debris_color: Color,
+    /// The asset path of the sound played when a floor breaks.
+    pub sound: Option<String>,
+}
+
+impl Default for FragileFloorConfig {
+    fn default() -> Self {
+        Self {
+            impulse_threshold: 2.5,
+            debris_count: 6,
+            debris_lifetime: 3.0,
+            debris_color: Color::srgb(0.6, 0.55, 0.5),
+            sound: None,
+        }
+    }
+}
+
+/// A floor created from a `fragile_` mesh, on the mesh's parent.
+#[derive(Component)]
+pub struct FragileFloor {
+    pub impulse_threshold: f32,
+    pub broken: bool,
+    /// The mesh, which is hidden while the floor is broken.
+    mesh: Entity,
+    /// The half size of the mesh, where the debris is scattered.
+    half_extents: Vec3,
+}
+
+/// The mesh and material shared by all the debris cubes.
+#[derive(Resource)]
+struct DebrisAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+impl FromWorld for DebrisAssets {
+    fn from_world(world: &mut World) -> Self {
+        let color = world
+            .get_resource::<FragileFloorConfig>()
+            .map_or(FragileFloorConfig::default().debris_color, |config| {
+                config.debris_color
+            });
+        Self {
+            mesh: world
+                .resource_mut::<Assets<Mesh>>()
+                .add(Cuboid::from_length(DEBRIS_SIZE)),
+            material: world
+                .resource_mut::<Assets<StandardMaterial>>()
+                .add(StandardMaterial::from_color(color)),
+        }
+    }
+}
+
+#[derive(Component)]
+struct Debris {
+    /// The seconds left until it's despawned.
+    lifetime: f32,
+}
+
+/// Parses the `fragile_impulse` from the extras JSON.
+fn parse_fragile_impulse(json: &str) -> Result<Option<f32>, String> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+    value
+        .get("fragile_impulse")
+        .map(|impulse| {
+            impulse
+                .as_f64()
+                .map(|impulse| impulse as f32)
+                .ok_or_else(|| format!("`fragile_impulse` should be a number, got {impulse}"))
+        })
+        .transpose()
+}
+
+fn insert_fragile_floors(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    config: Res<FragileFloorConfig>,
+    meshes: Res<Assets<Mesh>>,
+    children: Query<&Children>,
+    query: Query<(&Name, &Mesh3d, &ChildOf, Option<&Transform>)>,
+    extras_query: Query<&GltfExtras>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, mesh3d, child_of, transform)) = query.get(entity) else {
+            continue;
+        };
+        if !name.starts_with(PREFIX) {
+            continue;
+        }
+
+        let impulse_threshold = extras_query
+            .get(entity)
+            .or_else(|_| extras_query.get(child_of.parent()))
+            .map_or(Ok(None), |extras| parse_fragile_impulse(&extras.value))
+            .unwrap_or_else(|err| {
+                warn!("Skipping the extras of `{name}`: {err}");
+                None
+            })
+            .unwrap_or(config.impulse_threshold);
+
+        let scale = transform.map_or(Vec3::ONE, |transform| transform.scale);
+        let half_extents = meshes
+            .get(&mesh3d.0)
+            .and_then(|mesh| mesh.compute_aabb())
+            .map_or(Vec3::ONE, |aabb| Vec3::from(aabb.half_extents))
+            * scale;
+
+        commands.entity(child_of.parent()).insert((
+            FragileFloor {
+                impulse_threshold,
+                broken: false,
+                mesh: entity,
+                half_extents,
+            },
+            ActiveEvents::CONTACT_FORCE_EVENTS,
+            ContactForceEventThreshold(impulse_threshold / MAX_STEP),
+        ));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn break_floors(
+    mut commands: Commands,
+    mut contact_force_events: EventReader<ContactForceEvent>,
+    time: Res<Time>,
+    timestep_mode: Res<TimestepMode>,
+    config: Res<FragileFloorConfig>,
+    debris_assets: Res<DebrisAssets>,
+    asset_server: Res<AssetServer>,
+    mut floors: Query<(&mut FragileFloor, &GlobalTransform)>,
+    balls: Query<(), With<Ball>>,
+) {
+    // A contact force is the impulse of a step spread over the step.
+    let step = physics_dt(&timestep_mode, time.delta_secs());
+    for event in contact_force_events.read() {
+        for (floor_entity, ball) in [
+            (event.collider1, event.collider2),
+            (event.collider2, event.collider1),
+        ] {
+            let Ok((mut floor, transform)) = floors.get_mut(floor_entity) else {
+                continue;
+            };
+            if floor.broken
+                || !balls.contains(ball)
+                || event.total_force_magnitude * step < floor.impulse_threshold
+            {
+                continue;
+            }
+            floor.broken = true;
+
+            commands.entity(floor_entity).insert(ColliderDisabled);
+            commands.entity(floor.mesh).insert(Visibility::Hidden);
+            if let Some(sound) = &config.sound {
+                commands.spawn((
+                    LevelEntity,
+                    AudioPlayer::new(asset_server.load(sound.clone())),
+                    PlaybackSettings::DESPAWN,
+                ));
+            }
+
+            for i in 0..config.debris_count {
+                // Spread evenly on a ring around the center of the floor.
+                let angle = i as f32 / config.debris_count as f32 * std::f32::consts::TAU;
+                let offset = Vec3::new(angle.cos(), 0.0, angle.sin()) * floor.half_extents * 0.5;
+                commands.spawn((
+                    LevelEntity,
+                    Debris {
+                        lifetime: config.debris_lifetime,
+                    },
+                    Mesh3d(debris_assets.mesh.clone()),
+                    MeshMaterial3d(debris_assets.material.clone()),
+                    Transform::from_translation(transform.transform_point(offset)),
+                    RigidBody::Dynamic,
+                    Collider::cuboid(DEBRIS_SIZE / 2.0, DEBRIS_SIZE / 2.0, DEBRIS_SIZE / 2.0),
+                    // Outwards from the center of the floor as it's placed in the world.
+                    Velocity::linear(
+                        transform
+                            .affine()
+                            .transform_vector3(offset)
+                            .normalize_or_zero(),
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+fn mend_floors(
+    mut commands: Commands,
+    mut reset_checkpoints: EventReader<ResetCheckpoints>,
+    mut floors: Query<(Entity, &mut FragileFloor)>,
+) {
+    if reset_checkpoints.read().count() == 0 {
+        return;
+    }
+
+    for (entity, mut floor) in floors.iter_mut() {
+        if !floor.broken {
+            continue;
+        }
+        floor.broken = false;
+        commands.entity(entity).remove::<ColliderDisabled>();
+        commands.entity(floor.mesh).insert(Visibility::Inherited);
+    }
+}
+
+fn despawn_debris(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Debris)>,
+) {
+    for (entity, mut debris) in query.iter_mut() {
+        debris.lifetime -= time.delta_secs();
+        if debris.lifetime <= 0.0 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
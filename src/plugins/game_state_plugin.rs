@@ -0,0 +1,400 @@
+//! The [`GameState`] of playing a level, so gameplay only runs while the level can be played:
+//! - [`GameState::Loading`] until the colliders of a scene are inserted, see [`PhysicsReady`], and
+//!   the assets of the loading screen are loaded, see [`AssetsLoaded`]. An [`UnloadLevel`] goes
+//!   back to it, so the next level is played once it's loaded.
+//! - [`GameState::Playing`] until a [`Ball`] reaches an unlocked goal, see [`GoalPlugin`], which
+//!   enters [`GameState::Won`], or a [`BallFell`] event, which enters [`GameState::Fell`].
+//! - In [`GameState::Fell`], `R` puts the balls back at their [`RestartPosition`], or the
+//!   [`PlayerStart`] without one, and plays on. Balls that are [`Respawning`] or wait for their
+//!   [`RespawnCountdown`] are left to the
+//!   [`RespawnPlugin`](crate::plugins::respawn_plugin::RespawnPlugin), and the game plays on once
+//!   they arrive.
+//! - [`GameState::GameOver`] when the last life is lost, see
+//!   [`LivesPlugin`](crate::plugins::lives_plugin::LivesPlugin).
+//! - [`GameState::Paused`] stops the game while playing, see
+//!   [`PausePlugin`](crate::plugins::pause_plugin::PausePlugin).
+//!
+//! The win and fall messages are [`StateScoped`], so they're despawned when their state is left
+//! and a goal entered twice doesn't show the message twice. Controls like steering the ball or
+//! moving the camera should only run in [`GameState::Playing`], e.g. with the [`playing`]
+//! condition, which also holds when there's no [`GameState`].
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    checkpoint_plugin::RestartPosition,
+    goal_plugin::{GoalPlugin, GoalReached},
+    kill_volume_plugin::BallFell,
+    level_manifest_plugin::UnloadLevel,
+    loading_screen_plugin::AssetsLoaded,
+    mesh_physics_plugin::PhysicsReady,
+    respawn_plugin::{RespawnCountdown, Respawning},
+    spawn_point_plugin::PlayerStart,
+};
+
+pub struct GameStatePlugin;
+
+impl Plugin for GameStatePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<GoalPlugin>() {
+            app.add_plugins(GoalPlugin::default());
+        }
+        app.init_state::<GameState>()
+            .enable_state_scoped_entities::<GameState>()
+            .add_event::<PhysicsReady>()
+            .add_event::<BallFell>()
+            .add_event::<UnloadLevel>()
+            .add_systems(OnEnter(GameState::Won), spawn_won_message)
+            .add_systems(OnEnter(GameState::Fell), spawn_fell_message)
+            .add_systems(
+                Update,
+                (
+                    finish_loading.run_if(in_state(GameState::Loading)),
+                    (win_level, fall).run_if(in_state(GameState::Playing)),
+                    restart.run_if(in_state(GameState::Fell)),
+                    unload_level,
+                ),
+            )
+            .add_observer(finish_respawn);
+    }
+}
+
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GameState {
+    /// The level's colliders are being inserted.
+    #[default]
+    Loading,
+    Playing,
+    /// The ball was lost and waits for a restart.
+    Fell,
+    Won,
+    /// The player is out of lives.
+    GameOver,
+    Paused,
+}
+
+/// A run condition for the gameplay that only runs while playing. It also holds without a
+/// [`GameStatePlugin`], so plugins using it work without one.
+pub fn playing(state: Option<Res<State<GameState>>>) -> bool {
+    state.is_none_or(|state| *state.get() == GameState::Playing)
+}
+
+fn finish_loading(
+    mut physics_ready: EventReader<PhysicsReady>,
+    // Whether a `PhysicsReady` was read since the last time loading finished.
+    mut colliders_inserted: Local<bool>,
+    assets_loaded: Option<Res<AssetsLoaded>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if physics_ready.read().count() > 0 {
+        *colliders_inserted = true;
+    }
+    if *colliders_inserted && assets_loaded.is_none_or(|loaded| loaded.0) {
+        *colliders_inserted = false;
+        next_state.set(GameState::Playing);
+    }
+}
+
+fn win_level(
+    mut goal_reached: EventReader<GoalReached>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if goal_reached.read().count() > 0 {
+        next_state.set(GameState::Won);
+    }
+}
+
+fn fall(mut ball_fell: EventReader<BallFell>, mut next_state: ResMut<NextState<GameState>>) {
+    if ball_fell.read().count() > 0 {
+        next_state.set(GameState::Fell);
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn restart(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    player_start: Option<Res<PlayerStart>>,
+    mut balls: Query<
+        (
+            &mut Transform,
+            Option<&mut Velocity>,
+            Option<&RestartPosition>,
+        ),
+        (With<Ball>, Without<Respawning>, Without<RespawnCountdown>),
+    >,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyR) {
+        return;
+    }
+
+    let start = player_start.and_then(|player_start| player_start.0);
+    for (mut transform, velocity, restart_position) in balls.iter_mut() {
+        if let Some(mut velocity) = velocity {
+            *velocity = Velocity::zero();
+        }
+        match (restart_position, start) {
+            (Some(restart_position), _) => transform.translation = restart_position.0,
+            (None, Some(start)) => transform.translation = start.translation,
+            (None, None) => warn!("There's no start to put the ball back at."),
+        }
+    }
+    next_state.set(GameState::Playing);
+}
+
+fn unload_level(
+    mut unload: EventReader<UnloadLevel>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if unload.read().count() > 0 {
+        next_state.set(GameState::Loading);
+    }
+}
+
+/// Plays on once a fallen ball is back, so respawning doesn't wait for `R`.
+fn finish_respawn(
+    _trigger: Trigger<OnRemove, Respawning>,
+    state: Option<Res<State<GameState>>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if state.is_some_and(|state| *state.get() == GameState::Fell) {
+        next_state.set(GameState::Playing);
+    }
+}
+
+fn spawn_won_message(mut commands: Commands) {
+    spawn_message(&mut commands, GameState::Won, "You Win!");
+}
+
+fn spawn_fell_message(mut commands: Commands) {
+    spawn_message(
+        &mut commands,
+        GameState::Fell,
+        "You fell! Press R to try again",
+    );
+}
+
+fn spawn_message(commands: &mut Commands, state: GameState, message: &str) {
+    commands
+        .spawn((
+            StateScoped(state),
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            Pickable::IGNORE,
+        ))
+        .with_child((
+            Text::new(message),
+            TextFont {
+                font_size: 48.0,
+                ..default()
+            },
+        ));
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::plugins::{
+        goal_plugin::GOAL_TRIGGER,
+        trigger_volume_plugin::{TriggerEntered, TriggerVolume},
+    };
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, GameStatePlugin))
+            .init_resource::<ButtonInput<KeyCode>>();
+        app.update();
+        app
+    }
+
+    /// Runs the frame sending the next state, and the one entering it.
+    fn step(app: &mut App) {
+        app.update();
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .clear();
+        app.update();
+    }
+
+    fn state(app: &App) -> GameState {
+        *app.world().resource::<State<GameState>>().get()
+    }
+
+    fn messages(app: &mut App) -> usize {
+        app.world_mut()
+            .query_filtered::<(), With<StateScoped<GameState>>>()
+            .iter(app.world())
+            .count()
+    }
+
+    fn play(app: &mut App) {
+        app.world_mut().send_event(PhysicsReady {
+            scene: Entity::PLACEHOLDER,
+            colliders_inserted: 0,
+        });
+        step(app);
+    }
+
+    fn fall_with(app: &mut App, ball: Entity) {
+        app.world_mut().send_event(BallFell {
+            ball,
+            respawn: None,
+        });
+        step(app);
+    }
+
+    #[test]
+    fn plays_once_the_physics_is_ready() {
+        let mut app = app();
+        assert_eq!(state(&app), GameState::Loading);
+        // Controls don't run while loading.
+        assert!(!app.world_mut().run_system_cached(playing).unwrap());
+
+        play(&mut app);
+        assert_eq!(state(&app), GameState::Playing);
+        assert!(app.world_mut().run_system_cached(playing).unwrap());
+    }
+
+    #[test]
+    fn waits_for_the_assets_to_load() {
+        let mut app = app();
+        app.insert_resource(AssetsLoaded(false));
+
+        play(&mut app);
+        assert_eq!(state(&app), GameState::Loading);
+
+        app.insert_resource(AssetsLoaded(true));
+        step(&mut app);
+        assert_eq!(state(&app), GameState::Playing);
+    }
+
+    #[test]
+    fn restarts_a_fallen_ball_with_r() {
+        let mut app = app();
+        let ball = app
+            .world_mut()
+            .spawn((
+                Ball { radius: 0.5 },
+                Transform::from_xyz(0.0, -50.0, 0.0),
+                RestartPosition(Vec3::new(1.0, 2.0, 3.0)),
+            ))
+            .id();
+        play(&mut app);
+
+        fall_with(&mut app, ball);
+        assert_eq!(state(&app), GameState::Fell);
+        assert_eq!(messages(&mut app), 1);
+
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyR);
+        step(&mut app);
+        assert_eq!(state(&app), GameState::Playing);
+        assert_eq!(messages(&mut app), 0);
+        assert_eq!(
+            app.world().get::<Transform>(ball).unwrap().translation,
+            Vec3::new(1.0, 2.0, 3.0)
+        );
+    }
+
+    #[test]
+    fn plays_on_once_a_respawn_finishes() {
+        let mut app = app();
+        let ball = app
+            .world_mut()
+            .spawn((Ball { radius: 0.5 }, Transform::default()))
+            .id();
+        play(&mut app);
+
+        app.world_mut()
+            .entity_mut(ball)
+            .insert(Respawning::new(Vec3::Y, 1.0));
+        fall_with(&mut app, ball);
+        assert_eq!(state(&app), GameState::Fell);
+
+        app.world_mut().entity_mut(ball).remove::<Respawning>();
+        step(&mut app);
+        assert_eq!(state(&app), GameState::Playing);
+        assert_eq!(messages(&mut app), 0);
+    }
+
+    #[test]
+    fn leaves_counting_down_balls_to_the_respawn() {
+        let mut app = app();
+        let ball = app
+            .world_mut()
+            .spawn((
+                Ball { radius: 0.5 },
+                Transform::from_xyz(0.0, -50.0, 0.0),
+                RestartPosition(Vec3::ZERO),
+            ))
+            .id();
+        play(&mut app);
+
+        fall_with(&mut app, ball);
+        app.world_mut().entity_mut(ball).insert(RespawnCountdown {
+            remaining: 3.0,
+            target: None,
+        });
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyCode::KeyR);
+        step(&mut app);
+        assert_eq!(
+            app.world().get::<Transform>(ball).unwrap().translation,
+            Vec3::new(0.0, -50.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn loads_again_when_the_level_is_unloaded() {
+        let mut app = app();
+        play(&mut app);
+        assert_eq!(state(&app), GameState::Playing);
+
+        app.world_mut().send_event(UnloadLevel);
+        step(&mut app);
+        assert_eq!(state(&app), GameState::Loading);
+        play(&mut app);
+        assert_eq!(state(&app), GameState::Playing);
+    }
+
+    #[test]
+    fn wins_once() {
+        let mut app = app();
+        let ball = app.world_mut().spawn(Ball { radius: 0.5 }).id();
+        let goal = app
+            .world_mut()
+            .spawn(TriggerVolume {
+                name: GOAL_TRIGGER.to_string(),
+            })
+            .id();
+        play(&mut app);
+
+        for _ in 0..2 {
+            app.world_mut().send_event(TriggerEntered {
+                entity: ball,
+                volume: goal,
+                trigger_name: GOAL_TRIGGER.to_string(),
+            });
+            // The frame the goal sends its `GoalReached`.
+            app.update();
+            step(&mut app);
+            assert_eq!(state(&app), GameState::Won);
+            assert_eq!(messages(&mut app), 1);
+        }
+
+        // Falling after winning doesn't count.
+        fall_with(&mut app, ball);
+        assert_eq!(state(&app), GameState::Won);
+    }
+}
@@ -0,0 +1,341 @@
+//! Turns the `trigger_Goal_*` volumes of the
+//! [`TriggerVolumePlugin`](crate::plugins::trigger_volume_plugin::TriggerVolumePlugin) into
+//! [`Goal`]s that send a [`GoalReached`] event when a [`Ball`] enters them. Gameplay reacting to
+//! goals, like a win screen, listens for it instead of the trigger, so locked goals are ignored
+//! everywhere.
+//!
+//! A goal is locked until its [`GoalCondition`] is met, which is checked every frame: the [`Score`]
+//! has to be at least `required_score`, and all the `required_checkpoints` have to be activated.
+//! Goals get an empty condition when their trigger volume is inserted, unless they already have a
+//! [`Goal`], so levels set the conditions by inserting the goal themselves. The meshes of locked
+//! goals are shown in [`GoalConfig::locked_color`], and switch to [`GoalConfig::unlocked_color`]
+//! once they unlock.
+//!
+//! Goals with a [`GoalRotationSpeed`] spin around their up axis, e.g. `GoalRotationSpeed(0.0)`
+//! stops one goal while the others keep spinning. Goals without one don't rotate.
+
+use bevy::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    checkpoint_plugin::Checkpoint,
+    game_state_plugin::playing,
+    save_game_plugin::Score,
+    trigger_volume_plugin::{TriggerEntered, TriggerVolume},
+};
+
+/// The name of the trigger volumes that are goals.
+pub const GOAL_TRIGGER: &str = "Goal";
+
+#[derive(Default)]
+pub struct GoalPlugin {
+    pub config: GoalConfig,
+}
+
+impl Plugin for GoalPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone())
+            .add_event::<TriggerEntered>()
+            .add_event::<GoalReached>()
+            .add_systems(
+                Update,
+                (
+                    (check_goal_unlock, detect_goal.run_if(playing)).chain(),
+                    rotate_goals,
+                ),
+            )
+            .add_observer(insert_goals);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct GoalConfig {
+    pub locked_color: Color,
+    pub unlocked_color: Color,
+}
+
+impl Default for GoalConfig {
+    fn default() -> Self {
+        Self {
+            locked_color: Color::srgb(0.5, 0.5, 0.5),
+            unlocked_color: Color::srgb(1.0, 0.84, 0.0),
+        }
+    }
+}
+
+#[derive(Component, Clone, Debug, Default)]
+pub struct Goal {
+    pub condition: GoalCondition,
+    /// Whether the condition is met, updated every frame.
+    pub unlocked: bool,
+}
+
+/// What has to be done before a [`Goal`] can be reached. The default condition is always met.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GoalCondition {
+    pub required_score: Option<u32>,
+    pub required_checkpoints: Vec<Entity>,
+}
+
+impl GoalCondition {
+    /// Whether the condition is met with the score, given whether a checkpoint is activated.
+    pub fn is_met(&self, score: u32, activated: impl Fn(Entity) -> bool) -> bool {
+        self.required_score.is_none_or(|required| score >= required)
+            && self
+                .required_checkpoints
+                .iter()
+                .all(|&checkpoint| activated(checkpoint))
+    }
+}
+
+/// How fast a [`Goal`] spins around its up axis, in radians per second.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct GoalRotationSpeed(pub f32);
+
+/// Sent when a ball enters an unlocked [`Goal`].
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub struct GoalReached {
+    pub goal: Entity,
+    pub ball: Entity,
+}
+
+fn insert_goals(
+    trigger: Trigger<OnAdd, TriggerVolume>,
+    mut commands: Commands,
+    volumes: Query<&TriggerVolume>,
+) {
+    let Ok(volume) = volumes.get(trigger.target()) else {
+        return;
+    };
+    if volume.name == GOAL_TRIGGER {
+        commands
+            .entity(trigger.target())
+            .insert_if_new(Goal::default());
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn check_goal_unlock(
+    config: Res<GoalConfig>,
+    score: Option<Res<Score>>,
+    mut materials: Option<ResMut<Assets<StandardMaterial>>>,
+    // The locked and unlocked materials, created with the first goal.
+    mut goal_materials: Local<Option<[Handle<StandardMaterial>; 2]>>,
+    checkpoints: Query<&Checkpoint>,
+    children: Query<&Children>,
+    mut meshes: Query<&mut MeshMaterial3d<StandardMaterial>>,
+    mut goals: Query<(Entity, &mut Goal)>,
+) {
+    let score = score.map_or(0, |score| score.0);
+    for (entity, mut goal) in goals.iter_mut() {
+        let unlocked = goal.condition.is_met(score, |checkpoint| {
+            checkpoints
+                .get(checkpoint)
+                .is_ok_and(|checkpoint| checkpoint.activated)
+        });
+        if unlocked == goal.unlocked && !goal.is_added() {
+            continue;
+        }
+        goal.unlocked = unlocked;
+
+        let Some(materials) = materials.as_deref_mut() else {
+            continue;
+        };
+        let [locked_material, unlocked_material] = goal_materials.get_or_insert_with(|| {
+            [
+                materials.add(StandardMaterial::from_color(config.locked_color)),
+                materials.add(StandardMaterial::from_color(config.unlocked_color)),
+            ]
+        });
+        let material = if unlocked {
+            unlocked_material
+        } else {
+            locked_material
+        };
+        for mesh in std::iter::once(entity).chain(children.iter_descendants(entity)) {
+            if let Ok(mut mesh_material) = meshes.get_mut(mesh) {
+                mesh_material.0 = material.clone();
+            }
+        }
+    }
+}
+
+fn rotate_goals(
+    time: Res<Time>,
+    mut goals: Query<(&mut Transform, &GoalRotationSpeed), With<Goal>>,
+) {
+    for (mut transform, speed) in goals.iter_mut() {
+        transform.rotate_local_y(speed.0 * time.delta_secs());
+    }
+}
+
+fn detect_goal(
+    mut trigger_entered: EventReader<TriggerEntered>,
+    mut goal_reached: EventWriter<GoalReached>,
+    goals: Query<&Goal>,
+    balls: Query<(), With<Ball>>,
+) {
+    for event in trigger_entered.read() {
+        if event.trigger_name != GOAL_TRIGGER || !balls.contains(event.entity) {
+            continue;
+        }
+        let Ok(goal) = goals.get(event.volume) else {
+            continue;
+        };
+        if !goal.unlocked {
+            info!("The goal is still locked.");
+            continue;
+        }
+        goal_reached.write(GoalReached {
+            goal: event.volume,
+            ball: event.entity,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::time::TimeUpdateStrategy;
+
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Reached(Vec<GoalReached>);
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            GoalPlugin::default(),
+        ))
+        .init_asset::<StandardMaterial>()
+        .init_resource::<Score>()
+        .init_resource::<Reached>()
+        .add_systems(
+            PostUpdate,
+            |mut goal_reached: EventReader<GoalReached>, mut reached: ResMut<Reached>| {
+                reached.0.extend(goal_reached.read().copied());
+            },
+        );
+        app
+    }
+
+    fn enter(app: &mut App, goal: Entity, ball: Entity) {
+        app.world_mut().send_event(TriggerEntered {
+            entity: ball,
+            volume: goal,
+            trigger_name: GOAL_TRIGGER.to_string(),
+        });
+        app.update();
+    }
+
+    fn material_color(app: &App, mesh: Entity) -> Color {
+        let handle = &app
+            .world()
+            .get::<MeshMaterial3d<StandardMaterial>>(mesh)
+            .unwrap()
+            .0;
+        app.world()
+            .resource::<Assets<StandardMaterial>>()
+            .get(handle)
+            .unwrap()
+            .base_color
+    }
+
+    #[test]
+    fn goal_triggers_are_unlocked_goals() {
+        let mut app = app();
+        let ball = app.world_mut().spawn(Ball { radius: 0.5 }).id();
+        let goal = app
+            .world_mut()
+            .spawn(TriggerVolume {
+                name: GOAL_TRIGGER.to_string(),
+            })
+            .id();
+        app.update();
+        assert!(app.world().get::<Goal>(goal).unwrap().unlocked);
+
+        enter(&mut app, goal, ball);
+        assert_eq!(
+            app.world().resource::<Reached>().0,
+            [GoalReached { goal, ball }]
+        );
+    }
+
+    #[test]
+    fn locked_goals_open_once_the_condition_is_met() {
+        let mut app = app();
+        let ball = app.world_mut().spawn(Ball { radius: 0.5 }).id();
+        let checkpoint = app.world_mut().spawn(Checkpoint::default()).id();
+        let goal = app
+            .world_mut()
+            .spawn((
+                Goal {
+                    condition: GoalCondition {
+                        required_score: Some(5),
+                        required_checkpoints: vec![checkpoint],
+                    },
+                    ..default()
+                },
+                MeshMaterial3d::<StandardMaterial>::default(),
+            ))
+            .id();
+        let config = GoalConfig::default();
+
+        app.world_mut().resource_mut::<Score>().0 = 5;
+        enter(&mut app, goal, ball);
+        assert!(app.world().resource::<Reached>().0.is_empty());
+        assert_eq!(material_color(&app, goal), config.locked_color);
+
+        app.world_mut()
+            .get_mut::<Checkpoint>(checkpoint)
+            .unwrap()
+            .activated = true;
+        enter(&mut app, goal, ball);
+        assert_eq!(
+            app.world().resource::<Reached>().0,
+            [GoalReached { goal, ball }]
+        );
+        assert_eq!(material_color(&app, goal), config.unlocked_color);
+    }
+
+    #[test]
+    fn goals_rotate_at_their_own_speed() {
+        let mut app = app();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )));
+        let spinning = app
+            .world_mut()
+            .spawn((
+                Goal::default(),
+                GoalRotationSpeed(5.0),
+                Transform::default(),
+            ))
+            .id();
+        let stopped = app
+            .world_mut()
+            .spawn((
+                Goal::default(),
+                GoalRotationSpeed(0.0),
+                Transform::default(),
+            ))
+            .id();
+        let still = app
+            .world_mut()
+            .spawn((Goal::default(), Transform::default()))
+            .id();
+        // The first update doesn't advance the time.
+        app.update();
+        app.update();
+        app.update();
+
+        let rotation = |entity| app.world().get::<Transform>(entity).unwrap().rotation;
+        assert!(rotation(spinning).angle_between(Quat::from_rotation_y(1.0)) < 1e-4);
+        assert_eq!(rotation(stopped), Quat::IDENTITY);
+        assert_eq!(rotation(still), Quat::IDENTITY);
+    }
+}
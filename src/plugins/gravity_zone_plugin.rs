@@ -0,0 +1,310 @@
+//! Turns glTF meshes named `gravity_*` into zones that change the gravity of the balls inside
+//! them, e.g. for upside-down segments or zero-G bubbles, without changing rapier's global
+//! gravity. The zones stay visible, and their sensors are built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline.
+//! The zone's extra acceleration is read from the glTF extras of the mesh or its parent:
+//! - `gravity_direction`: an array of three numbers, up by default.
+//! - `gravity_magnitude`: a number, 9.81 by default, which cancels the normal gravity.
+//!
+//! A ball in a zone gets a force added to its [`ExternalForce`] that gives it that acceleration,
+//! and the force is removed when it leaves.
+//!
+//! A zone whose name starts with a number, e.g. `gravity_0.3_Moon` or `gravity_-1_CeilingRoom`,
+//! sets the [`GravityScale`] of the balls inside it instead, and the scale goes back to 1 when
+//! they leave. In overlapping scale zones, the one entered last wins. With a `nobottom` token,
+//! e.g. `gravity_-1_nobottom_CeilingRoom`, falling below the bottom isn't checked in the zone,
+//! since the ball falls up there. A restart or an unloaded level resets the scales.
+
+use bevy::{gltf::GltfExtras, prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    checkpoint_plugin::ResetCheckpoints,
+    kill_volume_plugin::SkipOutOfBounds,
+    level_manifest_plugin::UnloadLevel,
+    mesh_physics_plugin::{ObjectCollider, SensorTriggered, register_object_collider},
+};
+
+const PREFIX: &str = "gravity_";
+
+pub struct GravityZonePlugin;
+
+impl Plugin for GravityZonePlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                hide_mesh: false,
+                ..ObjectCollider::sensor(PREFIX)
+            },
+        );
+        app.add_event::<ResetCheckpoints>()
+            .add_event::<UnloadLevel>()
+            .add_systems(
+                Update,
+                (
+                    (track_zone_contacts, apply_zone_gravity).chain(),
+                    (
+                        track_scale_zone_contacts,
+                        reset_gravity_scales,
+                        apply_gravity_scales,
+                    )
+                        .chain(),
+                ),
+            )
+            .add_observer(insert_gravity_zones);
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct GravityZone {
+    pub direction: Vec3,
+    /// The acceleration added in the direction.
+    pub magnitude: f32,
+}
+
+impl Default for GravityZone {
+    fn default() -> Self {
+        Self {
+            direction: Vec3::Y,
+            magnitude: 9.81,
+        }
+    }
+}
+
+impl GravityZone {
+    /// Parses the zone from the extras JSON. Returns an error describing the first malformed
+    /// value.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        let mut zone = Self::default();
+
+        if let Some(direction) = value.get("gravity_direction") {
+            let components: Option<Vec<f32>> = direction.as_array().and_then(|array| {
+                array
+                    .iter()
+                    .map(|value| value.as_f64().map(|value| value as f32))
+                    .collect()
+            });
+            match components.as_deref() {
+                Some(&[x, y, z]) => zone.direction = Vec3::new(x, y, z),
+                _ => {
+                    return Err(format!(
+                        "`gravity_direction` should be an array of 3 numbers, got {direction}"
+                    ));
+                }
+            }
+        }
+        if let Some(magnitude) = value.get("gravity_magnitude") {
+            zone.magnitude = magnitude
+                .as_f64()
+                .ok_or_else(|| format!("`gravity_magnitude` should be a number, got {magnitude}"))?
+                as f32;
+        }
+
+        Ok(zone)
+    }
+
+    /// The acceleration of the zone.
+    pub fn acceleration(&self) -> Vec3 {
+        self.direction.normalize_or_zero() * self.magnitude
+    }
+}
+
+/// A zone created from a `gravity_<scale>_` mesh, which sets the [`GravityScale`] of the balls
+/// inside it.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct GravityScaleZone {
+    pub scale: f32,
+    /// Whether falling below the bottom isn't checked inside it.
+    pub skip_bottom: bool,
+}
+
+/// Returns the scale zone of a mesh name like `gravity_-1_nobottom_CeilingRoom`, if it starts
+/// with a number.
+pub fn parse_gravity_scale(name: &str) -> Option<GravityScaleZone> {
+    let mut tokens = name.strip_prefix(PREFIX)?.split('_');
+    let scale = tokens.next()?.parse().ok()?;
+    Some(GravityScaleZone {
+        scale,
+        skip_bottom: tokens.any(|token| token == "nobottom"),
+    })
+}
+
+/// The scale zones a ball is in, in the order they were entered.
+#[derive(Component, Default)]
+struct InScaleZones(Vec<Entity>);
+
+/// The zones a ball is in and the force they currently add to its [`ExternalForce`].
+#[derive(Component, Default)]
+struct InGravityZones {
+    zones: Vec<Entity>,
+    applied: Vec3,
+}
+
+fn insert_gravity_zones(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf), With<Mesh3d>>,
+    extras_query: Query<&GltfExtras>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of)) = query.get(entity) else {
+            continue;
+        };
+        if !name.starts_with(PREFIX) {
+            continue;
+        }
+
+        if let Some(scale_zone) = parse_gravity_scale(name) {
+            commands.entity(child_of.parent()).insert(scale_zone);
+            continue;
+        }
+
+        let zone = extras_query
+            .get(entity)
+            .or_else(|_| extras_query.get(child_of.parent()))
+            .map_or(Ok(GravityZone::default()), |extras| {
+                GravityZone::parse(&extras.value)
+            })
+            .unwrap_or_else(|err| {
+                warn!("Skipping the extras of `{name}`: {err}");
+                GravityZone::default()
+            });
+        commands.entity(child_of.parent()).insert(zone);
+    }
+}
+
+fn track_zone_contacts(
+    mut commands: Commands,
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    zones: Query<(), With<GravityZone>>,
+    mut balls: Query<Option<&mut InGravityZones>, With<Ball>>,
+) {
+    for event in sensor_triggered.read() {
+        let zone = event.sensor;
+        if !zones.contains(zone) {
+            continue;
+        }
+        let Ok(in_zones) = balls.get_mut(event.other) else {
+            continue;
+        };
+
+        match (in_zones, event.started) {
+            (Some(mut in_zones), true) => in_zones.zones.push(zone),
+            (Some(mut in_zones), false) => in_zones.zones.retain(|&other| other != zone),
+            // The mass is needed to turn the acceleration into a force.
+            (None, true) => {
+                commands.entity(event.other).insert((
+                    InGravityZones {
+                        zones: vec![zone],
+                        applied: Vec3::ZERO,
+                    },
+                    ReadMassProperties::default(),
+                ));
+            }
+            (None, false) => {}
+        }
+    }
+}
+
+fn apply_zone_gravity(
+    zones: Query<&GravityZone>,
+    mut balls: Query<(
+        &mut InGravityZones,
+        &mut ExternalForce,
+        Option<&ReadMassProperties>,
+    )>,
+) {
+    for (mut in_zones, mut force, mass) in balls.iter_mut() {
+        // Zones that were despawned don't send a stop event.
+        in_zones.zones.retain(|&zone| zones.contains(zone));
+
+        // The mass is zero until rapier has written it, so the force waits for it.
+        let Some(mass) = mass.map(|mass| mass.get().mass).filter(|&mass| mass > 0.0) else {
+            continue;
+        };
+        let zone_force: Vec3 = in_zones
+            .zones
+            .iter()
+            .filter_map(|&zone| zones.get(zone).ok())
+            .map(|zone| zone.acceleration() * mass)
+            .sum();
+
+        force.force += zone_force - in_zones.applied;
+        in_zones.applied = zone_force;
+    }
+}
+
+fn track_scale_zone_contacts(
+    mut commands: Commands,
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    zones: Query<(), With<GravityScaleZone>>,
+    mut balls: Query<Option<&mut InScaleZones>, With<Ball>>,
+) {
+    for event in sensor_triggered.read() {
+        let zone = event.sensor;
+        if !zones.contains(zone) {
+            continue;
+        }
+        let Ok(in_zones) = balls.get_mut(event.other) else {
+            continue;
+        };
+
+        match (in_zones, event.started) {
+            (Some(mut in_zones), true) => in_zones.0.push(zone),
+            (Some(mut in_zones), false) => in_zones.0.retain(|&other| other != zone),
+            (None, true) => {
+                commands
+                    .entity(event.other)
+                    .insert(InScaleZones(vec![zone]));
+            }
+            (None, false) => {}
+        }
+    }
+}
+
+fn reset_gravity_scales(
+    mut reset_checkpoints: EventReader<ResetCheckpoints>,
+    mut unload: EventReader<UnloadLevel>,
+    mut balls: Query<&mut InScaleZones>,
+) {
+    if reset_checkpoints.read().count() + unload.read().count() == 0 {
+        return;
+    }
+
+    for mut in_zones in balls.iter_mut() {
+        in_zones.0.clear();
+    }
+}
+
+fn apply_gravity_scales(
+    mut commands: Commands,
+    zones: Query<&GravityScaleZone>,
+    mut balls: Query<(
+        Entity,
+        &mut InScaleZones,
+        Option<&GravityScale>,
+        Has<SkipOutOfBounds>,
+    )>,
+) {
+    for (ball, mut in_zones, gravity_scale, skipping) in balls.iter_mut() {
+        // Zones that were despawned don't send a stop event.
+        in_zones.0.retain(|&zone| zones.contains(zone));
+
+        let zone = in_zones.0.last().and_then(|&zone| zones.get(zone).ok());
+        let scale = zone.map_or(1.0, |zone| zone.scale);
+        if gravity_scale.is_none_or(|gravity_scale| gravity_scale.0 != scale) {
+            commands.entity(ball).insert(GravityScale(scale));
+        }
+
+        let skip = zone.is_some_and(|zone| zone.skip_bottom);
+        if skip && !skipping {
+            commands.entity(ball).insert(SkipOutOfBounds);
+        } else if !skip && skipping {
+            commands.entity(ball).remove::<SkipOutOfBounds>();
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use bevy::prelude::*;
+
+pub struct HealthPlugin;
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<DamageEvent>()
+            .add_event::<DeathEvent>()
+            .add_systems(Update, (apply_damage, update_health_bars).chain());
+    }
+}
+
+#[derive(Component)]
+pub struct Health {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Health {
+    pub fn new(max: f32) -> Self {
+        Self { current: max, max }
+    }
+
+    pub fn fraction(&self) -> f32 {
+        if self.max > 0.0 {
+            (self.current / self.max).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Removes `amount` of health from the target. Negative amounts heal.
+#[derive(Event)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: f32,
+}
+
+/// Sent once when an entity's health reaches zero.
+#[derive(Event)]
+pub struct DeathEvent {
+    pub entity: Entity,
+}
+
+/// A UI node whose width shows the health of the target.
+#[derive(Component)]
+pub struct HealthBar {
+    pub target: Entity,
+}
+
+fn apply_damage(
+    mut damage_events: EventReader<DamageEvent>,
+    mut death_events: EventWriter<DeathEvent>,
+    mut query: Query<&mut Health>,
+) {
+    for event in damage_events.read() {
+        let Ok(mut health) = query.get_mut(event.target) else {
+            continue;
+        };
+
+        let was_alive = health.current > 0.0;
+        health.current = (health.current - event.amount).clamp(0.0, health.max);
+
+        if was_alive && health.current <= 0.0 {
+            death_events.write(DeathEvent {
+                entity: event.target,
+            });
+        }
+    }
+}
+
+fn update_health_bars(
+    healths: Query<&Health, Changed<Health>>,
+    mut bars: Query<(&HealthBar, &mut Node)>,
+) {
+    for (bar, mut node) in bars.iter_mut() {
+        if let Ok(health) = healths.get(bar.target) {
+            node.width = Val::Percent(health.fraction() * 100.0);
+        }
+    }
+}
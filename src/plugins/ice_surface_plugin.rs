@@ -0,0 +1,103 @@
+//! Turns glTF meshes named `ice_*` into slippery colliders.
+//! The meshes get physics like the ones of [`MeshPhysicsPlugin`] with the `ice_` prefix, so the
+//! same name tokens work, and their parents are marked with [`IceSurface`]. Their colliders are
+//! a [`DampingSurface::Slippery`] once they're inserted, so while a body touches ice its linear damping is swapped for
+//! [`IceSurfaceConfig::linear_damping`] by the surface damping of [`MeshPhysicsPlugin`], which
+//! restores the original damping when it leaves the last surface.
+
+use bevy::{prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::mesh_physics_plugin::{DampingSurface, MeshPhysicsPlugin};
+
+#[derive(Default)]
+pub struct IceSurfacePlugin {
+    pub config: IceSurfaceConfig,
+}
+
+impl Plugin for IceSurfacePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(MeshPhysicsPlugin {
+            prefix: "ice_".to_string(),
+            friction: Some(self.config.friction),
+            ..default()
+        })
+        .insert_resource(self.config.clone())
+        .add_observer(mark_ice_surfaces)
+        .add_observer(make_ice_slippery);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct IceSurfaceConfig {
+    pub friction: f32,
+    /// The linear damping of a body on ice.
+    pub linear_damping: f32,
+    /// A color the ice meshes are tinted with so players can see them, or `None` to keep their
+    /// materials.
+    pub tint: Option<Color>,
+}
+
+impl Default for IceSurfaceConfig {
+    fn default() -> Self {
+        Self {
+            friction: 0.02,
+            linear_damping: 0.01,
+            tint: Some(Color::linear_rgb(0.6, 0.85, 1.0)),
+        }
+    }
+}
+
+/// A collider made from an `ice_` mesh.
+#[derive(Component)]
+pub struct IceSurface;
+
+#[allow(clippy::type_complexity)]
+fn mark_ice_surfaces(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    config: Res<IceSurfaceConfig>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf, Option<&MeshMaterial3d<StandardMaterial>>), With<Mesh3d>>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of, material)) = query.get(entity) else {
+            continue;
+        };
+        if !name.starts_with("ice_") {
+            continue;
+        }
+
+        commands.entity(child_of.parent()).insert(IceSurface);
+
+        // Copied so other meshes sharing the material aren't tinted too.
+        let (Some(tint), Some(material)) = (
+            config.tint,
+            material.and_then(|material| materials.get(&material.0)),
+        ) else {
+            continue;
+        };
+        let mut material = material.clone();
+        material.base_color = tint;
+        material.perceptual_roughness = 0.1;
+        commands
+            .entity(entity)
+            .insert(MeshMaterial3d(materials.add(material)));
+    }
+}
+
+/// Adds the slippery damping to the ice colliders, also when they're rebuilt.
+fn make_ice_slippery(
+    trigger: Trigger<OnInsert, Collider>,
+    mut commands: Commands,
+    config: Res<IceSurfaceConfig>,
+    surfaces: Query<(), With<IceSurface>>,
+) {
+    if !surfaces.contains(trigger.target()) {
+        return;
+    }
+    commands
+        .entity(trigger.target())
+        .insert(DampingSurface::Slippery(config.linear_damping));
+}
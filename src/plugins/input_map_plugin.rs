@@ -0,0 +1,242 @@
+//! Maps [`GameAction`]s to keyboard keys, gamepad buttons and gamepad axes, so systems check
+//! actions instead of specific inputs, e.g. `action_map.just_pressed(GameAction::Jump)`.
+//!
+//! The bindings are loaded from [`InputMapPlugin::bindings_path`] at startup, falling back to the
+//! defaults, and are saved there whenever they're changed with [`ActionMap::rebind`].
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use bevy::{input::InputSystem, prelude::*};
+use serde::{Deserialize, Serialize};
+
+pub struct InputMapPlugin {
+    /// The JSON file the bindings are loaded from and saved to.
+    pub bindings_path: PathBuf,
+}
+
+impl Default for InputMapPlugin {
+    fn default() -> Self {
+        Self {
+            bindings_path: PathBuf::from("settings/bindings.json"),
+        }
+    }
+}
+
+impl Plugin for InputMapPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ActionMap::load_or_default(&self.bindings_path))
+            .insert_resource(BindingsPath(self.bindings_path.clone()))
+            .add_systems(PreUpdate, update_actions.after(InputSystem))
+            .add_systems(Update, save_bindings);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum GameAction {
+    MoveForward,
+    MoveBack,
+    MoveLeft,
+    MoveRight,
+    Jump,
+    Restart,
+    Boost,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum InputSource {
+    Key(KeyCode),
+    GamepadButton(GamepadButton),
+    /// Active while the axis is past the threshold. A negative threshold is for the negative
+    /// direction of the axis.
+    GamepadAxis {
+        axis: GamepadAxis,
+        threshold: f32,
+    },
+}
+
+impl InputSource {
+    fn is_active(&self, keyboard: &ButtonInput<KeyCode>, gamepads: &Query<&Gamepad>) -> bool {
+        match *self {
+            Self::Key(key) => keyboard.pressed(key),
+            Self::GamepadButton(button) => gamepads.iter().any(|gamepad| gamepad.pressed(button)),
+            Self::GamepadAxis { axis, threshold } => gamepads.iter().any(|gamepad| {
+                gamepad.get(axis).is_some_and(|value| {
+                    if threshold < 0.0 {
+                        value <= threshold
+                    } else {
+                        value >= threshold
+                    }
+                })
+            }),
+        }
+    }
+}
+
+/// The bindings of the [`GameAction`]s and their state in the current frame.
+#[derive(Resource, Clone, Serialize, Deserialize)]
+pub struct ActionMap {
+    bindings: BTreeMap<GameAction, Vec<InputSource>>,
+    #[serde(skip)]
+    input: ButtonInput<GameAction>,
+    /// Whether the bindings changed since they were last saved.
+    #[serde(skip)]
+    changed: bool,
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        let key = InputSource::Key;
+        let button = InputSource::GamepadButton;
+        let stick = |axis, threshold| InputSource::GamepadAxis { axis, threshold };
+        let bindings = BTreeMap::from([
+            (
+                GameAction::MoveForward,
+                vec![
+                    key(KeyCode::KeyW),
+                    key(KeyCode::ArrowUp),
+                    stick(GamepadAxis::LeftStickY, 0.5),
+                ],
+            ),
+            (
+                GameAction::MoveBack,
+                vec![
+                    key(KeyCode::KeyS),
+                    key(KeyCode::ArrowDown),
+                    stick(GamepadAxis::LeftStickY, -0.5),
+                ],
+            ),
+            (
+                GameAction::MoveLeft,
+                vec![
+                    key(KeyCode::KeyA),
+                    key(KeyCode::ArrowLeft),
+                    stick(GamepadAxis::LeftStickX, -0.5),
+                ],
+            ),
+            (
+                GameAction::MoveRight,
+                vec![
+                    key(KeyCode::KeyD),
+                    key(KeyCode::ArrowRight),
+                    stick(GamepadAxis::LeftStickX, 0.5),
+                ],
+            ),
+            (
+                GameAction::Jump,
+                vec![key(KeyCode::Space), button(GamepadButton::South)],
+            ),
+            (
+                GameAction::Restart,
+                vec![key(KeyCode::KeyR), button(GamepadButton::Select)],
+            ),
+            (
+                GameAction::Boost,
+                vec![
+                    key(KeyCode::ShiftLeft),
+                    button(GamepadButton::RightTrigger2),
+                ],
+            ),
+        ]);
+
+        Self {
+            bindings,
+            input: ButtonInput::default(),
+            changed: false,
+        }
+    }
+}
+
+impl ActionMap {
+    /// Loads the bindings from a JSON file. Falls back to the defaults if the file doesn't exist
+    /// or can't be parsed, and the defaults are kept for actions missing from the file.
+    pub fn load_or_default(path: &Path) -> Self {
+        let mut map = Self::default();
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return map,
+            Err(err) => {
+                warn!("Failed to read the bindings from {}: {err}", path.display());
+                return map;
+            }
+        };
+
+        match serde_json::from_str::<Self>(&json) {
+            Ok(loaded) => map.bindings.extend(loaded.bindings),
+            Err(err) => warn!("Failed to parse the bindings in {}: {err}", path.display()),
+        }
+        map
+    }
+
+    pub fn pressed(&self, action: GameAction) -> bool {
+        self.input.pressed(action)
+    }
+
+    pub fn just_pressed(&self, action: GameAction) -> bool {
+        self.input.just_pressed(action)
+    }
+
+    pub fn just_released(&self, action: GameAction) -> bool {
+        self.input.just_released(action)
+    }
+
+    pub fn bindings(&self, action: GameAction) -> &[InputSource] {
+        self.bindings
+            .get(&action)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Replaces the bindings of an action. They're saved at the end of the frame.
+    pub fn rebind(&mut self, action: GameAction, sources: Vec<InputSource>) {
+        self.bindings.insert(action, sources);
+        self.changed = true;
+    }
+}
+
+#[derive(Resource)]
+struct BindingsPath(PathBuf);
+
+fn update_actions(
+    mut action_map: ResMut<ActionMap>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+) {
+    let action_map = &mut *action_map;
+    action_map.input.clear();
+    for (&action, sources) in &action_map.bindings {
+        if sources
+            .iter()
+            .any(|source| source.is_active(&keyboard, &gamepads))
+        {
+            action_map.input.press(action);
+        } else {
+            action_map.input.release(action);
+        }
+    }
+}
+
+fn save_bindings(mut action_map: ResMut<ActionMap>, path: Res<BindingsPath>) {
+    if !action_map.changed {
+        return;
+    }
+    action_map.changed = false;
+
+    let json = match serde_json::to_string_pretty(&*action_map) {
+        Ok(json) => json,
+        Err(err) => {
+            error!("Failed to serialize the bindings: {err}");
+            return;
+        }
+    };
+    let directory = path.0.parent().unwrap_or(Path::new(""));
+    if let Err(err) = std::fs::create_dir_all(directory) {
+        error!("Failed to create {}: {err}", directory.display());
+        return;
+    }
+    if let Err(err) = std::fs::write(&path.0, json) {
+        error!("Failed to save the bindings to {}: {err}", path.0.display());
+    }
+}
@@ -0,0 +1,338 @@
+//! Turns glTF meshes named `kill_*` into invisible death zones, e.g. lava pits or the sides of
+//! a level.
+//! When a [`Ball`] touches one, a [`BallFell`] event is sent. Other ways of losing the ball can
+//! send the same event so the fail flow is handled in one place. The zones' sensors are built by
+//! the [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline.
+//!
+//! Empties named `bottom_*` are the lowest heights of the sections of a level. Each covers the
+//! area of its X and Z scale around it, like a plane of size 2 in Blender. Bottoms can be stacked
+//! for tiered levels, e.g. `bottom_tier1` above a catch platform with `bottom_tier2` below it.
+//! Among the bottoms whose area a ball is in, it's checked against the highest one below where
+//! it last touched something solid, so it has fallen once it drops below the tier it was on, even
+//! if a lower tier's bottom is still below it. Above all of them, it's checked against the one
+//! nearest horizontally, and outside all the areas against the lowest bottom.
+//! The [`BallFell`] event carries the position of the `respawn_*` empty with the same suffix as
+//! that bottom, e.g. `respawn_Tower` for `bottom_Tower`. Balls with [`SkipOutOfBounds`] aren't
+//! checked.
+//!
+//! A level with a single section can name its empties just `bottom` and `respawn`.
+//!
+//! The `bottom` part of the names can be changed by inserting a [`BottomThresholdName`], e.g. if
+//! the artists already use `bottom` for other objects.
+
+use bevy::{platform::collections::HashMap, prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    game_state_plugin::playing,
+    mesh_physics_plugin::{ObjectCollider, SensorTriggered, register_object_collider},
+};
+
+const PREFIX: &str = "kill_";
+
+pub struct KillVolumePlugin;
+
+impl Plugin for KillVolumePlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(app, ObjectCollider::sensor(PREFIX));
+        app.init_resource::<BottomZones>()
+            .init_resource::<BottomThresholdName>()
+            .add_event::<BallFell>()
+            .add_systems(
+                Update,
+                (
+                    detect_kill_volumes,
+                    (track_grounded_height, detect_fall_below_bottom).chain(),
+                )
+                    .run_if(playing),
+            )
+            .add_observer(insert_kill_volumes)
+            .add_observer(collect_bottom_zones);
+    }
+}
+
+/// A sensor created from a `kill_` mesh.
+#[derive(Component)]
+pub struct KillVolume;
+
+/// Sent when a ball is lost.
+#[derive(Event)]
+pub struct BallFell {
+    pub ball: Entity,
+    /// Where the ball should respawn instead of its restart position, if anywhere.
+    pub respawn: Option<Vec3>,
+}
+
+/// The name of the empties that are bottoms, before the `_` and suffix, e.g. `oob_floor` for
+/// empties named `oob_floor` or `oob_floor_Tower`.
+#[derive(Resource, Clone, Debug)]
+pub struct BottomThresholdName(pub String);
+
+impl Default for BottomThresholdName {
+    fn default() -> Self {
+        Self("bottom".to_string())
+    }
+}
+
+/// The bottom empties of the loaded scenes.
+#[derive(Resource, Default)]
+pub struct BottomZones(pub Vec<BottomZone>);
+
+pub struct BottomZone {
+    pub bottom: Entity,
+    /// The `respawn_` empty with the same suffix.
+    pub respawn: Option<Entity>,
+}
+
+/// Returns the suffix of a name like `bottom_Tower`, or an empty suffix for the bare `bottom`.
+fn name_suffix<'a>(name: &'a str, base: &str) -> Option<&'a str> {
+    match name.strip_prefix(base)? {
+        "" => Some(""),
+        rest => rest.strip_prefix('_'),
+    }
+}
+
+/// Keeps a ball from falling below the bottoms, e.g. in a zone where it falls up.
+#[derive(Component)]
+pub struct SkipOutOfBounds;
+
+/// Marks a ball that's below its bottom, so falling is only reported once.
+#[derive(Component)]
+struct BelowBottom;
+
+/// The height of a ball when it last touched something solid.
+#[derive(Component)]
+struct GroundedHeight(f32);
+
+fn insert_kill_volumes(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf), With<Mesh3d>>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of)) = query.get(entity) else {
+            continue;
+        };
+        if name.starts_with(PREFIX) {
+            commands.entity(child_of.parent()).insert(KillVolume);
+        }
+    }
+}
+
+fn detect_kill_volumes(
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    mut ball_fell: EventWriter<BallFell>,
+    kill_volumes: Query<(), With<KillVolume>>,
+    balls: Query<(), With<Ball>>,
+) {
+    for event in sensor_triggered.read() {
+        if event.started && kill_volumes.contains(event.sensor) && balls.contains(event.other) {
+            ball_fell.write(BallFell {
+                ball: event.other,
+                respawn: None,
+            });
+        }
+    }
+}
+
+fn collect_bottom_zones(
+    trigger: Trigger<SceneInstanceReady>,
+    mut zones: ResMut<BottomZones>,
+    bottom_name: Res<BottomThresholdName>,
+    children: Query<&Children>,
+    names: Query<&Name>,
+) {
+    let mut bottoms = Vec::new();
+    let mut respawns = HashMap::new();
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok(name) = names.get(entity) else {
+            continue;
+        };
+        if let Some(suffix) = name_suffix(name, &bottom_name.0) {
+            bottoms.push((name.as_str(), suffix, entity));
+        } else if let Some(suffix) = name_suffix(name, "respawn") {
+            respawns.insert(suffix, entity);
+        }
+    }
+
+    for (name, suffix, bottom) in bottoms {
+        let respawn = respawns.get(suffix).copied();
+        if respawn.is_none() {
+            let respawn_name = name.replacen(bottom_name.0.as_str(), "respawn", 1);
+            info!("`{name}` has no `{respawn_name}`, falling there restarts the ball.");
+        }
+        zones.0.push(BottomZone { bottom, respawn });
+    }
+}
+
+fn track_grounded_height(
+    mut commands: Commands,
+    rapier_context: ReadRapierContext,
+    mut balls: Query<(Entity, &GlobalTransform, Option<&mut GroundedHeight>), With<Ball>>,
+) {
+    let Ok(context) = rapier_context.single() else {
+        return;
+    };
+
+    for (ball, transform, grounded) in balls.iter_mut() {
+        // Sensors only make intersection pairs, so any active contact is with something solid.
+        if !context
+            .contact_pairs_with(ball)
+            .any(|pair| pair.has_any_active_contact())
+        {
+            continue;
+        }
+        let height = transform.translation().y;
+        match grounded {
+            Some(mut grounded) => grounded.0 = height,
+            None => {
+                commands.entity(ball).insert(GroundedHeight(height));
+            }
+        }
+    }
+}
+
+/// Returns the index of the bottom a ball at the position is checked against, given the global
+/// transforms of the bottoms and the height the ball last touched something solid at.
+fn select_bottom(
+    bottoms: &[&GlobalTransform],
+    position: Vec3,
+    grounded_height: f32,
+) -> Option<usize> {
+    let height = |&index: &usize| bottoms[index].translation().y;
+    let horizontal_distance =
+        |&index: &usize| position.xz().distance(bottoms[index].translation().xz());
+    let covering: Vec<usize> = (0..bottoms.len())
+        .filter(|&index| {
+            let (scale, _, translation) = bottoms[index].to_scale_rotation_translation();
+            (position.x - translation.x).abs() <= scale.x
+                && (position.z - translation.z).abs() <= scale.z
+        })
+        .collect();
+
+    covering
+        .iter()
+        .copied()
+        .filter(|index| height(index) < grounded_height)
+        .max_by(|a, b| height(a).total_cmp(&height(b)))
+        .or_else(|| {
+            covering
+                .iter()
+                .copied()
+                .min_by(|a, b| horizontal_distance(a).total_cmp(&horizontal_distance(b)))
+        })
+        .or_else(|| (0..bottoms.len()).min_by(|a, b| height(a).total_cmp(&height(b))))
+}
+
+#[allow(clippy::type_complexity)]
+fn detect_fall_below_bottom(
+    mut commands: Commands,
+    mut zones: ResMut<BottomZones>,
+    mut ball_fell: EventWriter<BallFell>,
+    transforms: Query<&GlobalTransform>,
+    balls: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            Option<&GroundedHeight>,
+            Has<BelowBottom>,
+        ),
+        (With<Ball>, Without<SkipOutOfBounds>),
+    >,
+) {
+    // The bottoms of despawned scenes.
+    zones.0.retain(|zone| transforms.contains(zone.bottom));
+
+    let (zones, bottoms): (Vec<_>, Vec<_>) = zones
+        .0
+        .iter()
+        .filter_map(|zone| Some((zone, transforms.get(zone.bottom).ok()?)))
+        .unzip();
+
+    for (ball, ball_transform, grounded, below_before) in balls.iter() {
+        let position = ball_transform.translation();
+        // A ball that hasn't landed yet is checked from where it is.
+        let grounded_height = grounded.map_or(position.y, |grounded| grounded.0);
+        let Some(index) = select_bottom(&bottoms, position, grounded_height) else {
+            return;
+        };
+
+        let below = position.y < bottoms[index].translation().y;
+        match (below, below_before) {
+            (true, false) => {
+                commands.entity(ball).insert(BelowBottom);
+                ball_fell.write(BallFell {
+                    ball,
+                    respawn: zones[index]
+                        .respawn
+                        .and_then(|respawn| transforms.get(respawn).ok())
+                        .map(GlobalTransform::translation),
+                });
+            }
+            (false, true) => {
+                commands.entity(ball).remove::<BelowBottom>();
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_bare_and_suffixed_names() {
+        assert_eq!(name_suffix("bottom", "bottom"), Some(""));
+        assert_eq!(name_suffix("bottom_Tower", "bottom"), Some("Tower"));
+        assert_eq!(name_suffix("oob_floor", "oob_floor"), Some(""));
+        assert_eq!(name_suffix("oob_floor_2", "oob_floor"), Some("2"));
+        // Only the whole name or a name followed by `_` matches.
+        assert_eq!(name_suffix("bottomless", "bottom"), None);
+        assert_eq!(name_suffix("the_bottom", "bottom"), None);
+    }
+
+    fn bottom(x: f32, y: f32, size: f32) -> GlobalTransform {
+        GlobalTransform::from(Transform::from_xyz(x, y, 0.0).with_scale(Vec3::splat(size)))
+    }
+
+    #[test]
+    fn checks_stacked_bottoms_from_the_tier_the_ball_was_on() {
+        let tier1 = bottom(0.0, 10.0, 5.0);
+        let tier2 = bottom(0.0, 0.0, 5.0);
+        let bottoms = [&tier1, &tier2];
+
+        // Falling from the upper tier, the lower bottom doesn't keep it in bounds.
+        assert_eq!(
+            select_bottom(&bottoms, Vec3::new(0.0, 8.0, 0.0), 15.0),
+            Some(0)
+        );
+        // On the catch platform between the bottoms, the lower one applies.
+        assert_eq!(
+            select_bottom(&bottoms, Vec3::new(0.0, 5.0, 0.0), 5.0),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_nearest_then_the_lowest_bottom() {
+        let near = bottom(0.0, 3.0, 5.0);
+        let far = bottom(4.0, 2.0, 5.0);
+        let lowest = bottom(100.0, -5.0, 1.0);
+        let bottoms = [&near, &far, &lowest];
+
+        // Below all the bottoms whose area it's in.
+        assert_eq!(
+            select_bottom(&bottoms, Vec3::new(1.0, 0.0, 0.0), 1.0),
+            Some(0)
+        );
+        // Outside all the areas.
+        assert_eq!(
+            select_bottom(&bottoms, Vec3::new(50.0, 0.0, 0.0), 1.0),
+            Some(2)
+        );
+        assert_eq!(select_bottom(&[], Vec3::ZERO, 0.0), None);
+    }
+}
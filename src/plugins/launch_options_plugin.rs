@@ -0,0 +1,207 @@
+//! Command line options for quick iteration, e.g.
+//! `cargo run -- --level levels/level2/level2.gltf --windowed --no-debug-render`:
+//! - `--level <path>`, or just the path: the level to load instead of [`DEFAULT_MANIFEST`]. It's a
+//!   `.level.ron` manifest, or a glTF whose first scene is loaded as a level without one.
+//! - `--windowed` or `--fullscreen`: the mode of the primary window.
+//! - `--no-debug-render`: turns off rapier's debug render.
+//! - `--mute`: silences all audio.
+//! - `--fly`: starts with the orbit camera, see [`OrbitCameraEnabled`].
+//!
+//! Without options, nothing is changed, so the app behaves as it would without the
+//! [`LaunchOptionsPlugin`]. [`LaunchOptions::from_args`] prints the usage and exits on `--help` or
+//! an unknown option.
+
+use bevy::{
+    audio::Volume,
+    prelude::*,
+    window::{MonitorSelection, PrimaryWindow, WindowMode},
+};
+use bevy_rapier3d::render::DebugRenderContext;
+
+use crate::plugins::{
+    level_manifest_plugin::DEFAULT_MANIFEST, orbit_camera_plugin::OrbitCameraEnabled,
+};
+
+const USAGE: &str = "\
+Usage: [OPTIONS] [LEVEL]
+
+Options:
+    --level <PATH>      The .level.ron manifest or glTF of the level to load
+    --windowed          Open the window windowed
+    --fullscreen        Open the window in borderless fullscreen
+    --no-debug-render   Turn off rapier's debug render
+    --mute              Silence all audio
+    --fly               Start with the orbit camera
+    --help              Print this message";
+
+pub struct LaunchOptionsPlugin {
+    pub options: LaunchOptions,
+}
+
+impl Plugin for LaunchOptionsPlugin {
+    fn build(&self, app: &mut App) {
+        if self.options.fly {
+            app.insert_resource(OrbitCameraEnabled(true));
+        }
+        app.insert_resource(self.options.clone())
+            .add_systems(Startup, apply_launch_options);
+    }
+}
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+pub struct LaunchOptions {
+    /// The level to load instead of the default one.
+    pub level: Option<String>,
+    /// The mode of the primary window, unless it's left as the app made it.
+    pub window_mode: Option<WindowMode>,
+    pub no_debug_render: bool,
+    pub mute: bool,
+    /// Start with the orbit camera.
+    pub fly: bool,
+}
+
+impl LaunchOptions {
+    /// Parses the options of the command line. Prints the usage and exits on `--help` or an
+    /// unknown option.
+    pub fn from_args() -> Self {
+        match Self::parse(std::env::args().skip(1)) {
+            Ok(Some(options)) => options,
+            Ok(None) => {
+                println!("{USAGE}");
+                std::process::exit(0);
+            }
+            Err(err) => {
+                eprintln!("{err}\n\n{USAGE}");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    /// Parses the options, without the program name. Returns `None` for `--help`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--level" => {
+                    let level = args.next().ok_or("`--level` needs a path")?;
+                    options.set_level(level)?;
+                }
+                "--windowed" => options.window_mode = Some(WindowMode::Windowed),
+                "--fullscreen" => {
+                    options.window_mode =
+                        Some(WindowMode::BorderlessFullscreen(MonitorSelection::Current));
+                }
+                "--no-debug-render" => options.no_debug_render = true,
+                "--mute" => options.mute = true,
+                "--fly" => options.fly = true,
+                "--help" | "-h" => return Ok(None),
+                _ if arg.starts_with('-') => return Err(format!("Unknown option `{arg}`")),
+                _ => options.set_level(arg)?,
+            }
+        }
+        Ok(Some(options))
+    }
+
+    /// The level to load, or [`DEFAULT_MANIFEST`].
+    pub fn level_or_default(&self) -> String {
+        self.level
+            .clone()
+            .unwrap_or_else(|| DEFAULT_MANIFEST.to_string())
+    }
+
+    fn set_level(&mut self, level: String) -> Result<(), String> {
+        if let Some(previous) = &self.level {
+            return Err(format!("Two levels are given, `{previous}` and `{level}`"));
+        }
+        self.level = Some(level);
+        Ok(())
+    }
+}
+
+fn apply_launch_options(
+    options: Res<LaunchOptions>,
+    window: Option<Single<&mut Window, With<PrimaryWindow>>>,
+    debug_render: Option<ResMut<DebugRenderContext>>,
+    global_volume: Option<ResMut<GlobalVolume>>,
+) {
+    if let (Some(mode), Some(mut window)) = (options.window_mode, window) {
+        window.mode = mode;
+    }
+    if let Some(mut debug_render) = debug_render {
+        debug_render.enabled &= !options.no_debug_render;
+    }
+    if let (true, Some(mut global_volume)) = (options.mute, global_volume) {
+        global_volume.volume = Volume::SILENT;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Option<LaunchOptions>, String> {
+        LaunchOptions::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_the_options() {
+        assert_eq!(parse(&[]), Ok(Some(LaunchOptions::default())));
+        assert_eq!(
+            parse(&[
+                "--level",
+                "levels/level2/level2.gltf",
+                "--windowed",
+                "--no-debug-render",
+                "--mute",
+                "--fly",
+            ]),
+            Ok(Some(LaunchOptions {
+                level: Some("levels/level2/level2.gltf".to_string()),
+                window_mode: Some(WindowMode::Windowed),
+                no_debug_render: true,
+                mute: true,
+                fly: true,
+            }))
+        );
+        // A bare path is the level, like before there were options.
+        assert_eq!(
+            parse(&["levels/level2/level2.level.ron"])
+                .unwrap()
+                .unwrap()
+                .level,
+            Some("levels/level2/level2.level.ron".to_string())
+        );
+        assert_eq!(parse(&["--help"]), Ok(None));
+    }
+
+    #[test]
+    fn rejects_unknown_options_and_missing_paths() {
+        assert!(parse(&["--windowd"]).is_err());
+        assert!(parse(&["--level"]).is_err());
+        assert!(parse(&["a.level.ron", "b.level.ron"]).is_err());
+    }
+
+    #[test]
+    fn applies_the_options_to_the_app() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            LaunchOptionsPlugin {
+                options: parse(&["--no-debug-render", "--mute", "--fly"])
+                    .unwrap()
+                    .unwrap(),
+            },
+        ))
+        .init_resource::<DebugRenderContext>()
+        .init_resource::<GlobalVolume>();
+        app.update();
+
+        assert!(!app.world().resource::<DebugRenderContext>().enabled);
+        assert_eq!(
+            app.world().resource::<GlobalVolume>().volume.to_linear(),
+            0.0
+        );
+        assert!(app.world().resource::<OrbitCameraEnabled>().0);
+    }
+}
@@ -0,0 +1,427 @@
+//! Loads levels from `.level.ron` manifests instead of hard-coding their assets, e.g.
+//!
+//! ```ron
+//! (
+//!     scene: "levels/level1/level1.gltf#Scene0",
+//!     skybox: Some("textures/skybox.png"),
+//!     music: None,
+//!     gravity: Some((0.0, -9.81, 0.0)),
+//!     par_time: Some(90.0),
+//! )
+//! ```
+//!
+//! Only `scene` is required. Once the manifest in [`LevelManager`] is loaded, its scene and music
+//! are spawned as [`LevelEntity`]s, its skybox is put on every [`Camera3d`], and rapier's gravity
+//! is set. Loading another manifest despawns the previous level first.
+//!
+//! An [`UnloadLevel`] event cleans up everything the level left behind: the [`LevelEntity`]s with
+//! their descendants, which holds the colliders and sensors inserted into the scene, the skybox,
+//! the gravity, the play time, the checkpoints, the start and bottom zones, and the respawns in
+//! progress. Plugins that spawn entities for a level outside of its scene should tag them with
+//! [`LevelEntity`].
+//!
+//! A skybox image can be a cubemap, or a 2D image with the six faces stacked vertically. A 2D
+//! image that isn't 6 times as tall as it's wide is skipped with a warning.
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, io::Reader},
+    core_pipeline::Skybox,
+    prelude::*,
+    render::render_resource::{TextureViewDescriptor, TextureViewDimension},
+};
+use bevy_rapier3d::prelude::*;
+use serde::Deserialize;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    checkpoint_plugin::ResetCheckpoints,
+    kill_volume_plugin::BottomZones,
+    launch_options_plugin::LaunchOptions,
+    lives_plugin::PlayTime,
+    respawn_plugin::{RespawnCountdown, Respawning},
+    spawn_point_plugin::PlayerStart,
+};
+
+/// The manifest loaded when no path is passed on the command line.
+pub const DEFAULT_MANIFEST: &str = "levels/level1/level1.level.ron";
+/// Rapier's gravity, used when a manifest doesn't set one.
+const DEFAULT_GRAVITY: Vec3 = Vec3::new(0.0, -9.81, 0.0);
+const SKYBOX_BRIGHTNESS: f32 = 1000.0;
+
+#[derive(Default)]
+pub struct LevelManifestPlugin {
+    /// The manifest loaded at startup.
+    pub initial: Option<String>,
+}
+
+impl Plugin for LevelManifestPlugin {
+    fn build(&self, app: &mut App) {
+        let initial = self.initial.clone();
+        app.init_asset::<LevelManifest>()
+            .register_asset_loader(LevelManifestLoader)
+            .init_resource::<LevelManager>()
+            .add_event::<UnloadLevel>()
+            .add_event::<ResetCheckpoints>()
+            .add_systems(
+                Startup,
+                move |mut manager: ResMut<LevelManager>, asset_server: Res<AssetServer>| {
+                    if let Some(path) = &initial {
+                        manager.load(&asset_server, path);
+                    }
+                },
+            )
+            // After Update, so a level unloaded and loaded again in Update is cleaned up before
+            // it's spawned.
+            .add_systems(
+                PostUpdate,
+                (cleanup_level, spawn_level, prepare_skybox).chain(),
+            );
+    }
+}
+
+/// Returns the level passed on the command line, or [`DEFAULT_MANIFEST`]. The arguments are
+/// parsed as [`LaunchOptions`], which exits on unknown options.
+pub fn manifest_path_from_args() -> String {
+    LaunchOptions::from_args().level_or_default()
+}
+
+/// What a level is made of. The paths are asset paths.
+#[derive(Asset, TypePath, Deserialize, Clone, Debug)]
+pub struct LevelManifest {
+    pub scene: String,
+    #[serde(default)]
+    pub skybox: Option<String>,
+    #[serde(default)]
+    pub music: Option<String>,
+    #[serde(default)]
+    pub gravity: Option<(f32, f32, f32)>,
+    /// The time to beat, in seconds.
+    #[serde(default)]
+    pub par_time: Option<f32>,
+}
+
+impl LevelManifest {
+    /// The gravity of the level, or rapier's default.
+    pub fn gravity(&self) -> Vec3 {
+        self.gravity
+            .map_or(DEFAULT_GRAVITY, |(x, y, z)| Vec3::new(x, y, z))
+    }
+}
+
+/// The error of the loaders of RON assets.
+#[derive(Debug)]
+pub enum RonLoaderError {
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError),
+}
+
+impl std::fmt::Display for RonLoaderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read the file: {err}"),
+            Self::Ron(err) => write!(f, "failed to parse the RON: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for RonLoaderError {}
+
+impl From<std::io::Error> for RonLoaderError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<ron::error::SpannedError> for RonLoaderError {
+    fn from(err: ron::error::SpannedError) -> Self {
+        Self::Ron(err)
+    }
+}
+
+#[derive(Default)]
+struct LevelManifestLoader;
+
+impl AssetLoader for LevelManifestLoader {
+    type Asset = LevelManifest;
+    type Settings = ();
+    type Error = RonLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+/// Tracks the manifest of the current level.
+#[derive(Resource, Default)]
+pub struct LevelManager {
+    current: Option<Handle<LevelManifest>>,
+    /// The index of the current level in the campaign.
+    pub current_level: usize,
+    /// Whether the current level has been spawned.
+    spawned: bool,
+    /// The skybox image waiting to be turned into a cubemap.
+    pending_skybox: Option<Handle<Image>>,
+}
+
+impl LevelManager {
+    /// Starts loading a manifest. The current level is replaced once it's loaded.
+    ///
+    /// A glTF path is loaded as a level of its first scene, without a manifest.
+    pub fn load(&mut self, asset_server: &AssetServer, path: &str) {
+        let is_gltf = path.ends_with(".gltf") || path.ends_with(".glb");
+        self.current = Some(if is_gltf {
+            asset_server.add(LevelManifest {
+                scene: format!("{path}#Scene0"),
+                skybox: None,
+                music: None,
+                gravity: None,
+                par_time: None,
+            })
+        } else {
+            asset_server.load(path.to_string())
+        });
+        self.spawned = false;
+    }
+
+    /// Forgets the current level, e.g. when going back to a menu. Send [`UnloadLevel`] to despawn
+    /// it.
+    pub fn unload(&mut self) {
+        self.current = None;
+        self.spawned = false;
+        self.pending_skybox = None;
+    }
+
+    pub fn current(&self) -> Option<&Handle<LevelManifest>> {
+        self.current.as_ref()
+    }
+
+    /// The manifest of the current level, if it's loaded.
+    pub fn manifest<'a>(&self, manifests: &'a Assets<LevelManifest>) -> Option<&'a LevelManifest> {
+        manifests.get(self.current.as_ref()?)
+    }
+}
+
+/// An entity of the current level, which is despawned with its descendants when the level is
+/// unloaded or another level is spawned.
+#[derive(Component)]
+pub struct LevelEntity;
+
+/// Despawns the current level and resets what it changed, so loading a level again behaves like
+/// a fresh start. It doesn't change the [`LevelManager`].
+#[derive(Event)]
+pub struct UnloadLevel;
+
+#[allow(clippy::too_many_arguments)]
+fn spawn_level(
+    mut commands: Commands,
+    mut manager: ResMut<LevelManager>,
+    manifests: Res<Assets<LevelManifest>>,
+    asset_server: Res<AssetServer>,
+    level_entities: Query<Entity, With<LevelEntity>>,
+    cameras: Query<Entity, With<Camera3d>>,
+    mut rapier_configs: Query<&mut RapierConfiguration>,
+) {
+    if manager.spawned {
+        return;
+    }
+    let Some(manifest) = manager.manifest(&manifests).cloned() else {
+        return;
+    };
+    manager.spawned = true;
+
+    for entity in level_entities.iter() {
+        commands.entity(entity).despawn();
+    }
+
+    commands.spawn((
+        LevelEntity,
+        SceneRoot(asset_server.load(manifest.scene.clone())),
+    ));
+    if let Some(music) = &manifest.music {
+        commands.spawn((
+            LevelEntity,
+            AudioPlayer::new(asset_server.load(music.clone())),
+            PlaybackSettings::LOOP,
+        ));
+    }
+
+    manager.pending_skybox = manifest
+        .skybox
+        .as_ref()
+        .map(|skybox| asset_server.load(skybox.clone()));
+    for camera in cameras.iter() {
+        match &manager.pending_skybox {
+            Some(image) => {
+                commands.entity(camera).insert(Skybox {
+                    image: image.clone(),
+                    brightness: SKYBOX_BRIGHTNESS,
+                    ..default()
+                });
+            }
+            None => {
+                commands.entity(camera).remove::<Skybox>();
+            }
+        }
+    }
+
+    for mut config in rapier_configs.iter_mut() {
+        config.gravity = manifest.gravity();
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn cleanup_level(
+    mut commands: Commands,
+    mut unload: EventReader<UnloadLevel>,
+    mut reset_checkpoints: EventWriter<ResetCheckpoints>,
+    level_entities: Query<Entity, With<LevelEntity>>,
+    cameras: Query<Entity, With<Camera3d>>,
+    balls: Query<Entity, With<Ball>>,
+    mut rapier_configs: Query<&mut RapierConfiguration>,
+    play_time: Option<ResMut<PlayTime>>,
+    player_start: Option<ResMut<PlayerStart>>,
+    bottom_zones: Option<ResMut<BottomZones>>,
+) {
+    if unload.read().count() == 0 {
+        return;
+    }
+
+    for entity in level_entities.iter() {
+        commands.entity(entity).despawn();
+    }
+    for camera in cameras.iter() {
+        commands.entity(camera).remove::<Skybox>();
+    }
+    // A respawn would put the ball back into the unloaded level.
+    for ball in balls.iter() {
+        commands
+            .entity(ball)
+            .remove::<(RespawnCountdown, Respawning)>();
+    }
+    for mut config in rapier_configs.iter_mut() {
+        config.gravity = DEFAULT_GRAVITY;
+    }
+
+    reset_checkpoints.write(ResetCheckpoints);
+    if let Some(mut play_time) = play_time {
+        play_time.0 = 0.0;
+    }
+    if let Some(mut player_start) = player_start {
+        player_start.0 = None;
+    }
+    if let Some(mut bottom_zones) = bottom_zones {
+        bottom_zones.0.clear();
+    }
+}
+
+fn prepare_skybox(
+    mut commands: Commands,
+    mut manager: ResMut<LevelManager>,
+    mut images: ResMut<Assets<Image>>,
+    cameras: Query<Entity, With<Camera3d>>,
+) {
+    let Some(handle) = &manager.pending_skybox else {
+        return;
+    };
+    let Some(image) = images.get_mut(handle) else {
+        return;
+    };
+
+    manager.pending_skybox = None;
+
+    // Stacked faces are 6 times as tall as they're wide.
+    if image.texture_descriptor.array_layer_count() == 1 {
+        if image.height() != 6 * image.width() {
+            warn!(
+                "The skybox is {}x{}, but should be 6 square faces stacked vertically, so it's \
+                 skipped.",
+                image.width(),
+                image.height()
+            );
+            for camera in cameras.iter() {
+                commands.entity(camera).remove::<Skybox>();
+            }
+            return;
+        }
+        image.reinterpret_stacked_2d_as_array(6);
+        image.texture_view_descriptor = Some(TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::Cube),
+            ..default()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::scene::ScenePlugin;
+
+    use super::*;
+
+    fn level_entity_count(app: &mut App) -> usize {
+        app.world_mut()
+            .query_filtered::<(), With<LevelEntity>>()
+            .iter(app.world())
+            .count()
+    }
+
+    #[test]
+    fn unloading_a_level_leaves_the_entities_from_before_it() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            TransformPlugin,
+            ScenePlugin,
+            LevelManifestPlugin::default(),
+        ))
+        .init_asset::<Image>();
+        app.update();
+        let baseline = app.world().entities().len();
+
+        // Twice, so a level loaded after an unload is cleaned up too.
+        for _ in 0..2 {
+            let manifest =
+                app.world_mut()
+                    .resource_mut::<Assets<LevelManifest>>()
+                    .add(LevelManifest {
+                        scene: "levels/missing/missing.gltf#Scene0".to_string(),
+                        skybox: None,
+                        music: None,
+                        gravity: None,
+                        par_time: None,
+                    });
+            let mut manager = app.world_mut().resource_mut::<LevelManager>();
+            manager.current = Some(manifest);
+            manager.spawned = false;
+            app.update();
+            assert_eq!(level_entity_count(&mut app), 1);
+
+            // Stands in for the meshes of the scene and the colliders inserted into it.
+            let root = app
+                .world_mut()
+                .query_filtered::<Entity, With<LevelEntity>>()
+                .single(app.world())
+                .unwrap();
+            app.world_mut()
+                .spawn((ChildOf(root), Collider::ball(1.0), Sensor))
+                .with_child(Transform::default());
+            assert_eq!(app.world().entities().len(), baseline + 3);
+
+            app.world_mut().send_event(UnloadLevel);
+            app.update();
+            assert_eq!(level_entity_count(&mut app), 0);
+            assert_eq!(app.world().entities().len(), baseline);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Shows the levels of the campaign in [`CampaignState::LevelSelect`], each with its best time
+//! from the [`Leaderboard`]. The entries are picked with the arrow keys and `Enter`, or clicked,
+//! which loads the level and enters [`CampaignState::Loading`].
+//!
+//! `Escape` in the level select exits the app, and `Escape` while playing unloads the level and
+//! goes back to the level select, so this replaces the
+//! [`EscExitPlugin`](crate::plugins::esc_exit_plugin::EscExitPlugin).
+
+use bevy::prelude::*;
+
+use crate::plugins::{
+    campaign_plugin::{Campaign, CampaignHandle, CampaignState},
+    level_manifest_plugin::{LevelManager, UnloadLevel},
+    save_game_plugin::Leaderboard,
+};
+
+const SELECTED_COLOR: Color = Color::srgb(0.35, 0.35, 0.45);
+const UNSELECTED_COLOR: Color = Color::srgb(0.15, 0.15, 0.2);
+
+pub struct LevelSelectPlugin;
+
+impl Plugin for LevelSelectPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<LevelSelection>()
+            .add_systems(OnEnter(CampaignState::LevelSelect), spawn_level_select)
+            .add_systems(OnExit(CampaignState::LevelSelect), despawn_level_select)
+            .add_systems(
+                Update,
+                (
+                    (
+                        fill_level_list,
+                        navigate_level_select,
+                        highlight_level_select,
+                    )
+                        .chain()
+                        .run_if(in_state(CampaignState::LevelSelect)),
+                    handle_escape,
+                ),
+            );
+    }
+}
+
+/// The index of the selected level in the campaign.
+#[derive(Resource, Default)]
+struct LevelSelection(usize);
+
+#[derive(Component)]
+struct LevelSelectMenu;
+
+/// The column the entries are spawned in once the campaign is loaded.
+#[derive(Component)]
+struct LevelList;
+
+/// The entry of the level with this index in the campaign.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+struct LevelEntry(usize);
+
+fn spawn_level_select(
+    mut commands: Commands,
+    mut selection: ResMut<LevelSelection>,
+    manager: Res<LevelManager>,
+) {
+    // Starts at the level played last, e.g. from a save.
+    selection.0 = manager.current_level;
+    commands
+        .spawn((
+            LevelSelectMenu,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Select a Level"),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                LevelList,
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.0),
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn despawn_level_select(mut commands: Commands, menus: Query<Entity, With<LevelSelectMenu>>) {
+    for menu in menus.iter() {
+        commands.entity(menu).despawn();
+    }
+}
+
+fn fill_level_list(
+    mut commands: Commands,
+    campaigns: Res<Assets<Campaign>>,
+    campaign: Option<Res<CampaignHandle>>,
+    leaderboard: Option<Res<Leaderboard>>,
+    mut selection: ResMut<LevelSelection>,
+    list: Single<Entity, With<LevelList>>,
+    entries: Query<(), With<LevelEntry>>,
+) {
+    if !entries.is_empty() {
+        return;
+    }
+    let Some(campaign) = campaign.and_then(|campaign| campaigns.get(&campaign.0)) else {
+        return;
+    };
+    if selection.0 >= campaign.levels.len() {
+        selection.0 = 0;
+    }
+
+    commands.entity(*list).with_children(|parent| {
+        for (index, level) in campaign.levels.iter().enumerate() {
+            let best_time = leaderboard
+                .as_ref()
+                .and_then(|leaderboard| leaderboard.best_time(&level.manifest))
+                .map_or("--".to_string(), |time| format!("{time:.1} s"));
+            parent
+                .spawn((
+                    LevelEntry(index),
+                    Button,
+                    Node {
+                        width: Val::Px(360.0),
+                        padding: UiRect::all(Val::Px(8.0)),
+                        justify_content: JustifyContent::SpaceBetween,
+                        ..default()
+                    },
+                    BackgroundColor(UNSELECTED_COLOR),
+                ))
+                .with_children(|entry| {
+                    for text in [level.name.clone(), best_time] {
+                        entry.spawn((
+                            Text::new(text),
+                            TextFont {
+                                font_size: 28.0,
+                                ..default()
+                            },
+                        ));
+                    }
+                });
+        }
+    });
+}
+
+#[allow(clippy::too_many_arguments)]
+fn navigate_level_select(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut selection: ResMut<LevelSelection>,
+    mut manager: ResMut<LevelManager>,
+    asset_server: Res<AssetServer>,
+    campaigns: Res<Assets<Campaign>>,
+    campaign: Option<Res<CampaignHandle>>,
+    mut next_state: ResMut<NextState<CampaignState>>,
+    mut unload: EventWriter<UnloadLevel>,
+    buttons: Query<(&LevelEntry, &Interaction), Changed<Interaction>>,
+) {
+    let Some(campaign) = campaign.and_then(|campaign| campaigns.get(&campaign.0)) else {
+        return;
+    };
+    let count = campaign.levels.len();
+    if count == 0 {
+        return;
+    }
+
+    if keyboard.just_pressed(KeyCode::ArrowDown) {
+        selection.0 = (selection.0 + 1) % count;
+    }
+    if keyboard.just_pressed(KeyCode::ArrowUp) {
+        selection.0 = (selection.0 + count - 1) % count;
+    }
+
+    let mut picked = keyboard.just_pressed(KeyCode::Enter).then_some(selection.0);
+    for (entry, interaction) in buttons.iter() {
+        match interaction {
+            Interaction::Hovered => selection.0 = entry.0,
+            Interaction::Pressed => picked = Some(entry.0),
+            Interaction::None => {}
+        }
+    }
+
+    let Some(index) = picked else {
+        return;
+    };
+    let Some(level) = campaign.levels.get(index) else {
+        return;
+    };
+    manager.current_level = index;
+    manager.load(&asset_server, &level.manifest);
+    unload.write(UnloadLevel);
+    next_state.set(CampaignState::Loading);
+}
+
+fn highlight_level_select(
+    selection: Res<LevelSelection>,
+    mut buttons: Query<(&LevelEntry, &mut BackgroundColor)>,
+) {
+    for (entry, mut color) in buttons.iter_mut() {
+        color.0 = if entry.0 == selection.0 {
+            SELECTED_COLOR
+        } else {
+            UNSELECTED_COLOR
+        };
+    }
+}
+
+fn handle_escape(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    state: Res<State<CampaignState>>,
+    mut next_state: ResMut<NextState<CampaignState>>,
+    mut manager: ResMut<LevelManager>,
+    mut unload: EventWriter<UnloadLevel>,
+    mut exit: EventWriter<AppExit>,
+) {
+    if !keyboard.just_pressed(KeyCode::Escape) {
+        return;
+    }
+
+    match state.get() {
+        CampaignState::LevelSelect => {
+            exit.write(AppExit::Success);
+        }
+        _ => {
+            manager.unload();
+            unload.write(UnloadLevel);
+            next_state.set(CampaignState::LevelSelect);
+        }
+    }
+}
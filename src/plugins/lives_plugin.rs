@@ -0,0 +1,273 @@
+//! Gives the player a number of lives, shown in the corner of the screen.
+//! Every [`BallFell`] event that enters [`GameState::Fell`] takes one, and losing the last one
+//! enters [`GameState::GameOver`], which shows the time played and waits for `Enter` to start the
+//! level over: the lives are refilled, the time is reset, the checkpoints are deactivated, every
+//! [`Ball`] goes back to the [`PlayerStart`], and the game plays on.
+//!
+//! Restarting without falling doesn't send [`BallFell`], so it doesn't cost a life. Controls stop
+//! in [`GameState::GameOver`] like in every state but [`GameState::Playing`].
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    checkpoint_plugin::{ResetCheckpoints, RestartPosition},
+    game_state_plugin::{GameState, GameStatePlugin},
+    kill_volume_plugin::BallFell,
+    spawn_point_plugin::PlayerStart,
+};
+
+pub struct LivesPlugin {
+    pub max_lives: u32,
+}
+
+impl Default for LivesPlugin {
+    fn default() -> Self {
+        Self { max_lives: 3 }
+    }
+}
+
+impl Plugin for LivesPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<GameStatePlugin>() {
+            app.add_plugins(GameStatePlugin);
+        }
+        app.insert_resource(Lives {
+            current: self.max_lives,
+            max: self.max_lives,
+        })
+        .init_resource::<PlayTime>()
+        .add_event::<ResetCheckpoints>()
+        .add_systems(Startup, spawn_lives_text)
+        .add_systems(OnEnter(GameState::Fell), lose_lives)
+        .add_systems(OnEnter(GameState::GameOver), spawn_game_over_screen)
+        .add_systems(OnExit(GameState::GameOver), despawn_game_over_screen)
+        .add_systems(
+            Update,
+            (
+                count_play_time.run_if(in_state(GameState::Playing)),
+                start_over.run_if(in_state(GameState::GameOver)),
+                update_lives_text,
+            ),
+        );
+    }
+}
+
+#[derive(Resource)]
+pub struct Lives {
+    pub current: u32,
+    pub max: u32,
+}
+
+/// The seconds played since the level started, not counting pauses.
+#[derive(Resource, Default)]
+pub struct PlayTime(pub f32);
+
+#[derive(Component)]
+struct LivesText;
+
+#[derive(Component)]
+struct GameOverScreen;
+
+fn spawn_lives_text(mut commands: Commands) {
+    commands.spawn((
+        LivesText,
+        Text::default(),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            right: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+fn update_lives_text(lives: Res<Lives>, mut text: Single<&mut Text, With<LivesText>>) {
+    if lives.is_changed() {
+        text.0 = format!("Lives: {}", lives.current);
+    }
+}
+
+fn count_play_time(time: Res<Time>, mut play_time: ResMut<PlayTime>) {
+    play_time.0 += time.delta_secs();
+}
+
+fn lose_lives(
+    mut ball_fell: EventReader<BallFell>,
+    mut lives: ResMut<Lives>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    // Every ball that fell in the frame before entering costs a life.
+    let falls = (ball_fell.read().count() as u32).max(1);
+    lives.current = lives.current.saturating_sub(falls);
+    if lives.current == 0 {
+        info!("Out of lives.");
+        next_state.set(GameState::GameOver);
+    }
+}
+
+fn spawn_game_over_screen(mut commands: Commands, play_time: Res<PlayTime>) {
+    commands
+        .spawn((
+            GameOverScreen,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+        ))
+        .with_children(|parent| {
+            for (text, font_size) in [
+                ("Game Over".to_string(), 56.0),
+                (format!("Time: {:.1} s", play_time.0), 28.0),
+                ("Press Enter to start over".to_string(), 24.0),
+            ] {
+                parent.spawn((
+                    Text::new(text),
+                    TextFont {
+                        font_size,
+                        ..default()
+                    },
+                ));
+            }
+        });
+}
+
+fn despawn_game_over_screen(mut commands: Commands, screens: Query<Entity, With<GameOverScreen>>) {
+    for screen in screens.iter() {
+        commands.entity(screen).despawn();
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn start_over(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut lives: ResMut<Lives>,
+    mut play_time: ResMut<PlayTime>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut reset_checkpoints: EventWriter<ResetCheckpoints>,
+    player_start: Option<Res<PlayerStart>>,
+    mut balls: Query<
+        (
+            &mut Transform,
+            Option<&mut Velocity>,
+            Option<&mut RestartPosition>,
+        ),
+        With<Ball>,
+    >,
+) {
+    if !keyboard.just_pressed(KeyCode::Enter) {
+        return;
+    }
+
+    lives.current = lives.max;
+    play_time.0 = 0.0;
+    reset_checkpoints.write(ResetCheckpoints);
+
+    let start = player_start.and_then(|player_start| player_start.0);
+    for (mut transform, velocity, restart_position) in balls.iter_mut() {
+        if let Some(mut velocity) = velocity {
+            *velocity = Velocity::zero();
+        }
+        let Some(start) = start else {
+            continue;
+        };
+        transform.translation = start.translation;
+        if let Some(mut restart_position) = restart_position {
+            restart_position.0 = start.translation;
+        }
+    }
+    if start.is_none() {
+        warn!("There's no start to put the balls back at.");
+    }
+
+    next_state.set(GameState::Playing);
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::plugins::mesh_physics_plugin::PhysicsReady;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, LivesPlugin { max_lives: 2 }))
+            .init_resource::<ButtonInput<KeyCode>>();
+        app.update();
+        app.world_mut().send_event(PhysicsReady {
+            scene: Entity::PLACEHOLDER,
+            colliders_inserted: 0,
+        });
+        step(&mut app);
+        app
+    }
+
+    /// Runs the frame sending the next state, the one entering it, and the one entering the state
+    /// set on entering.
+    fn step(app: &mut App) {
+        app.update();
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .clear();
+        app.update();
+        app.update();
+    }
+
+    fn state(app: &App) -> GameState {
+        *app.world().resource::<State<GameState>>().get()
+    }
+
+    fn fall(app: &mut App, ball: Entity) {
+        app.world_mut().send_event(BallFell {
+            ball,
+            respawn: None,
+        });
+        step(app);
+    }
+
+    fn press(app: &mut App, key: KeyCode) {
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(key);
+        step(app);
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .release(key);
+    }
+
+    #[test]
+    fn ends_the_game_when_the_last_life_is_lost() {
+        let mut app = app();
+        let ball = app
+            .world_mut()
+            .spawn((Ball { radius: 0.5 }, Transform::default()))
+            .id();
+        assert_eq!(state(&app), GameState::Playing);
+
+        fall(&mut app, ball);
+        assert_eq!(state(&app), GameState::Fell);
+        assert_eq!(app.world().resource::<Lives>().current, 1);
+
+        press(&mut app, KeyCode::KeyR);
+        assert_eq!(state(&app), GameState::Playing);
+        fall(&mut app, ball);
+        assert_eq!(state(&app), GameState::GameOver);
+        assert_eq!(app.world().resource::<Lives>().current, 0);
+
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(state(&app), GameState::Playing);
+        assert_eq!(app.world().resource::<Lives>().current, 2);
+    }
+}
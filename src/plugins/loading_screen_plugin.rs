@@ -0,0 +1,132 @@
+//! Shows a progress bar in [`GameState::Loading`] until the assets in [`LoadingAssets`] are loaded
+//! with their dependencies. The [`GameStatePlugin`] waits for them with [`AssetsLoaded`] before it
+//! enters [`GameState::Playing`], so the game starts once both the assets and the level's
+//! colliders are ready.
+//!
+//! Assets that fail to load are counted as done, so the game still starts without them.
+
+use bevy::{asset::RecursiveDependencyLoadState, prelude::*};
+
+use crate::plugins::game_state_plugin::{GameState, GameStatePlugin};
+
+const BAR_WIDTH: f32 = 400.0;
+
+pub struct LoadingScreenPlugin;
+
+impl Plugin for LoadingScreenPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<GameStatePlugin>() {
+            app.add_plugins(GameStatePlugin);
+        }
+        app.init_resource::<LoadingAssets>()
+            .init_resource::<AssetsLoaded>()
+            .add_systems(OnEnter(GameState::Loading), spawn_loading_screen)
+            .add_systems(OnExit(GameState::Loading), despawn_loading_screen)
+            .add_systems(
+                Update,
+                update_loading_progress.run_if(in_state(GameState::Loading)),
+            );
+    }
+}
+
+/// The assets waited for in [`GameState::Loading`]. Add them in `Startup`.
+#[derive(Resource, Default)]
+pub struct LoadingAssets(pub Vec<UntypedHandle>);
+
+/// Whether every asset in [`LoadingAssets`] is done loading.
+#[derive(Resource, Default, PartialEq)]
+pub struct AssetsLoaded(pub bool);
+
+#[derive(Component)]
+struct LoadingScreen;
+
+#[derive(Component)]
+struct ProgressBar;
+
+#[derive(Component)]
+struct ProgressText;
+
+fn spawn_loading_screen(mut commands: Commands) {
+    commands
+        .spawn((
+            LoadingScreen,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::BLACK),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                ProgressText,
+                Text::new("Loading… 0%"),
+                TextFont {
+                    font_size: 28.0,
+                    ..default()
+                },
+            ));
+            parent
+                .spawn((
+                    Node {
+                        width: Val::Px(BAR_WIDTH),
+                        height: Val::Px(16.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.15, 0.15, 0.2)),
+                ))
+                .with_child((
+                    ProgressBar,
+                    Node {
+                        width: Val::Percent(0.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(Color::srgb(0.4, 0.7, 1.0)),
+                ));
+        });
+}
+
+fn despawn_loading_screen(mut commands: Commands, screens: Query<Entity, With<LoadingScreen>>) {
+    for screen in screens.iter() {
+        commands.entity(screen).despawn();
+    }
+}
+
+fn update_loading_progress(
+    asset_server: Res<AssetServer>,
+    loading_assets: Res<LoadingAssets>,
+    mut assets_loaded: ResMut<AssetsLoaded>,
+    mut bar: Single<&mut Node, With<ProgressBar>>,
+    mut text: Single<&mut Text, With<ProgressText>>,
+) {
+    let mut loaded = 0;
+    let mut failed = 0;
+    for handle in loading_assets.0.iter() {
+        match asset_server.get_recursive_dependency_load_state(handle.id()) {
+            Some(RecursiveDependencyLoadState::Loaded) => loaded += 1,
+            Some(RecursiveDependencyLoadState::Failed(_)) => failed += 1,
+            _ => {}
+        }
+    }
+
+    let total = loading_assets.0.len();
+    let done = loaded + failed;
+    let progress = if total == 0 {
+        1.0
+    } else {
+        done as f32 / total as f32
+    };
+    bar.width = Val::Percent(progress * 100.0);
+    text.0 = format!("Loading… {:.0}%", progress * 100.0);
+
+    let all_done = done == total;
+    if all_done && !assets_loaded.0 && failed > 0 {
+        warn!("{failed} of {total} assets failed to load.");
+    }
+    assets_loaded.set_if_neq(AssetsLoaded(all_done));
+}
@@ -0,0 +1,181 @@
+//! Turns glTF meshes named `magnet_*` into magnets that pull the balls towards the origin of the
+//! mesh's parent, e.g. to keep the ball on a loop-the-loop without modeling a tube around it.
+//! The magnet is read from the glTF extras of the mesh or its parent:
+//! - `magnet_strength`: a number, the force at the origin, 20 by default.
+//! - `magnet_radius`: a number, the radius of the sphere it pulls in, 5 by default, scaled with
+//!   the parent.
+//!
+//! The pull falls off linearly to zero at the radius, so it's never stronger than the strength,
+//! however close the ball gets. It's added to the ball's [`ExternalForce`] next to the forces of
+//! other systems, like the player's, so the ball can still be steered.
+//!
+//! The magnet's ball sensor is reported through the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline.
+
+use bevy::{gltf::GltfExtras, prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    mesh_physics_plugin::{SensorTriggered, add_collider_pipeline, sensor_components},
+};
+
+const PREFIX: &str = "magnet_";
+
+pub struct MagnetZonePlugin;
+
+impl Plugin for MagnetZonePlugin {
+    fn build(&self, app: &mut App) {
+        add_collider_pipeline(app);
+        app.add_systems(Update, (track_magnet_contacts, apply_magnets).chain())
+            .add_observer(insert_magnets);
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct Magnet {
+    /// The force at the origin.
+    pub strength: f32,
+    pub radius: f32,
+}
+
+impl Default for Magnet {
+    fn default() -> Self {
+        Self {
+            strength: 20.0,
+            radius: 5.0,
+        }
+    }
+}
+
+impl Magnet {
+    /// Parses the magnet from the extras JSON. Returns an error describing the first malformed
+    /// value.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        let mut magnet = Self::default();
+
+        for (key, field) in [
+            ("magnet_strength", &mut magnet.strength),
+            ("magnet_radius", &mut magnet.radius),
+        ] {
+            if let Some(number) = value.get(key) {
+                *field = number
+                    .as_f64()
+                    .ok_or_else(|| format!("`{key}` should be a number, got {number}"))?
+                    as f32;
+            }
+        }
+
+        Ok(magnet)
+    }
+
+    /// The force pulling something at the offset from the origin.
+    pub fn pull(&self, offset: Vec3) -> Vec3 {
+        let distance = offset.length();
+        if distance >= self.radius {
+            return Vec3::ZERO;
+        }
+        -offset.normalize_or_zero() * self.strength * (1.0 - distance / self.radius)
+    }
+}
+
+/// The magnets a ball is in and the force they currently add to its [`ExternalForce`].
+#[derive(Component, Default)]
+struct InMagnets {
+    magnets: Vec<Entity>,
+    applied: Vec3,
+}
+
+fn insert_magnets(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf), With<Mesh3d>>,
+    extras_query: Query<&GltfExtras>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of)) = query.get(entity) else {
+            continue;
+        };
+        if !name.starts_with(PREFIX) {
+            continue;
+        }
+
+        let magnet = extras_query
+            .get(entity)
+            .or_else(|_| extras_query.get(child_of.parent()))
+            .map_or(Ok(Magnet::default()), |extras| Magnet::parse(&extras.value))
+            .unwrap_or_else(|err| {
+                warn!("Skipping the extras of `{name}`: {err}");
+                Magnet::default()
+            });
+
+        commands.entity(child_of.parent()).insert((
+            magnet,
+            Collider::ball(magnet.radius),
+            sensor_components("magnet"),
+        ));
+    }
+}
+
+fn track_magnet_contacts(
+    mut commands: Commands,
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    magnets: Query<(), With<Magnet>>,
+    mut balls: Query<Option<&mut InMagnets>, With<Ball>>,
+) {
+    for event in sensor_triggered.read() {
+        let magnet = event.sensor;
+        if !magnets.contains(magnet) {
+            continue;
+        }
+        let Ok(in_magnets) = balls.get_mut(event.other) else {
+            continue;
+        };
+
+        match (in_magnets, event.started) {
+            (Some(mut in_magnets), true) => in_magnets.magnets.push(magnet),
+            (Some(mut in_magnets), false) => {
+                in_magnets.magnets.retain(|&other| other != magnet);
+            }
+            (None, true) => {
+                commands.entity(event.other).insert(InMagnets {
+                    magnets: vec![magnet],
+                    applied: Vec3::ZERO,
+                });
+            }
+            (None, false) => {}
+        }
+    }
+}
+
+fn apply_magnets(
+    magnets: Query<(&Magnet, &GlobalTransform)>,
+    mut balls: Query<(&mut InMagnets, &mut ExternalForce, &GlobalTransform)>,
+) {
+    for (mut in_magnets, mut force, transform) in balls.iter_mut() {
+        // Magnets that were despawned don't send a stop event.
+        in_magnets
+            .magnets
+            .retain(|&magnet| magnets.contains(magnet));
+
+        let position = transform.translation();
+        let pull: Vec3 = in_magnets
+            .magnets
+            .iter()
+            .filter_map(|&magnet| magnets.get(magnet).ok())
+            .map(|(magnet, origin)| {
+                // The sensor is scaled with the parent, so the radius is too.
+                let scaled = Magnet {
+                    radius: magnet.radius * origin.scale().max_element(),
+                    ..*magnet
+                };
+                scaled.pull(position - origin.translation())
+            })
+            .sum();
+
+        force.force += pull - in_magnets.applied;
+        in_magnets.applied = pull;
+    }
+}
@@ -0,0 +1,2524 @@
+//! Inserts rapier physics onto glTF scene objects based on their names.
+//! When a scene instance is ready, every mesh in it whose name starts with the configured prefix
+//! gets a collider built from its vertices, which is inserted together with a rigid body on the
+//! mesh's parent entity.
+//!
+//! The colliders of all the collider meshes of a parent are combined into one, placed with each
+//! mesh's transform relative to the parent. Rapier can't put trimeshes in a compound collider, so
+//! if any of them is a trimesh, they're all merged into a single trimesh instead. The body and
+//! surface settings come from the first of the meshes.
+//!
+//! The `_` separated parts after the prefix, except for the last one which is the object's own
+//! name, are tokens that tweak the generated physics:
+//! - `box`, `ball` or `hull` pick a cheaper shape than the default trimesh: a cuboid or a sphere
+//!   fitted to the mesh's bounding box, or the convex hull of the mesh.
+//! - A material name from [`MeshPhysicsPlugin::materials`], e.g. `collider_ice_floor`.
+//! - `layer<name>`, `nocam`, `ballonly` or `camonly`, see
+//!   [`PhysicsLayerPlugin`](crate::plugins::physics_layer_plugin).
+//! - The body type, used instead of the plugin's `body`. By convention it's the first token, e.g.
+//!   `collider_dyn_m2.5_crate`:
+//!   - `static`: a fixed body, e.g. floors.
+//!   - `kin`: a position based kinematic body, e.g. platforms moved by animations.
+//!   - `kinvel` or `kvel`: a velocity based kinematic body, see [`PlatformMotion`].
+//!   - `dyn`: a dynamic body, e.g. pushable crates. It also gets a [`Velocity`] and some
+//!     [`Damping`] so it comes to rest.
+//! - `m<mass>` sets the mass of the body, e.g. `m2.5`. The default mass computed from a trimesh
+//!   is often wrong or zero.
+//! - `ccd` enables continuous collision detection, so fast objects don't tunnel through thin
+//!   ones. It costs performance, so it's only for the objects that need it.
+//! - `vis` keeps the mesh visible when [`MeshPhysicsPlugin::hide_colliders`] is on.
+//!
+//! Unknown tokens are warned about and ignored.
+//!
+//! Custom properties exported as glTF extras on the mesh or its parent override the values
+//! from the name. The supported keys are:
+//! - `restitution`, `friction`: numbers.
+//! - `mass`, `density`: positive numbers, only one of them can be set. They override the
+//!   mass from the name with a warning.
+//! - `body`: `"fixed"`, `"kinematic"` or `"dynamic"`.
+//! - `sensor`, `ccd`: booleans.
+//! - `limit_min`, `limit_max`, `motor_velocity`: numbers, see the joints below.
+//!
+//! Other keys are ignored. If the extras are malformed, they are skipped with a warning.
+//!
+//! Meshes named `sensor_<label>_*` don't need a configured prefix. They get a convex hull
+//! sensor on their parent and are hidden, and a [`SensorTriggered`] event with the label is sent
+//! whenever something starts or stops touching them, so gameplay like hints, kill zones or
+//! checkpoints only has to listen for its labels.
+//!
+//! Gameplay plugins whose objects need a collider, e.g. the `kill_` volumes or the `elevator_`
+//! platforms, register their prefix with [`register_object_collider`] instead of building the
+//! collider themselves, so it's built, cached and hot reloaded like the other colliders and
+//! counted in the [`PhysicsReady`] of the scene. The plugins only insert their marker components
+//! and react to the [`SensorTriggered`] events of the sensors, which are labeled with the prefix
+//! without the `_`.
+//!
+//! Objects named `hinge_<axis>_*` or `slider_<axis>_*`, where the axis is `x`, `y` or `z` in the
+//! object's own space, become dynamic bodies that swing around or slide along the axis. They're
+//! jointed to the parent of the body, or to an empty named `anchor_<name>` with the same own name
+//! as the object, e.g. `anchor_Hammer` for `hinge_x_Hammer`. The pivot is the anchor's origin, or
+//! the body's origin without an anchor. Tokens can follow the axis like for collider meshes, and
+//! these extras keys are supported too:
+//! - `limit_min`, `limit_max`: the range of the joint, in degrees for hinges and in meters for
+//!   sliders. Both need to be set.
+//! - `motor_velocity`: drives the joint at this speed, in degrees or meters per second.
+//!
+//! Position based kinematic bodies teleport every step, so a ball on a moving platform is left
+//! behind. Velocity based kinematic bodies, from the `kinvel` token or the plugin's `body`, get a
+//! [`PlatformMotion`] that measures how their animation moves them every frame and sets their
+//! [`Velocity`] to match, so the ball is carried along by friction.
+//!
+//! The meshes of a scene are queued sorted by name and processed a few per frame, see
+//! [`ColliderBudget`], so a scene's [`PhysicsReady`] is only sent once all of them are done.
+//!
+//! When a collider mesh is modified, e.g. by hot reloading the glTF file, the physics inserted
+//! for it is removed and inserted again with a collider built from the new mesh.
+
+use std::collections::VecDeque;
+
+use bevy::{
+    ecs::world::OnDespawn,
+    gltf::GltfExtras,
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+    render::mesh::MeshAabb,
+    scene::{SceneInstance, SceneInstanceReady},
+    tasks::{AsyncComputeTaskPool, Task, block_on, poll_once},
+};
+use bevy_rapier3d::{parry::shape::SharedShape, prelude::*, utils::iso_to_transform};
+
+use crate::plugins::physics_layer_plugin::{
+    LayerRegistry, parse_group_token, parse_layer_token, sensor_groups,
+};
+
+/// The systems building the colliders of the scenes and inserting their physics.
+#[derive(SystemSet, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct MeshPhysicsSet;
+
+/// Configuration for one group of collider meshes.
+/// The plugin can be added several times with different prefixes, e.g. one for fixed level
+/// geometry and one for kinematic objects that move with animations.
+#[derive(Clone)]
+pub struct MeshPhysicsPlugin {
+    /// Meshes whose names start with this prefix get physics.
+    pub prefix: String,
+    pub body: RigidBody,
+    pub restitution: f32,
+    /// Leave rapier's default friction when `None`.
+    pub friction: Option<f32>,
+    /// Materials that can be picked with a name token.
+    pub materials: HashMap<String, PhysicsMaterial>,
+    /// Hide the meshes once their collider is inserted, for invisible collision-only geometry.
+    /// Meshes with a `vis` token stay visible.
+    pub hide_colliders: bool,
+}
+
+impl Default for MeshPhysicsPlugin {
+    fn default() -> Self {
+        Self {
+            prefix: "collider_".to_string(),
+            // Kinematic so the objects can still be moved by animations.
+            body: RigidBody::KinematicPositionBased,
+            restitution: 0.8,
+            friction: None,
+            materials: HashMap::from_iter([
+                (
+                    "ice".to_string(),
+                    PhysicsMaterial {
+                        friction: Some(0.02),
+                        ..default()
+                    },
+                ),
+                (
+                    "rubber".to_string(),
+                    PhysicsMaterial {
+                        restitution: Some(1.2),
+                        ..default()
+                    },
+                ),
+                (
+                    "mud".to_string(),
+                    PhysicsMaterial {
+                        friction: Some(1.5),
+                        damping: Some(2.0),
+                        ..default()
+                    },
+                ),
+            ]),
+            hide_colliders: false,
+        }
+    }
+}
+
+impl MeshPhysicsPlugin {
+    /// Parses the tokens of a mesh name that starts with the prefix.
+    pub fn parse_name(&self, name: &str) -> ParsedName {
+        let mut parsed = ParsedName::default();
+
+        let rest = match name.strip_prefix(self.prefix.as_str()) {
+            Some(rest) => rest,
+            // A bare joint name like `hinge_x` is its prefix without the `_`, so it has no tokens.
+            None if self.prefix.strip_suffix('_') == Some(name) => "",
+            None => {
+                warn!(
+                    "`{name}` doesn't start with `{}`, ignoring its tokens.",
+                    self.prefix
+                );
+                return parsed;
+            }
+        };
+        parsed.kind = parse_collider_kind(rest);
+        for token in name_tokens(rest) {
+            if ColliderKind::from_token(token).is_some() {
+                // Already picked by `parse_collider_kind`.
+            } else if let Some(material) = self.materials.get(token) {
+                parsed.material = Some(*material);
+            } else if let Some(layer) = parse_layer_token(token) {
+                parsed.layer = Some(layer.to_string());
+            } else if let Some(groups) = parse_group_token(token) {
+                parsed.groups = Some(groups);
+            } else if token == "ccd" {
+                parsed.ccd = true;
+            } else if token == "vis" {
+                parsed.visible = true;
+            } else if let Some(body) = parse_body_token(token) {
+                parsed.body = Some(body);
+            } else if let Some(mass) = parse_mass_token(token) {
+                match mass {
+                    Some(mass) => parsed.mass = Some(mass),
+                    None => warn!("Invalid mass `{token}` in `{name}`, ignoring it."),
+                }
+            } else {
+                warn!("Unknown token `{token}` in `{name}`, ignoring it.");
+            }
+        }
+
+        parsed
+    }
+}
+
+impl Plugin for MeshPhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        add_collider_pipeline(app);
+        app.world_mut()
+            .resource_mut::<MeshPhysicsConfigs>()
+            .0
+            .push(self.clone());
+    }
+
+    fn is_unique(&self) -> bool {
+        false
+    }
+}
+
+/// Adds the systems building the colliders of the scenes, unless they were already added by a
+/// [`MeshPhysicsPlugin`] or [`register_object_collider`]. Plugins inserting their own colliders
+/// with [`sensor_components`] call it to get the [`SensorTriggered`] events.
+pub fn add_collider_pipeline(app: &mut App) {
+    if app.world().contains_resource::<MeshPhysicsConfigs>() {
+        return;
+    }
+
+    app.init_resource::<MeshPhysicsConfigs>()
+        .init_resource::<ObjectColliders>()
+        .init_resource::<ColliderProgress>()
+        .init_resource::<ColliderCache>()
+        .init_resource::<ColliderQueue>()
+        .init_resource::<ColliderBudget>()
+        .add_event::<PhysicsReady>()
+        .add_event::<SensorTriggered>()
+        .add_systems(
+            Update,
+            (
+                (
+                    invalidate_collider_cache,
+                    reinsert_modified_physics,
+                    insert_physics,
+                    insert_built_colliders,
+                    insert_joints,
+                )
+                    .chain(),
+                apply_surface_damping,
+                send_sensor_events,
+            )
+                .in_set(MeshPhysicsSet),
+        )
+        // After the animations move the platforms and before their velocities are sent to
+        // rapier.
+        .add_systems(
+            PostUpdate,
+            measure_platform_motion
+                .after(bevy::app::Animation)
+                .before(PhysicsSet::SyncBackend),
+        )
+        .add_observer(on_scene_ready)
+        .add_observer(queue_physics)
+        .add_observer(forget_despawned_collider);
+}
+
+/// Gives the meshes whose names start with the prefix of the object collider its physics, on
+/// their parents. The collider pipeline is added if it isn't yet, so gameplay plugins work
+/// without a [`MeshPhysicsPlugin`] too.
+pub fn register_object_collider(app: &mut App, collider: ObjectCollider) {
+    add_collider_pipeline(app);
+    app.world_mut()
+        .resource_mut::<ObjectColliders>()
+        .0
+        .push(collider);
+}
+
+/// All the configurations added through [`MeshPhysicsPlugin`].
+#[derive(Resource, Default)]
+pub struct MeshPhysicsConfigs(pub Vec<MeshPhysicsPlugin>);
+
+impl MeshPhysicsConfigs {
+    /// Returns the first configuration whose prefix matches the name.
+    pub fn find(&self, name: &str) -> Option<&MeshPhysicsPlugin> {
+        self.0
+            .iter()
+            .find(|config| name.starts_with(&config.prefix))
+    }
+}
+
+/// The collider of the objects of a gameplay plugin, see [`register_object_collider`].
+#[derive(Clone, Debug)]
+pub struct ObjectCollider {
+    /// Meshes whose names start with this prefix get the collider.
+    pub prefix: String,
+    pub kind: ColliderKind,
+    pub body: RigidBody,
+    /// Make it a sensor sending [`SensorTriggered`] events, see [`sensor_components`].
+    pub sensor: bool,
+    pub restitution: f32,
+    /// Hide the meshes once their collider is inserted.
+    pub hide_mesh: bool,
+    /// Give a velocity based kinematic body a [`PlatformMotion`], for objects moved by
+    /// animations rather than by setting their velocity.
+    pub platform_motion: bool,
+}
+
+impl Default for ObjectCollider {
+    fn default() -> Self {
+        Self {
+            prefix: String::new(),
+            kind: ColliderKind::TriMesh,
+            body: RigidBody::Fixed,
+            sensor: false,
+            restitution: 0.0,
+            hide_mesh: false,
+            platform_motion: false,
+        }
+    }
+}
+
+impl ObjectCollider {
+    /// A hidden sensor covering the volume of the meshes.
+    pub fn sensor(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.to_string(),
+            // A trimesh sensor only detects its surface, so a hull is used to cover the volume.
+            kind: ColliderKind::Hull,
+            // Kinematic so the sensor can still be moved by animations.
+            body: RigidBody::KinematicPositionBased,
+            sensor: true,
+            hide_mesh: true,
+            ..default()
+        }
+    }
+
+    /// The label of the [`SensorTriggered`] events of a sensor.
+    pub fn label(&self) -> &str {
+        self.prefix.strip_suffix('_').unwrap_or(&self.prefix)
+    }
+
+    fn physics(&self, label: &str) -> ObjectPhysics {
+        ObjectPhysics {
+            body: self.body,
+            restitution: self.restitution,
+            friction: None,
+            mass: None,
+            sensor: false,
+            ccd: false,
+            damping: None,
+            collision_groups: None,
+            sensor_label: self.sensor.then(|| label.to_string()),
+            joint: None,
+            platform_motion: self.platform_motion,
+        }
+    }
+}
+
+/// All the object colliders registered with [`register_object_collider`].
+#[derive(Resource, Default)]
+pub struct ObjectColliders(pub Vec<ObjectCollider>);
+
+impl ObjectColliders {
+    /// Returns the first object collider whose prefix matches the name.
+    pub fn find(&self, name: &str) -> Option<&ObjectCollider> {
+        self.0
+            .iter()
+            .find(|collider| name.starts_with(&collider.prefix))
+    }
+}
+
+/// The components making a collider a sensor that sends [`SensorTriggered`] events with the
+/// label, for sensors whose collider isn't built from a mesh, e.g. a ball around an empty.
+pub fn sensor_components(label: &str) -> impl Bundle {
+    (
+        Sensor,
+        sensor_groups(),
+        SensorVolume {
+            label: label.to_string(),
+        },
+        ActiveEvents::COLLISION_EVENTS,
+    )
+}
+
+/// The seconds rapier advances the simulation by in a frame lasting `frame_delta` seconds, for
+/// converting its contact forces to impulses and moving bodies in step with it.
+pub fn physics_dt(mode: &TimestepMode, frame_delta: f32) -> f32 {
+    match *mode {
+        TimestepMode::Fixed { dt, .. } | TimestepMode::Interpolated { dt, .. } => dt,
+        TimestepMode::Variable {
+            max_dt, time_scale, ..
+        } => (frame_delta * time_scale).min(max_dt),
+    }
+}
+
+/// Queues the collider meshes in a scene that don't have physics yet.
+#[derive(Event)]
+struct InsertScenePhysics;
+
+fn on_scene_ready(trigger: Trigger<SceneInstanceReady>, mut commands: Commands) {
+    commands.trigger_targets(InsertScenePhysics, trigger.target());
+}
+
+/// Queues the collider meshes of a scene that don't have physics yet for [`insert_physics`].
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn queue_physics(
+    trigger: Trigger<InsertScenePhysics>,
+    mut queue: ResMut<ColliderQueue>,
+    mut progress: ResMut<ColliderProgress>,
+    mut physics_ready: EventWriter<PhysicsReady>,
+    configs: Res<MeshPhysicsConfigs>,
+    objects: Res<ObjectColliders>,
+    children: Query<&Children>,
+    query: Query<&Name, (With<Mesh3d>, With<ChildOf>, Without<PhysicsProcessed>)>,
+) {
+    let scene = trigger.target();
+    let mut meshes: Vec<(&Name, Entity)> = children
+        .iter_descendants(scene)
+        .filter(|entity| !queue.queued.contains(entity))
+        .filter_map(|entity| query.get(entity).ok().map(|name| (name, entity)))
+        .filter(|(name, _)| {
+            parse_sensor_label(name).is_some()
+                || parse_joint_name(name).is_some()
+                || objects.find(name.as_str()).is_some()
+                || configs.find(name.as_str()).is_some()
+        })
+        .collect();
+    // Sorted so repeated loads process the meshes in the same order.
+    meshes.sort_by(|(name1, entity1), (name2, entity2)| {
+        name1
+            .as_str()
+            .cmp(name2.as_str())
+            .then(entity1.cmp(entity2))
+    });
+
+    info!("Queued {} collider meshes in scene {scene}.", meshes.len());
+
+    progress.pending += meshes.len();
+    let scene_progress = progress.scenes.entry(scene).or_default();
+    scene_progress.pending += meshes.len();
+    // Nothing to wait for, e.g. a scene without collider meshes.
+    if scene_progress.pending == 0 {
+        progress.scenes.remove(&scene);
+        physics_ready.write(PhysicsReady {
+            scene,
+            colliders_inserted: 0,
+        });
+    }
+
+    for (_, entity) in meshes {
+        queue.queued.insert(entity);
+        queue.meshes.push_back(QueuedMesh { entity, scene });
+    }
+}
+
+/// Starts building the colliders of the queued meshes, at most
+/// [`ColliderBudget::meshes_per_frame`] of them.
+#[allow(clippy::too_many_arguments)]
+fn insert_physics(
+    mut commands: Commands,
+    mut queue: ResMut<ColliderQueue>,
+    budget: Res<ColliderBudget>,
+    mut progress: ResMut<ColliderProgress>,
+    mut cache: ResMut<ColliderCache>,
+    mut physics_ready: EventWriter<PhysicsReady>,
+    configs: Res<MeshPhysicsConfigs>,
+    objects: Res<ObjectColliders>,
+    meshes: Res<Assets<Mesh>>,
+    layers: Option<Res<LayerRegistry>>,
+    children: Query<&Children>,
+    query: Query<(&Name, &Mesh3d, &ChildOf), Without<PhysicsProcessed>>,
+    extras_query: Query<&GltfExtras>,
+    names: Query<&Name>,
+) {
+    let task_pool = AsyncComputeTaskPool::get();
+    for _ in 0..budget.meshes_per_frame {
+        let Some(QueuedMesh { entity, scene }) = queue.meshes.pop_front() else {
+            break;
+        };
+        queue.queued.remove(&entity);
+
+        let queued = 'mesh: {
+            // The mesh was despawned or got its physics another way while it was queued.
+            let Ok((name, mesh3d, child_of)) = query.get(entity) else {
+                break 'mesh false;
+            };
+            let (kind, physics, hide_mesh) = if let Some(label) = parse_sensor_label(name) {
+                // Hidden right away because the sensor only marks a volume of the level.
+                commands.entity(entity).insert(Visibility::Hidden);
+                let sensor = ObjectCollider::sensor("sensor_");
+                (sensor.kind, sensor.physics(label), false)
+            } else if let Some(object) = objects.find(name.as_str()) {
+                (
+                    object.kind,
+                    object.physics(object.label()),
+                    object.hide_mesh,
+                )
+            } else if let Some((joint_kind, axis)) = parse_joint_name(name) {
+                let extras = parse_extras(name, &extras_query, entity, child_of.parent());
+                // The axis is part of the prefix so the rest is parsed like a collider name.
+                let config = MeshPhysicsPlugin {
+                    prefix: format!("{}_{}_", joint_kind.prefix(), axis_name(axis)),
+                    body: RigidBody::Dynamic,
+                    ..default()
+                };
+                let (kind, mut physics, hide_mesh) =
+                    resolve_physics(&config, name, &extras, layers.as_deref());
+
+                let own_name = name.rsplit('_').next().unwrap_or_default();
+                let anchor_name = format!("anchor_{own_name}");
+                let anchor = children.iter_descendants(scene).find(|&descendant| {
+                    names
+                        .get(descendant)
+                        .is_ok_and(|name| name.as_str() == anchor_name)
+                });
+                physics.joint = Some(JointSetup {
+                    kind: joint_kind,
+                    axis,
+                    anchor,
+                    limits: match (extras.limit_min, extras.limit_max) {
+                        (Some(min), Some(max)) => Some([min, max]),
+                        (None, None) => None,
+                        _ => {
+                            warn!(
+                                "`{name}` needs both `limit_min` and `limit_max`, ignoring its \
+                                limits."
+                            );
+                            None
+                        }
+                    },
+                    motor_velocity: extras.motor_velocity,
+                });
+                (kind, physics, hide_mesh)
+            } else if let Some(config) = configs.find(name.as_str()) {
+                let extras = parse_extras(name, &extras_query, entity, child_of.parent());
+                resolve_physics(config, name, &extras, layers.as_deref())
+            } else {
+                break 'mesh false;
+            };
+
+            // Instances of the same mesh share one collider, which is only built once.
+            let key = (mesh3d.id(), kind);
+            let task = if cache.colliders.contains_key(&key) || cache.building.contains(&key) {
+                progress.cache_hits += 1;
+                None
+            } else {
+                // Building a trimesh from a big mesh takes a while, so it's done off the main
+                // thread.
+                let Some(mesh) = meshes.get(&mesh3d.0) else {
+                    error!(
+                        "The mesh {:?} of `{name}` isn't loaded, skipping its collider.",
+                        mesh3d.0
+                    );
+                    break 'mesh false;
+                };
+                let mesh = mesh.clone();
+                cache.building.insert(key);
+                progress.built += 1;
+                Some(task_pool.spawn(async move { build_collider_with_fallback(&mesh, kind) }))
+            };
+
+            progress
+                .targets
+                .entry(child_of.parent())
+                .or_default()
+                .pending += 1;
+            commands.entity(entity).insert((
+                PendingCollider {
+                    key,
+                    task,
+                    target: child_of.parent(),
+                    scene,
+                    physics,
+                    hide_mesh,
+                },
+                PhysicsProcessed {
+                    target: child_of.parent(),
+                },
+            ));
+            true
+        };
+
+        if !queued {
+            skip_queued_mesh(&mut progress, &mut physics_ready, scene);
+        }
+    }
+}
+
+/// Counts a queued mesh that won't get a collider as done, and sends the [`PhysicsReady`] of its
+/// scene if it was the last one.
+fn skip_queued_mesh(
+    progress: &mut ColliderProgress,
+    physics_ready: &mut EventWriter<PhysicsReady>,
+    scene: Entity,
+) {
+    progress.pending -= 1;
+    let Some(scene_progress) = progress.scenes.get_mut(&scene) else {
+        return;
+    };
+    scene_progress.pending = scene_progress.pending.saturating_sub(1);
+    if scene_progress.pending == 0 {
+        let colliders_inserted = scene_progress.inserted;
+        progress.scenes.remove(&scene);
+        physics_ready.write(PhysicsReady {
+            scene,
+            colliders_inserted,
+        });
+    }
+}
+
+/// Parses the extras of a mesh, or of its parent if the mesh has none.
+fn parse_extras(
+    name: &str,
+    extras_query: &Query<&GltfExtras>,
+    entity: Entity,
+    parent: Entity,
+) -> PhysicsExtras {
+    extras_query
+        .get(entity)
+        .or_else(|_| extras_query.get(parent))
+        .map_or(Ok(PhysicsExtras::default()), |extras| {
+            PhysicsExtras::parse(&extras.value)
+        })
+        .unwrap_or_else(|err| {
+            warn!("Skipping the extras of `{name}`: {err}");
+            PhysicsExtras::default()
+        })
+}
+
+/// Resolves the physics of a mesh matching the config from its name and glTF extras, and
+/// whether the mesh should be hidden.
+fn resolve_physics(
+    config: &MeshPhysicsPlugin,
+    name: &str,
+    extras: &PhysicsExtras,
+    layers: Option<&LayerRegistry>,
+) -> (ColliderKind, ObjectPhysics, bool) {
+    let parsed = config.parse_name(name);
+    let material = parsed.material.unwrap_or_default();
+
+    let physics = ObjectPhysics {
+        body: extras.body.or(parsed.body).unwrap_or(config.body),
+        restitution: extras
+            .restitution
+            .or(material.restitution)
+            .unwrap_or(config.restitution),
+        friction: extras.friction.or(material.friction).or(config.friction),
+        mass: match (extras.mass, parsed.mass) {
+            (Some(mass), name_mass) => {
+                if name_mass.is_some() {
+                    warn!(
+                        "The mass of `{name}` is set by both its name and extras, using {mass:?}."
+                    );
+                }
+                Some(mass)
+            }
+            (None, name_mass) => name_mass.map(MassSetting::Mass),
+        },
+        sensor: extras.sensor.unwrap_or_default(),
+        ccd: extras.ccd.unwrap_or(parsed.ccd),
+        damping: material.damping,
+        collision_groups: parsed.groups.or_else(|| {
+            let layer = parsed.layer.as_ref()?;
+            // Bit indices work without registering any layer.
+            let default_layers = LayerRegistry::default();
+            let groups = layers.unwrap_or(&default_layers).collision_groups(layer);
+            if groups.is_none() {
+                warn!("Unknown layer `{layer}` in `{name}`, ignoring it.");
+            }
+            groups
+        }),
+        sensor_label: None,
+        joint: None,
+        platform_motion: true,
+    };
+
+    let hide_mesh = config.hide_colliders && !parsed.visible;
+    (parsed.kind, physics, hide_mesh)
+}
+
+/// Counts the collider of a mesh despawned while it was being built as done, e.g. when the level
+/// is unloaded right after loading, so the [`PhysicsReady`] of its scene is still sent.
+fn forget_despawned_collider(
+    trigger: Trigger<OnDespawn, PendingCollider>,
+    mut progress: ResMut<ColliderProgress>,
+    mut cache: ResMut<ColliderCache>,
+    mut physics_ready: EventWriter<PhysicsReady>,
+    query: Query<&PendingCollider>,
+) {
+    let Ok(pending) = query.get(trigger.target()) else {
+        return;
+    };
+
+    // Dropping the task cancels it, so the collider isn't being built anymore.
+    if pending.task.is_some() {
+        cache.building.remove(&pending.key);
+    }
+    if let Some(parts) = progress.targets.get_mut(&pending.target) {
+        parts.pending = parts.pending.saturating_sub(1);
+        if parts.pending == 0 {
+            progress.targets.remove(&pending.target);
+        }
+    }
+    skip_queued_mesh(&mut progress, &mut physics_ready, pending.scene);
+}
+
+fn insert_built_colliders(
+    mut commands: Commands,
+    mut progress: ResMut<ColliderProgress>,
+    mut cache: ResMut<ColliderCache>,
+    mut physics_ready: EventWriter<PhysicsReady>,
+    mut query: Query<(Entity, &Name, Option<&Transform>, &mut PendingCollider)>,
+) {
+    for (entity, name, transform, mut pending) in query.iter_mut() {
+        let key = pending.key;
+        let built = match &mut pending.task {
+            Some(task) => {
+                let Some(built) = block_on(poll_once(task)) else {
+                    continue;
+                };
+                cache.building.remove(&key);
+                cache.colliders.insert(key, built.clone());
+                built
+            }
+            // Another instance of the mesh is building the collider.
+            None => match cache.colliders.get(&key) {
+                Some(built) => built.clone(),
+                None if cache.building.contains(&key) => continue,
+                // The cache was cleared by a mesh change before the collider was built.
+                None => None,
+            },
+        };
+
+        commands.entity(entity).remove::<PendingCollider>();
+        progress.pending -= 1;
+        let scene = progress.scenes.entry(pending.scene).or_default();
+        scene.pending = scene.pending.saturating_sub(1);
+
+        let (mesh_id, kind) = key;
+        let parts = progress.targets.entry(pending.target).or_default();
+        parts.pending = parts.pending.saturating_sub(1);
+        match built {
+            Some((collider, built_kind)) => {
+                if built_kind != kind {
+                    warn!(
+                        "Failed to build a {kind:?} collider for `{name}` (mesh {mesh_id:?}), \
+                        using a {built_kind:?} instead."
+                    );
+                }
+                parts
+                    .colliders
+                    .push((collider, transform.copied().unwrap_or_default()));
+                parts.physics.get_or_insert_with(|| pending.physics.clone());
+                if pending.hide_mesh {
+                    commands.entity(entity).insert(Visibility::Hidden);
+                }
+            }
+            None => error!(
+                "Failed to build any collider for `{name}` (mesh {mesh_id:?}), it won't have physics."
+            ),
+        }
+
+        // The last collider mesh of the target is done.
+        if parts.pending == 0 {
+            let parts = progress.targets.remove(&pending.target).unwrap_or_default();
+            let collider = combine_colliders(parts.colliders);
+            match (collider, parts.physics, commands.get_entity(pending.target)) {
+                (Some(collider), Some(physics), Ok(mut target)) => {
+                    physics.insert(&mut target, collider);
+                    progress.inserted += 1;
+                    progress.scenes.entry(pending.scene).or_default().inserted += 1;
+                }
+                // The scene was despawned while the collider was being built.
+                (Some(_), Some(_), Err(_)) => {}
+                _ => error!("Failed to combine the colliders of {}.", pending.target),
+            }
+        }
+
+        if progress
+            .scenes
+            .get(&pending.scene)
+            .is_some_and(|scene| scene.pending == 0)
+        {
+            let scene = progress.scenes.remove(&pending.scene).unwrap_or_default();
+            physics_ready.write(PhysicsReady {
+                scene: pending.scene,
+                colliders_inserted: scene.inserted,
+            });
+        }
+
+        if progress.pending == 0 {
+            info!(
+                "Inserted {} colliders ({} built, {} reused from the cache).",
+                progress.inserted, progress.built, progress.cache_hits
+            );
+            *progress = ColliderProgress::default();
+        }
+    }
+}
+
+/// How strongly a joint motor drives the joint towards its velocity.
+const MOTOR_FACTOR: f32 = 10.0;
+
+fn insert_joints(
+    mut commands: Commands,
+    query: Query<(Entity, &PendingJoint, &GlobalTransform, &ChildOf)>,
+    transforms: Query<&GlobalTransform>,
+    bodies: Query<(), With<RigidBody>>,
+) {
+    for (entity, pending, body_transform, child_of) in query.iter() {
+        let joint = &pending.0;
+        let anchor = joint.anchor.unwrap_or(child_of.parent());
+        let Ok(anchor_transform) = transforms.get(anchor) else {
+            continue;
+        };
+        commands.entity(entity).remove::<PendingJoint>();
+
+        let pivot = match joint.anchor {
+            Some(_) => anchor_transform.translation(),
+            None => body_transform.translation(),
+        };
+        let (_, anchor_rotation, _) = anchor_transform.to_scale_rotation_translation();
+        let (_, body_rotation, _) = body_transform.to_scale_rotation_translation();
+        let local_anchor1 = anchor_transform.affine().inverse().transform_point3(pivot);
+        let local_anchor2 = body_transform.affine().inverse().transform_point3(pivot);
+        // The axis is given in the body's space, the anchor may be rotated differently.
+        let local_axis1 = anchor_rotation.inverse() * body_rotation * joint.axis;
+
+        let impulse_joint = match joint.kind {
+            JointKind::Hinge => {
+                let mut builder = RevoluteJointBuilder::new(joint.axis)
+                    .local_anchor1(local_anchor1)
+                    .local_anchor2(local_anchor2);
+                if let Some([min, max]) = joint.limits {
+                    builder = builder.limits([min.to_radians(), max.to_radians()]);
+                }
+                if let Some(velocity) = joint.motor_velocity {
+                    builder = builder.motor_velocity(velocity.to_radians(), MOTOR_FACTOR);
+                }
+                let mut revolute = builder.build();
+                revolute.data.set_local_axis1(local_axis1);
+                ImpulseJoint::new(anchor, revolute)
+            }
+            JointKind::Slider => {
+                let mut builder = PrismaticJointBuilder::new(joint.axis)
+                    .local_anchor1(local_anchor1)
+                    .local_anchor2(local_anchor2);
+                if let Some(limits) = joint.limits {
+                    builder = builder.limits(limits);
+                }
+                if let Some(velocity) = joint.motor_velocity {
+                    builder = builder.motor_velocity(velocity, MOTOR_FACTOR);
+                }
+                let mut prismatic = builder.build();
+                prismatic.data.set_local_axis1(local_axis1);
+                ImpulseJoint::new(anchor, prismatic)
+            }
+        };
+        commands.entity(entity).insert(impulse_joint);
+
+        // Rapier only joints two bodies, so an anchor without a body holds the joint in place.
+        if !bodies.contains(anchor) {
+            commands.entity(anchor).insert(RigidBody::Fixed);
+        }
+    }
+}
+
+fn invalidate_collider_cache(
+    mut mesh_events: EventReader<AssetEvent<Mesh>>,
+    mut cache: ResMut<ColliderCache>,
+) {
+    for event in mesh_events.read() {
+        if let AssetEvent::Modified { id }
+        | AssetEvent::Removed { id }
+        | AssetEvent::Unused { id } = event
+        {
+            cache.colliders.retain(|(mesh_id, _), _| mesh_id != id);
+        }
+    }
+}
+
+/// Removes the physics of the meshes that were modified, e.g. by a hot reload, and inserts it
+/// again from the new meshes.
+/// Only the targets of [`PhysicsProcessed`] are touched, so bodies added by other plugins keep
+/// their physics.
+fn reinsert_modified_physics(
+    mut commands: Commands,
+    mut mesh_events: EventReader<AssetEvent<Mesh>>,
+    mut progress: ResMut<ColliderProgress>,
+    mut cache: ResMut<ColliderCache>,
+    query: Query<(Entity, &Mesh3d, &PhysicsProcessed, Option<&PendingCollider>)>,
+    parents: Query<&ChildOf>,
+    scene_instances: Query<(), With<SceneInstance>>,
+) {
+    let modified: HashSet<_> = mesh_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect();
+    if modified.is_empty() {
+        return;
+    }
+
+    // A reload usually modifies many meshes of a scene at once, but each scene is only
+    // processed once. Respawned entities don't have the marker yet, so they're left to the
+    // `SceneInstanceReady` observer, and whichever of the two runs second skips the meshes the
+    // other one marked.
+    // The collider of a target combines all its meshes, so the unmodified meshes of the same
+    // target are inserted again too.
+    let targets: HashSet<_> = query
+        .iter()
+        .filter(|(_, mesh3d, _, _)| modified.contains(&mesh3d.id()))
+        .map(|(_, _, processed, _)| processed.target)
+        .collect();
+
+    let mut scenes = HashSet::new();
+    for (entity, _, processed, pending) in query.iter() {
+        if !targets.contains(&processed.target) {
+            continue;
+        }
+
+        // Dropping the pending task cancels building the stale collider.
+        if let Some(pending) = pending {
+            progress.pending -= 1;
+            if let Some(scene) = progress.scenes.get_mut(&pending.scene) {
+                scene.pending = scene.pending.saturating_sub(1);
+            }
+        }
+        commands
+            .entity(entity)
+            .remove::<(PhysicsProcessed, PendingCollider)>();
+        if let Ok(mut target) = commands.get_entity(processed.target) {
+            // Nested, since a bundle tuple has at most 15 components.
+            target.remove::<(
+                (
+                    RigidBody,
+                    Collider,
+                    Restitution,
+                    Friction,
+                    ColliderMassProperties,
+                    AdditionalMassProperties,
+                    Sensor,
+                    Ccd,
+                ),
+                (
+                    DampingSurface,
+                    CollisionGroups,
+                    SensorVolume,
+                    ActiveEvents,
+                    Velocity,
+                    Damping,
+                    PlatformMotion,
+                    ImpulseJoint,
+                    PendingJoint,
+                ),
+            )>();
+        }
+
+        if let Some(scene) = parents
+            .iter_ancestors(entity)
+            .find(|&ancestor| scene_instances.contains(ancestor))
+        {
+            scenes.insert(scene);
+        }
+    }
+    for target in &targets {
+        progress.targets.remove(target);
+    }
+    cache
+        .building
+        .retain(|(mesh_id, _)| !modified.contains(mesh_id));
+
+    for scene in scenes {
+        info!("Reinserting the modified colliders of scene {scene}.");
+        commands.trigger_targets(InsertScenePhysics, scene);
+    }
+}
+
+/// The number of colliders still queued or being built.
+/// Gameplay should wait for the [`PhysicsReady`] of its scene or for `pending` to reach zero.
+/// The totals are reset whenever `pending` reaches zero.
+#[derive(Resource, Default)]
+pub struct ColliderProgress {
+    pub pending: usize,
+    pub inserted: usize,
+    pub built: usize,
+    /// Colliders reused from the [`ColliderCache`].
+    pub cache_hits: usize,
+    /// The scenes whose colliders are still being built.
+    scenes: HashMap<Entity, SceneProgress>,
+    /// The entities whose collider meshes are still being built.
+    targets: HashMap<Entity, TargetParts>,
+}
+
+/// The collider meshes waiting for [`insert_physics`], in the order they're processed.
+#[derive(Resource, Default)]
+struct ColliderQueue {
+    meshes: VecDeque<QueuedMesh>,
+    /// The entities in `meshes`, so a scene reported again doesn't queue them twice.
+    queued: HashSet<Entity>,
+}
+
+struct QueuedMesh {
+    entity: Entity,
+    scene: Entity,
+}
+
+/// How many queued collider meshes are processed per frame.
+/// Processing a mesh inserts several components and starts building its collider, so a big
+/// level is spread over a few frames instead of stuttering when it appears. Set it to
+/// `usize::MAX` to process whole scenes at once.
+#[derive(Resource)]
+pub struct ColliderBudget {
+    pub meshes_per_frame: usize,
+}
+
+impl Default for ColliderBudget {
+    fn default() -> Self {
+        Self {
+            meshes_per_frame: 32,
+        }
+    }
+}
+
+#[derive(Default)]
+struct SceneProgress {
+    pending: usize,
+    inserted: usize,
+}
+
+/// The colliders of the meshes of one entity, which are combined once they're all built.
+#[derive(Default)]
+struct TargetParts {
+    pending: usize,
+    /// The colliders and the transforms of their meshes relative to the entity.
+    colliders: Vec<(Collider, Transform)>,
+    /// The physics of the first mesh.
+    physics: Option<ObjectPhysics>,
+}
+
+/// The colliders built for each mesh and shape, so instances of a mesh don't rebuild them.
+/// `None` is cached for meshes whose collider failed to build.
+/// Entries are dropped when their mesh asset is modified or unloaded.
+#[derive(Resource, Default)]
+pub struct ColliderCache {
+    pub colliders: HashMap<ColliderKey, BuiltCollider>,
+    building: HashSet<ColliderKey>,
+}
+
+pub type ColliderKey = (AssetId<Mesh>, ColliderKind);
+
+/// A collider and the kind it was actually built as, which differs from the requested kind
+/// when building that failed.
+pub type BuiltCollider = Option<(Collider, ColliderKind)>;
+
+/// Sent when all the colliders of a loaded scene are inserted, also when the scene has none.
+/// Gameplay like controlling the ball should wait for it, so nothing falls through the level
+/// while it's loading.
+#[derive(Event)]
+pub struct PhysicsReady {
+    /// The scene instance root.
+    pub scene: Entity,
+    pub colliders_inserted: usize,
+}
+
+/// A joint created from a `hinge_` or `slider_` object name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JointKind {
+    Hinge,
+    Slider,
+}
+
+impl JointKind {
+    pub fn prefix(self) -> &'static str {
+        match self {
+            Self::Hinge => "hinge",
+            Self::Slider => "slider",
+        }
+    }
+}
+
+/// The joint settings of a `hinge_` or `slider_` object.
+#[derive(Clone, Debug)]
+struct JointSetup {
+    kind: JointKind,
+    /// In the body's space.
+    axis: Vec3,
+    /// The `anchor_` empty, or `None` to joint the body to its parent.
+    anchor: Option<Entity>,
+    /// In degrees for hinges and meters for sliders.
+    limits: Option<[f32; 2]>,
+    motor_velocity: Option<f32>,
+}
+
+/// A joint waiting for its body to be inserted.
+#[derive(Component)]
+struct PendingJoint(JointSetup);
+
+/// Sets the [`Velocity`] of a velocity based kinematic body from how its transform changed since
+/// the last frame, e.g. by an animation.
+#[derive(Component, Default)]
+pub struct PlatformMotion {
+    previous: Option<Transform>,
+}
+
+impl PlatformMotion {
+    /// Forgets the last transform, so a body that was teleported doesn't get a velocity from it.
+    pub fn reset(&mut self) {
+        self.previous = None;
+    }
+}
+
+/// A sensor created from a `sensor_<label>_*` mesh, on the mesh's parent.
+#[derive(Component)]
+pub struct SensorVolume {
+    pub label: String,
+}
+
+/// Sent when something starts or stops touching a [`SensorVolume`].
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct SensorTriggered {
+    pub label: String,
+    pub sensor: Entity,
+    /// The entity touching the sensor.
+    pub other: Entity,
+    /// `true` when the contact started, `false` when it stopped.
+    pub started: bool,
+}
+
+/// Marks a mesh whose physics was already inserted, so it isn't processed twice when the
+/// scene instance is reported again.
+#[derive(Component)]
+pub struct PhysicsProcessed {
+    /// The entity that got the physics.
+    pub target: Entity,
+}
+
+/// A collider waiting to be inserted, kept on the mesh entity.
+#[derive(Component)]
+struct PendingCollider {
+    key: ColliderKey,
+    /// The collider being built on the async compute pool.
+    /// `None` if it's built for another instance of the same mesh.
+    task: Option<Task<BuiltCollider>>,
+    /// The entity that gets the physics.
+    target: Entity,
+    scene: Entity,
+    physics: ObjectPhysics,
+    /// See [`MeshPhysicsPlugin::hide_colliders`].
+    hide_mesh: bool,
+}
+
+/// The physics settings resolved for one collider mesh.
+#[derive(Clone)]
+struct ObjectPhysics {
+    body: RigidBody,
+    restitution: f32,
+    friction: Option<f32>,
+    mass: Option<MassSetting>,
+    sensor: bool,
+    ccd: bool,
+    damping: Option<f32>,
+    collision_groups: Option<CollisionGroups>,
+    /// Makes it a sensor sending [`SensorTriggered`] events with the label.
+    sensor_label: Option<String>,
+    joint: Option<JointSetup>,
+    /// See [`ObjectCollider::platform_motion`].
+    platform_motion: bool,
+}
+
+impl ObjectPhysics {
+    fn insert(&self, entity: &mut EntityCommands, collider: Collider) {
+        entity.insert((
+            self.body,
+            collider,
+            Restitution::coefficient(self.restitution),
+        ));
+        if let Some(friction) = self.friction {
+            entity.insert(Friction::coefficient(friction));
+        }
+        match self.mass {
+            // Added to the body so it doesn't depend on the mass computed from the collider.
+            Some(MassSetting::Mass(mass)) => {
+                entity.insert(AdditionalMassProperties::Mass(mass));
+            }
+            Some(MassSetting::Density(density)) => {
+                entity.insert(ColliderMassProperties::Density(density));
+            }
+            None => {}
+        }
+        if self.sensor {
+            entity.insert(Sensor);
+        }
+        if self.ccd {
+            entity.insert(Ccd::enabled());
+        }
+        if let Some(damping) = self.damping {
+            entity.insert(DampingSurface::Extra(damping));
+        }
+        if let Some(collision_groups) = self.collision_groups {
+            entity.insert(collision_groups);
+        }
+        if let Some(label) = &self.sensor_label {
+            entity.insert(sensor_components(label));
+        }
+        if let Some(joint) = &self.joint {
+            // The joint is created once the transforms of the body and anchor are known.
+            entity.insert(PendingJoint(joint.clone()));
+        }
+        match self.body {
+            RigidBody::KinematicVelocityBased => {
+                entity.insert(Velocity::zero());
+                if self.platform_motion {
+                    entity.insert(PlatformMotion::default());
+                }
+            }
+            RigidBody::Dynamic => {
+                entity.insert((
+                    Velocity::zero(),
+                    Damping {
+                        linear_damping: 0.1,
+                        angular_damping: 0.5,
+                    },
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Physics settings parsed from a mesh name.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ParsedName {
+    pub kind: ColliderKind,
+    pub material: Option<PhysicsMaterial>,
+    /// See [`PhysicsLayerPlugin`](crate::plugins::physics_layer_plugin::PhysicsLayerPlugin).
+    pub layer: Option<String>,
+    /// Groups picked with a token like `nocam`, which take precedence over the layer.
+    pub groups: Option<CollisionGroups>,
+    pub body: Option<RigidBody>,
+    pub mass: Option<f32>,
+    pub ccd: bool,
+    pub visible: bool,
+}
+
+/// The collider shape generated for a mesh.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ColliderKind {
+    #[default]
+    TriMesh,
+    Box,
+    Ball,
+    Hull,
+}
+
+impl ColliderKind {
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "box" => Some(Self::Box),
+            "ball" => Some(Self::Ball),
+            "hull" => Some(Self::Hull),
+            _ => None,
+        }
+    }
+}
+
+/// Surface properties that override the plugin's defaults.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PhysicsMaterial {
+    pub friction: Option<f32>,
+    pub restitution: Option<f32>,
+    /// Extra damping for the bodies touching the surface.
+    pub damping: Option<f32>,
+}
+
+/// How the mass of an object is set.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MassSetting {
+    /// The mass of the whole body.
+    Mass(f32),
+    /// The density of the collider, which the mass is computed from.
+    Density(f32),
+}
+
+/// Physics settings read from the glTF extras of an object.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PhysicsExtras {
+    pub restitution: Option<f32>,
+    pub friction: Option<f32>,
+    pub mass: Option<MassSetting>,
+    pub body: Option<RigidBody>,
+    pub sensor: Option<bool>,
+    pub ccd: Option<bool>,
+    pub limit_min: Option<f32>,
+    pub limit_max: Option<f32>,
+    pub motor_velocity: Option<f32>,
+}
+
+impl PhysicsExtras {
+    /// Parses the extras JSON. Returns an error describing the first malformed value.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let value: serde_json::Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        let Some(object) = value.as_object() else {
+            return Err("the extras are not a JSON object".to_string());
+        };
+
+        let number = |key: &str| {
+            object
+                .get(key)
+                .map(|value| {
+                    value
+                        .as_f64()
+                        .map(|value| value as f32)
+                        .ok_or_else(|| format!("`{key}` should be a number, got {value}"))
+                })
+                .transpose()
+        };
+
+        let body = object
+            .get("body")
+            .map(|value| match value.as_str() {
+                Some("fixed") => Ok(RigidBody::Fixed),
+                Some("kinematic") => Ok(RigidBody::KinematicPositionBased),
+                Some("dynamic") => Ok(RigidBody::Dynamic),
+                _ => Err(format!(
+                    "`body` should be \"fixed\", \"kinematic\" or \"dynamic\", got {value}"
+                )),
+            })
+            .transpose()?;
+
+        let boolean = |key: &str| {
+            object
+                .get(key)
+                .map(|value| {
+                    value
+                        .as_bool()
+                        .ok_or_else(|| format!("`{key}` should be a boolean, got {value}"))
+                })
+                .transpose()
+        };
+
+        let positive = |key: &str| {
+            number(key)?
+                .map(|value| {
+                    (value.is_finite() && value > 0.0)
+                        .then_some(value)
+                        .ok_or_else(|| format!("`{key}` should be positive, got {value}"))
+                })
+                .transpose()
+        };
+        let mass = match (positive("mass")?, positive("density")?) {
+            (Some(_), Some(_)) => {
+                return Err("only one of `mass` and `density` can be set".to_string());
+            }
+            (Some(mass), None) => Some(MassSetting::Mass(mass)),
+            (None, Some(density)) => Some(MassSetting::Density(density)),
+            (None, None) => None,
+        };
+
+        Ok(Self {
+            restitution: number("restitution")?,
+            friction: number("friction")?,
+            mass,
+            body,
+            sensor: boolean("sensor")?,
+            ccd: boolean("ccd")?,
+            limit_min: number("limit_min")?,
+            limit_max: number("limit_max")?,
+            motor_velocity: number("motor_velocity")?,
+        })
+    }
+}
+
+/// How this collider changes the damping of the bodies touching it.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub enum DampingSurface {
+    /// Added to the linear and angular damping, e.g. by mud.
+    Extra(f32),
+    /// Replaces the linear damping, e.g. on ice, so the bodies keep rolling.
+    Slippery(f32),
+}
+
+/// The damping a body had before it touched a [`DampingSurface`].
+#[derive(Component)]
+struct DampingBeforeSurface(Damping);
+
+/// Returns the tokens of a mesh name with the prefix already stripped.
+/// The last `_` separated part is the object's own name, so it's never a token.
+pub fn name_tokens(name: &str) -> impl Iterator<Item = &str> {
+    let tokens = name.rsplit_once('_').map_or("", |(tokens, _)| tokens);
+    tokens.split('_').filter(|token| !token.is_empty())
+}
+
+/// Parses a body type token like `dyn`.
+pub fn parse_body_token(token: &str) -> Option<RigidBody> {
+    match token {
+        "static" => Some(RigidBody::Fixed),
+        "kin" => Some(RigidBody::KinematicPositionBased),
+        "kinvel" | "kvel" => Some(RigidBody::KinematicVelocityBased),
+        "dyn" => Some(RigidBody::Dynamic),
+        _ => None,
+    }
+}
+
+/// Parses a mass token like `m2.5`.
+/// Returns `Some(None)` for a mass token whose value isn't a positive finite number.
+pub fn parse_mass_token(token: &str) -> Option<Option<f32>> {
+    let value = token.strip_prefix('m')?;
+    // Leave the token to the other parsers unless it looks like a number, e.g. `mud`.
+    if !value.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') {
+        return None;
+    }
+    Some(
+        value
+            .parse::<f32>()
+            .ok()
+            .filter(|mass| mass.is_finite() && *mass > 0.0),
+    )
+}
+
+/// Returns the joint kind and axis of an object name like `hinge_x_Hammer`.
+pub fn parse_joint_name(name: &str) -> Option<(JointKind, Vec3)> {
+    let (kind, rest) = if let Some(rest) = name.strip_prefix("hinge_") {
+        (JointKind::Hinge, rest)
+    } else {
+        (JointKind::Slider, name.strip_prefix("slider_")?)
+    };
+    let axis = match rest.split('_').next()? {
+        "x" => Vec3::X,
+        "y" => Vec3::Y,
+        "z" => Vec3::Z,
+        _ => return None,
+    };
+    Some((kind, axis))
+}
+
+fn axis_name(axis: Vec3) -> &'static str {
+    if axis == Vec3::X {
+        "x"
+    } else if axis == Vec3::Y {
+        "y"
+    } else {
+        "z"
+    }
+}
+
+/// Returns the label of a mesh name like `sensor_KillZone_floor`.
+pub fn parse_sensor_label(name: &str) -> Option<&str> {
+    let rest = name.strip_prefix("sensor_")?;
+    let label = rest.split('_').next()?;
+    (!label.is_empty()).then_some(label)
+}
+
+/// Translates a rapier collision event into a [`SensorTriggered`] event if one of its entities
+/// is a sensor, which can be either member of the pair. `sensor_label` returns the label of an
+/// entity that's a sensor.
+pub fn translate_sensor_event<'a>(
+    event: &CollisionEvent,
+    sensor_label: impl Fn(Entity) -> Option<&'a str>,
+) -> Option<SensorTriggered> {
+    let (entity1, entity2, started) = match *event {
+        CollisionEvent::Started(entity1, entity2, _) => (entity1, entity2, true),
+        CollisionEvent::Stopped(entity1, entity2, _) => (entity1, entity2, false),
+    };
+
+    [(entity1, entity2), (entity2, entity1)]
+        .into_iter()
+        .find_map(|(sensor, other)| {
+            sensor_label(sensor).map(|label| SensorTriggered {
+                label: label.to_string(),
+                sensor,
+                other,
+                started,
+            })
+        })
+}
+
+/// Parses the collider shape from a mesh name with the prefix already stripped.
+/// The first shape token wins, and names without one get a trimesh.
+pub fn parse_collider_kind(name: &str) -> ColliderKind {
+    name_tokens(name)
+        .find_map(ColliderKind::from_token)
+        .unwrap_or_default()
+}
+
+/// Builds the collider of the kind, falling back to a convex hull and then a bounding box
+/// for degenerate meshes, e.g. a flattened plane that has no valid trimesh.
+pub fn build_collider_with_fallback(mesh: &Mesh, kind: ColliderKind) -> BuiltCollider {
+    [kind, ColliderKind::Hull, ColliderKind::Box]
+        .into_iter()
+        .find_map(|kind| build_collider(mesh, kind).map(|collider| (collider, kind)))
+}
+
+/// Builds the collider in the mesh's local space.
+fn build_collider(mesh: &Mesh, kind: ColliderKind) -> Option<Collider> {
+    match kind {
+        ColliderKind::TriMesh => Collider::from_bevy_mesh(
+            mesh,
+            &ComputedColliderShape::TriMesh(TriMeshFlags::default()),
+        ),
+        ColliderKind::Hull => Collider::from_bevy_mesh(mesh, &ComputedColliderShape::ConvexHull),
+        ColliderKind::Box | ColliderKind::Ball => {
+            let aabb = mesh.compute_aabb()?;
+            let half_extents = Vec3::from(aabb.half_extents);
+            let shape = if kind == ColliderKind::Box {
+                Collider::cuboid(half_extents.x, half_extents.y, half_extents.z)
+            } else {
+                Collider::ball(half_extents.max_element())
+            };
+
+            // The bounding box isn't necessarily centered at the mesh origin.
+            Some(Collider::compound(vec![(
+                aabb.center.into(),
+                Quat::IDENTITY,
+                shape,
+            )]))
+        }
+    }
+}
+
+/// Combines colliders placed with the transforms into one collider.
+/// A single collider without an offset is returned as is, otherwise the colliders are put in a
+/// compound, or merged into one trimesh if any of them is a trimesh, which a compound can't
+/// contain.
+pub fn combine_colliders(parts: Vec<(Collider, Transform)>) -> Option<Collider> {
+    if parts.len() == 1 && parts[0].1 == Transform::IDENTITY {
+        return parts.into_iter().next().map(|(collider, _)| collider);
+    }
+
+    if parts
+        .iter()
+        .any(|(collider, _)| collider.raw.as_trimesh().is_some())
+    {
+        let mut vertices = Vec::new();
+        let mut indices = Vec::new();
+        for (collider, transform) in &parts {
+            append_triangles(&collider.raw, *transform, &mut vertices, &mut indices);
+        }
+        return Collider::trimesh(vertices, indices).ok();
+    }
+
+    let mut shapes = Vec::new();
+    for (collider, transform) in parts {
+        // Compounds can't be nested, so the parts of the box and ball colliders are added
+        // directly.
+        let subshapes = match collider.raw.as_compound() {
+            Some(compound) => compound
+                .shapes()
+                .iter()
+                .map(|(isometry, shape)| {
+                    (iso_to_transform(isometry), Collider::from(shape.clone()))
+                })
+                .collect(),
+            None => vec![(Transform::IDENTITY, collider)],
+        };
+
+        for (local, mut shape) in subshapes {
+            shape.set_scale(transform.scale, 10);
+            shapes.push((
+                transform.transform_point(local.translation),
+                transform.rotation * local.rotation,
+                shape,
+            ));
+        }
+    }
+    (!shapes.is_empty()).then(|| Collider::compound(shapes))
+}
+
+/// Appends the triangles of the shape placed with the transform.
+pub(crate) fn append_triangles(
+    shape: &SharedShape,
+    transform: Transform,
+    vertices: &mut Vec<Vec3>,
+    indices: &mut Vec<[u32; 3]>,
+) {
+    let (points, triangles) = if let Some(trimesh) = shape.as_trimesh() {
+        (trimesh.vertices().to_vec(), trimesh.indices().to_vec())
+    } else if let Some(cuboid) = shape.as_cuboid() {
+        cuboid.to_trimesh()
+    } else if let Some(ball) = shape.as_ball() {
+        ball.to_trimesh(16, 16)
+    } else if let Some(hull) = shape.as_convex_polyhedron() {
+        hull.to_trimesh()
+    } else if let Some(compound) = shape.as_compound() {
+        for (isometry, shape) in compound.shapes() {
+            let local = iso_to_transform(isometry);
+            append_triangles(shape, transform * local, vertices, indices);
+        }
+        return;
+    } else {
+        warn!(
+            "Can't merge a {:?} collider into a trimesh.",
+            shape.shape_type()
+        );
+        return;
+    };
+
+    let offset = vertices.len() as u32;
+    vertices.extend(
+        points
+            .into_iter()
+            .map(|point| transform.transform_point(Vec3::new(point.x, point.y, point.z))),
+    );
+    indices.extend(
+        triangles
+            .into_iter()
+            .map(|triangle| triangle.map(|index| index + offset)),
+    );
+}
+
+fn measure_platform_motion(
+    time: Res<Time>,
+    mut query: Query<(&mut Transform, &mut Velocity, &mut PlatformMotion)>,
+) {
+    let dt = time.delta_secs();
+    if dt == 0.0 {
+        return;
+    }
+
+    for (mut transform, mut velocity, mut motion) in query.iter_mut() {
+        let current = *transform;
+        if let Some(previous) = motion.previous {
+            velocity.linvel = (current.translation - previous.translation) / dt;
+            velocity.angvel =
+                (current.rotation * previous.rotation.inverse()).to_scaled_axis() / dt;
+            // Rapier moves the body with the velocity, so it's put back where it was to not move
+            // twice as far as the animation.
+            *transform = previous;
+        }
+        motion.previous = Some(current);
+    }
+}
+
+fn send_sensor_events(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut sensor_triggered: EventWriter<SensorTriggered>,
+    sensors: Query<&SensorVolume>,
+) {
+    for event in collision_events.read() {
+        let translated = translate_sensor_event(event, |entity| {
+            sensors.get(entity).ok().map(|sensor| sensor.label.as_str())
+        });
+        if let Some(translated) = translated {
+            sensor_triggered.write(translated);
+        }
+    }
+}
+
+fn apply_surface_damping(
+    mut commands: Commands,
+    rapier_context: ReadRapierContext,
+    surfaces: Query<(Entity, &DampingSurface)>,
+    mut bodies: Query<(Entity, &mut Damping, Option<&DampingBeforeSurface>)>,
+) {
+    let Ok(context) = rapier_context.single() else {
+        return;
+    };
+
+    // The largest extra damping and the lowest slippery damping of the touched surfaces.
+    let mut surface_damping = HashMap::<Entity, (f32, Option<f32>)>::default();
+    for (surface, damping) in surfaces.iter() {
+        for pair in context.contact_pairs_with(surface) {
+            if !pair.has_any_active_contact() {
+                continue;
+            }
+
+            let other = if pair.collider1() == Some(surface) {
+                pair.collider2()
+            } else {
+                pair.collider1()
+            };
+            let Some(other) = other else {
+                continue;
+            };
+            let (extra, slippery) = surface_damping.entry(other).or_default();
+            match *damping {
+                DampingSurface::Extra(damping) => *extra = extra.max(damping),
+                DampingSurface::Slippery(damping) => {
+                    *slippery = Some(slippery.map_or(damping, |slippery| slippery.min(damping)));
+                }
+            }
+        }
+    }
+
+    for (entity, mut damping, before) in bodies.iter_mut() {
+        match (surface_damping.get(&entity), before) {
+            (Some(&(extra, slippery)), before) => {
+                let base = match before {
+                    Some(before) => before.0,
+                    None => {
+                        commands
+                            .entity(entity)
+                            .insert(DampingBeforeSurface(*damping));
+                        *damping
+                    }
+                };
+                damping.linear_damping = slippery.unwrap_or(base.linear_damping) + extra;
+                damping.angular_damping = base.angular_damping + extra;
+            }
+            (None, Some(before)) => {
+                *damping = before.0;
+                commands.entity(entity).remove::<DampingBeforeSurface>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::{
+        render::{
+            mesh::{Indices, PrimitiveTopology},
+            render_asset::RenderAssetUsages,
+        },
+        scene::ScenePlugin,
+        time::TimeUpdateStrategy,
+    };
+    use bevy_rapier3d::rapier::geometry::CollisionEventFlags;
+
+    use super::*;
+
+    #[test]
+    fn parses_collider_kinds() {
+        for (name, kind) in [
+            ("floor", ColliderKind::TriMesh),
+            ("box_crate", ColliderKind::Box),
+            ("ball_rock", ColliderKind::Ball),
+            ("hull_rock", ColliderKind::Hull),
+            ("dyn_box_m2_crate", ColliderKind::Box),
+            ("ice_floor", ColliderKind::TriMesh),
+            // The own name is never a token.
+            ("box", ColliderKind::TriMesh),
+            ("wall_box", ColliderKind::TriMesh),
+            // Unknown tokens fall back to a trimesh.
+            ("cone_spike", ColliderKind::TriMesh),
+        ] {
+            assert_eq!(parse_collider_kind(name), kind, "{name}");
+        }
+    }
+
+    #[test]
+    fn parse_name_uses_the_collider_kind() {
+        let config = MeshPhysicsPlugin::default();
+        assert_eq!(
+            config.parse_name("collider_box_crate").kind,
+            ColliderKind::Box
+        );
+        assert_eq!(
+            config.parse_name("collider_crate").kind,
+            ColliderKind::TriMesh
+        );
+        assert_eq!(
+            config.parse_name("collider_cone_crate").kind,
+            ColliderKind::TriMesh
+        );
+    }
+
+    #[test]
+    fn parses_joint_names() {
+        assert_eq!(
+            parse_joint_name("hinge_x_Hammer"),
+            Some((JointKind::Hinge, Vec3::X))
+        );
+        assert_eq!(
+            parse_joint_name("slider_z_dyn_m2_Door"),
+            Some((JointKind::Slider, Vec3::Z))
+        );
+        // Bare names without an own name are valid too.
+        assert_eq!(
+            parse_joint_name("hinge_y"),
+            Some((JointKind::Hinge, Vec3::Y))
+        );
+        assert_eq!(
+            parse_joint_name("slider_z"),
+            Some((JointKind::Slider, Vec3::Z))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_joint_names() {
+        for name in [
+            "hinge_",
+            "hinge",
+            "hinge_w_Hammer",
+            "hingex_Hammer",
+            "slider__Door",
+            "",
+        ] {
+            assert_eq!(parse_joint_name(name), None, "{name}");
+        }
+    }
+
+    #[test]
+    fn parses_joint_name_tokens() {
+        let config = MeshPhysicsPlugin {
+            prefix: "hinge_x_".to_string(),
+            ..default()
+        };
+        let parsed = config.parse_name("hinge_x_hull_m2_ccd_Hammer");
+        assert_eq!(parsed.kind, ColliderKind::Hull);
+        assert_eq!(parsed.mass, Some(2.0));
+        assert!(parsed.ccd);
+    }
+
+    #[test]
+    fn parses_bare_and_mismatched_names_without_panicking() {
+        let config = MeshPhysicsPlugin {
+            prefix: "hinge_x_".to_string(),
+            ..default()
+        };
+        assert_eq!(config.parse_name("hinge_x"), ParsedName::default());
+        assert_eq!(config.parse_name("hinge"), ParsedName::default());
+        assert_eq!(config.parse_name(""), ParsedName::default());
+        assert_eq!(config.parse_name("hinge_x_"), ParsedName::default());
+    }
+
+    #[test]
+    fn finds_the_first_config_with_a_matching_prefix() {
+        let configs = MeshPhysicsConfigs(vec![
+            MeshPhysicsPlugin {
+                prefix: "collider_kin_".to_string(),
+                body: RigidBody::KinematicVelocityBased,
+                ..default()
+            },
+            MeshPhysicsPlugin::default(),
+        ]);
+
+        let find_body = |name| configs.find(name).map(|config| config.body);
+        assert_eq!(
+            find_body("collider_kin_Lift"),
+            Some(RigidBody::KinematicVelocityBased)
+        );
+        assert_eq!(
+            find_body("collider_Floor"),
+            Some(RigidBody::KinematicPositionBased)
+        );
+        assert_eq!(find_body("Floor"), None);
+    }
+
+    #[test]
+    fn adding_the_plugin_again_adds_its_config() {
+        let mut app = App::new();
+        app.add_plugins(MeshPhysicsPlugin::default())
+            .add_plugins(MeshPhysicsPlugin {
+                prefix: "dynamic_".to_string(),
+                body: RigidBody::Dynamic,
+                restitution: 0.2,
+                ..default()
+            });
+
+        let configs = &app.world().resource::<MeshPhysicsConfigs>().0;
+        let prefixes: Vec<_> = configs
+            .iter()
+            .map(|config| config.prefix.as_str())
+            .collect();
+        assert_eq!(prefixes, ["collider_", "dynamic_"]);
+        assert_eq!(configs[1].restitution, 0.2);
+    }
+
+    #[test]
+    fn inserts_the_physics_of_each_config_on_the_parents() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .add_event::<CollisionEvent>()
+            .add_plugins(MeshPhysicsPlugin::default())
+            .add_plugins(MeshPhysicsPlugin {
+                prefix: "static_".to_string(),
+                body: RigidBody::Fixed,
+                restitution: 0.1,
+                friction: Some(0.7),
+                ..default()
+            });
+        let mesh = app
+            .world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::default());
+
+        let world = app.world_mut();
+        let scene = world.spawn(Transform::default()).id();
+        let platform = world.spawn((Transform::default(), ChildOf(scene))).id();
+        world.spawn((
+            Name::new("collider_Platform"),
+            Mesh3d(mesh.clone()),
+            ChildOf(platform),
+        ));
+        let floor = world.spawn((Transform::default(), ChildOf(scene))).id();
+        world.spawn((Name::new("static_Floor"), Mesh3d(mesh), ChildOf(floor)));
+        world.trigger_targets(InsertScenePhysics, scene);
+        for _ in 0..100 {
+            app.update();
+            if app.world().resource::<ColliderProgress>().pending == 0 {
+                break;
+            }
+        }
+
+        let world = app.world();
+        assert_eq!(
+            world.get::<RigidBody>(platform),
+            Some(&RigidBody::KinematicPositionBased)
+        );
+        assert_eq!(
+            world
+                .get::<Restitution>(platform)
+                .map(|restitution| restitution.coefficient),
+            Some(0.8)
+        );
+        // The default config leaves rapier's friction.
+        assert!(world.get::<Friction>(platform).is_none());
+
+        assert_eq!(world.get::<RigidBody>(floor), Some(&RigidBody::Fixed));
+        assert_eq!(
+            world
+                .get::<Restitution>(floor)
+                .map(|restitution| restitution.coefficient),
+            Some(0.1)
+        );
+        assert_eq!(
+            world
+                .get::<Friction>(floor)
+                .map(|friction| friction.coefficient),
+            Some(0.7)
+        );
+    }
+
+    #[test]
+    fn parses_physics_extras() {
+        let extras = PhysicsExtras::parse(
+            r#"{"restitution": 0.5, "friction": 1, "mass": 2.5, "body": "dynamic",
+            "sensor": true, "ccd": false, "limit_min": -45, "author": "ignored"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            extras,
+            PhysicsExtras {
+                restitution: Some(0.5),
+                friction: Some(1.0),
+                mass: Some(MassSetting::Mass(2.5)),
+                body: Some(RigidBody::Dynamic),
+                sensor: Some(true),
+                ccd: Some(false),
+                limit_min: Some(-45.0),
+                ..default()
+            }
+        );
+        assert_eq!(
+            PhysicsExtras::parse(r#"{"density": 3}"#).unwrap().mass,
+            Some(MassSetting::Density(3.0))
+        );
+        assert_eq!(PhysicsExtras::parse("{}"), Ok(PhysicsExtras::default()));
+    }
+
+    #[test]
+    fn rejects_malformed_extras() {
+        for json in [
+            "not json",
+            "[1, 2]",
+            r#"{"friction": "high"}"#,
+            r#"{"body": "floating"}"#,
+            r#"{"sensor": 1}"#,
+            r#"{"mass": 0}"#,
+            r#"{"density": -1}"#,
+            r#"{"mass": 1, "density": 2}"#,
+        ] {
+            assert!(PhysicsExtras::parse(json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn extras_override_the_name_and_material() {
+        let config = MeshPhysicsPlugin::default();
+        let extras = PhysicsExtras {
+            restitution: Some(0.1),
+            mass: Some(MassSetting::Density(4.0)),
+            body: Some(RigidBody::Fixed),
+            ..default()
+        };
+        let (kind, physics, _) =
+            resolve_physics(&config, "collider_rubber_dyn_m2_box_Crate", &extras, None);
+        assert_eq!(kind, ColliderKind::Box);
+        assert_eq!(physics.body, RigidBody::Fixed);
+        assert_eq!(physics.restitution, 0.1);
+        assert_eq!(physics.mass, Some(MassSetting::Density(4.0)));
+
+        // Without extras, the name and the material are used.
+        let (_, physics, _) = resolve_physics(
+            &config,
+            "collider_rubber_dyn_m2_box_Crate",
+            &PhysicsExtras::default(),
+            None,
+        );
+        assert_eq!(physics.body, RigidBody::Dynamic);
+        assert_eq!(physics.restitution, 1.2);
+        assert_eq!(physics.mass, Some(MassSetting::Mass(2.0)));
+    }
+
+    #[test]
+    fn falls_back_to_simpler_colliders() {
+        let built_kind =
+            |mesh: &Mesh, kind| build_collider_with_fallback(mesh, kind).map(|(_, kind)| kind);
+
+        let cube = Mesh::from(Cuboid::default());
+        assert_eq!(
+            built_kind(&cube, ColliderKind::TriMesh),
+            Some(ColliderKind::TriMesh)
+        );
+        assert_eq!(
+            built_kind(&cube, ColliderKind::Ball),
+            Some(ColliderKind::Ball)
+        );
+
+        // A trimesh needs triangles, but the hull only needs the vertices.
+        let mut no_triangles = cube.clone();
+        no_triangles.insert_indices(Indices::U32(Vec::new()));
+        assert_eq!(
+            built_kind(&no_triangles, ColliderKind::TriMesh),
+            Some(ColliderKind::Hull)
+        );
+
+        // Neither can be built without indices, but the bounding box can.
+        let unindexed = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+        );
+        assert_eq!(
+            built_kind(&unindexed, ColliderKind::TriMesh),
+            Some(ColliderKind::Box)
+        );
+    }
+
+    #[test]
+    fn builds_no_collider_without_vertices() {
+        let empty = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        );
+        for kind in [
+            ColliderKind::TriMesh,
+            ColliderKind::Hull,
+            ColliderKind::Box,
+            ColliderKind::Ball,
+        ] {
+            assert!(
+                build_collider_with_fallback(&empty, kind).is_none(),
+                "{kind:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn inserts_the_physics_of_each_scene_instance_once() {
+        #[derive(Resource, Default)]
+        struct Counts {
+            ready: Vec<Entity>,
+            collider_inserts: HashMap<Entity, usize>,
+        }
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            MeshPhysicsPlugin::default(),
+        ))
+        .init_asset::<Mesh>()
+        .add_event::<CollisionEvent>()
+        .init_resource::<Counts>()
+        .add_systems(
+            Last,
+            |mut physics_ready: EventReader<PhysicsReady>, mut counts: ResMut<Counts>| {
+                counts
+                    .ready
+                    .extend(physics_ready.read().map(|ready| ready.scene));
+            },
+        )
+        .add_observer(
+            |trigger: Trigger<OnInsert, Collider>, mut counts: ResMut<Counts>| {
+                *counts.collider_inserts.entry(trigger.target()).or_default() += 1;
+            },
+        );
+        let mesh = app
+            .world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::default());
+
+        // Two instances of the same glTF scene, sharing the mesh.
+        let mut spawn_instance = || {
+            let world = app.world_mut();
+            let scene = world.spawn(Transform::default()).id();
+            let object = world.spawn((Transform::default(), ChildOf(scene))).id();
+            world.spawn((
+                Name::new("collider_box_Crate"),
+                Mesh3d(mesh.clone()),
+                ChildOf(object),
+            ));
+            (scene, object)
+        };
+        let (scene1, object1) = spawn_instance();
+        let (scene2, object2) = spawn_instance();
+
+        // The first instance is reported again while its meshes are queued.
+        for scene in [scene1, scene2, scene1] {
+            app.world_mut().trigger_targets(InsertScenePhysics, scene);
+        }
+        for _ in 0..100 {
+            app.update();
+            if app.world().resource::<Counts>().ready.len() == 2 {
+                break;
+            }
+        }
+
+        let counts = app.world().resource::<Counts>();
+        assert_eq!(counts.ready, [scene1, scene2]);
+        assert_eq!(counts.collider_inserts.get(&object1), Some(&1));
+        assert_eq!(counts.collider_inserts.get(&object2), Some(&1));
+        assert_eq!(app.world().resource::<ColliderCache>().colliders.len(), 1);
+
+        // Reported again once done, it's ready right away without inserting anything.
+        app.world_mut().trigger_targets(InsertScenePhysics, scene1);
+        app.update();
+        app.update();
+        let counts = app.world().resource::<Counts>();
+        assert_eq!(counts.ready, [scene1, scene2, scene1]);
+        assert_eq!(counts.collider_inserts.get(&object1), Some(&1));
+    }
+
+    #[test]
+    fn translates_collision_events_of_either_member() {
+        let sensor = Entity::from_raw(1);
+        let ball = Entity::from_raw(2);
+        let label = |entity| (entity == sensor).then_some("Goal");
+        let triggered = |started| {
+            Some(SensorTriggered {
+                label: "Goal".to_string(),
+                sensor,
+                other: ball,
+                started,
+            })
+        };
+
+        let flags = CollisionEventFlags::SENSOR;
+        assert_eq!(
+            translate_sensor_event(&CollisionEvent::Started(sensor, ball, flags), label),
+            triggered(true)
+        );
+        assert_eq!(
+            translate_sensor_event(&CollisionEvent::Stopped(ball, sensor, flags), label),
+            triggered(false)
+        );
+        assert_eq!(
+            translate_sensor_event(&CollisionEvent::Started(ball, ball, flags), label),
+            None
+        );
+        assert_eq!(parse_sensor_label("sensor_Goal_volume"), Some("Goal"));
+        assert_eq!(parse_sensor_label("sensor__volume"), None);
+    }
+
+    #[test]
+    fn sends_sensor_events_when_a_ball_passes_through() {
+        #[derive(Resource, Default)]
+        struct Triggered(Vec<SensorTriggered>);
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin::default(),
+            ScenePlugin,
+            RapierPhysicsPlugin::<NoUserData>::default(),
+            MeshPhysicsPlugin::default(),
+        ))
+        .init_asset::<Mesh>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            1.0 / 60.0,
+        )))
+        .init_resource::<Triggered>()
+        .add_systems(
+            Last,
+            |mut sensor_triggered: EventReader<SensorTriggered>,
+             mut triggered: ResMut<Triggered>| {
+                triggered.0.extend(sensor_triggered.read().cloned());
+            },
+        );
+        let mesh = app
+            .world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::default());
+
+        let world = app.world_mut();
+        let scene = world.spawn(Transform::default()).id();
+        let sensor = world.spawn((Transform::default(), ChildOf(scene))).id();
+        world.spawn((
+            Name::new("sensor_Goal_volume"),
+            Mesh3d(mesh),
+            ChildOf(sensor),
+        ));
+        let ball = world
+            .spawn((
+                Transform::from_xyz(0.0, 3.0, 0.0),
+                RigidBody::Dynamic,
+                Collider::ball(0.2),
+            ))
+            .id();
+        world.trigger_targets(InsertScenePhysics, scene);
+
+        // The ball falls through the sensor within two seconds.
+        for _ in 0..120 {
+            app.update();
+        }
+
+        let triggered = &app.world().resource::<Triggered>().0;
+        let expected = |started| SensorTriggered {
+            label: "Goal".to_string(),
+            sensor,
+            other: ball,
+            started,
+        };
+        assert_eq!(triggered, &[expected(true), expected(false)]);
+        assert!(app.world().get::<Sensor>(sensor).is_some());
+    }
+
+    #[test]
+    fn group_tokens_take_precedence_over_layers() {
+        let (_, physics, _) = resolve_physics(
+            &MeshPhysicsPlugin::default(),
+            "collider_layer5_nocam_Vase",
+            &PhysicsExtras::default(),
+            None,
+        );
+        assert_eq!(physics.collision_groups, parse_group_token("nocam"));
+
+        let (_, physics, _) = resolve_physics(
+            &MeshPhysicsPlugin::default(),
+            "collider_layer5_Vase",
+            &PhysicsExtras::default(),
+            None,
+        );
+        assert_eq!(
+            physics.collision_groups,
+            LayerRegistry::default().collision_groups("5")
+        );
+    }
+
+    #[test]
+    fn keeps_a_single_collider_without_an_offset() {
+        let collider = combine_colliders(vec![(Collider::ball(1.0), Transform::IDENTITY)]).unwrap();
+        assert!(collider.raw.as_ball().is_some());
+        assert!(combine_colliders(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn combines_colliders_into_a_compound_with_their_offsets() {
+        let offset = Transform::from_xyz(0.0, 2.0, 0.0);
+        let collider = combine_colliders(vec![
+            (Collider::cuboid(1.0, 1.0, 1.0), Transform::IDENTITY),
+            (Collider::ball(0.5), offset),
+        ])
+        .unwrap();
+
+        let compound = collider.raw.as_compound().unwrap();
+        let translations: Vec<_> = compound
+            .shapes()
+            .iter()
+            .map(|(isometry, _)| iso_to_transform(isometry).translation)
+            .collect();
+        assert_eq!(translations, [Vec3::ZERO, offset.translation]);
+    }
+
+    #[test]
+    fn inserts_a_compound_of_offset_child_meshes() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            MeshPhysicsPlugin::default(),
+        ))
+        .init_asset::<Mesh>()
+        .add_event::<CollisionEvent>();
+        let mesh = app
+            .world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::default());
+
+        let left = Transform::from_xyz(-2.0, 0.0, 0.0);
+        let right = Transform::from_xyz(0.0, 1.0, 3.0)
+            .with_rotation(Quat::from_rotation_y(std::f32::consts::FRAC_PI_2));
+        let world = app.world_mut();
+        let scene = world.spawn(Transform::default()).id();
+        let object = world.spawn((Transform::default(), ChildOf(scene))).id();
+        for (name, transform) in [("collider_box_Left", left), ("collider_box_Right", right)] {
+            world.spawn((
+                Name::new(name),
+                Mesh3d(mesh.clone()),
+                transform,
+                ChildOf(object),
+            ));
+        }
+        world.trigger_targets(InsertScenePhysics, scene);
+        for _ in 0..100 {
+            app.update();
+            if app.world().resource::<ColliderProgress>().pending == 0 {
+                break;
+            }
+        }
+
+        let collider = app.world().get::<Collider>(object).unwrap();
+        let compound = collider.raw.as_compound().unwrap();
+        let placements: Vec<_> = compound
+            .shapes()
+            .iter()
+            .map(|(isometry, _)| {
+                let transform = iso_to_transform(isometry);
+                (transform.translation, transform.rotation)
+            })
+            .collect();
+        assert_eq!(placements.len(), 2);
+        assert!(placements[0].0.abs_diff_eq(left.translation, 1e-5));
+        assert!(placements[1].0.abs_diff_eq(right.translation, 1e-5));
+        assert!(placements[1].1.abs_diff_eq(right.rotation, 1e-5));
+    }
+
+    #[test]
+    fn bakes_the_transform_of_a_scaled_rotated_ramp_into_its_collider() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            MeshPhysicsPlugin::default(),
+        ))
+        .init_asset::<Mesh>()
+        .add_event::<CollisionEvent>();
+        let ramp_mesh = Mesh::from(Cuboid::new(2.0, 0.2, 4.0));
+        let mesh = app
+            .world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(ramp_mesh.clone());
+
+        // Scaled 2x and tilted in Blender without applying the transforms.
+        let parent = Transform::from_xyz(5.0, 0.0, -1.0).with_rotation(Quat::from_rotation_y(0.5));
+        let ramp = Transform::from_xyz(1.0, 0.5, 0.0)
+            .with_rotation(Quat::from_rotation_x(-0.35))
+            .with_scale(Vec3::splat(2.0));
+        let world = app.world_mut();
+        let scene = world.spawn(Transform::default()).id();
+        let object = world.spawn((parent, ChildOf(scene))).id();
+        world.spawn((
+            Name::new("collider_Ramp"),
+            Mesh3d(mesh),
+            ramp,
+            ChildOf(object),
+        ));
+        world.trigger_targets(InsertScenePhysics, scene);
+        for _ in 0..100 {
+            app.update();
+            if app.world().resource::<ColliderProgress>().pending == 0 {
+                break;
+            }
+        }
+
+        // Where the ramp is drawn, and where its collider ends up on the parent.
+        let visual: Vec<Vec3> = ramp_mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(|positions| positions.as_float3())
+            .unwrap()
+            .iter()
+            .map(|&position| (parent * ramp).transform_point(position.into()))
+            .collect();
+        let collider = app.world().get::<Collider>(object).unwrap();
+        let physical: Vec<Vec3> = collider
+            .raw
+            .as_trimesh()
+            .unwrap()
+            .vertices()
+            .iter()
+            .map(|point| parent.transform_point(Vec3::new(point.x, point.y, point.z)))
+            .collect();
+
+        let contains = |points: &[Vec3], point: Vec3| {
+            points.iter().any(|other| other.abs_diff_eq(point, 1e-4))
+        };
+        assert!(!physical.is_empty());
+        assert!(physical.iter().all(|&point| contains(&visual, point)));
+        assert!(visual.iter().all(|&point| contains(&physical, point)));
+    }
+
+    #[test]
+    fn flattens_box_colliders_with_an_off_center_bounding_box() {
+        // The bounding box of the mesh is centered at (1, 0, 0).
+        let mesh = Mesh::from(Cuboid::default()).translated_by(Vec3::X);
+        let (collider, _) = build_collider_with_fallback(&mesh, ColliderKind::Box).unwrap();
+
+        let collider = combine_colliders(vec![(
+            collider,
+            Transform::from_xyz(0.0, 0.0, 3.0).with_scale(Vec3::splat(2.0)),
+        )])
+        .unwrap();
+
+        let compound = collider.raw.as_compound().unwrap();
+        let [(isometry, shape)] = compound.shapes() else {
+            panic!("The box should be the only part of the compound.");
+        };
+        // Not nested in another compound, and both the offset and the scale applied.
+        assert_eq!(
+            iso_to_transform(isometry).translation,
+            Vec3::new(2.0, 0.0, 3.0)
+        );
+        let cuboid = shape.as_cuboid().unwrap();
+        assert_eq!(cuboid.half_extents.as_slice(), [1.0; 3]);
+    }
+
+    #[test]
+    fn merges_into_a_trimesh_when_any_part_is_a_trimesh() {
+        let cube = Mesh::from(Cuboid::default());
+        let (trimesh, _) = build_collider_with_fallback(&cube, ColliderKind::TriMesh).unwrap();
+        let trimesh_vertices = trimesh.raw.as_trimesh().unwrap().vertices().len();
+
+        let collider = combine_colliders(vec![
+            (trimesh, Transform::IDENTITY),
+            (
+                Collider::cuboid(0.5, 0.5, 0.5),
+                Transform::from_xyz(5.0, 0.0, 0.0),
+            ),
+        ])
+        .unwrap();
+
+        let merged = collider.raw.as_trimesh().unwrap();
+        // A cuboid is turned into 8 vertices.
+        assert_eq!(merged.vertices().len(), trimesh_vertices + 8);
+        assert_eq!(merged.local_aabb().maxs.x, 5.5);
+    }
+
+    #[test]
+    fn parses_body_tokens() {
+        for (token, body) in [
+            ("static", Some(RigidBody::Fixed)),
+            ("kin", Some(RigidBody::KinematicPositionBased)),
+            ("kinvel", Some(RigidBody::KinematicVelocityBased)),
+            ("kvel", Some(RigidBody::KinematicVelocityBased)),
+            ("dyn", Some(RigidBody::Dynamic)),
+            ("dynamic", None),
+        ] {
+            assert_eq!(parse_body_token(token), body, "{token}");
+        }
+    }
+
+    #[test]
+    fn body_tokens_override_the_plugin_body() {
+        let config = MeshPhysicsPlugin::default();
+        let body = |name| {
+            resolve_physics(&config, name, &PhysicsExtras::default(), None)
+                .1
+                .body
+        };
+        assert_eq!(body("collider_Floor"), config.body);
+        assert_eq!(body("collider_static_Floor"), RigidBody::Fixed);
+        assert_eq!(body("collider_dyn_m2_Crate"), RigidBody::Dynamic);
+        assert_eq!(
+            body("collider_kinvel_Lift"),
+            RigidBody::KinematicVelocityBased
+        );
+        // The own name is never a token.
+        assert_eq!(body("collider_dyn"), config.body);
+    }
+
+    #[test]
+    fn inserts_registered_object_colliders() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()))
+            .init_asset::<Mesh>()
+            .add_event::<CollisionEvent>();
+        // Without a `MeshPhysicsPlugin`, registering adds the pipeline.
+        register_object_collider(&mut app, ObjectCollider::sensor("kill_"));
+        register_object_collider(
+            &mut app,
+            ObjectCollider {
+                prefix: "lift_".to_string(),
+                body: RigidBody::KinematicVelocityBased,
+                ..default()
+            },
+        );
+        let mesh = app
+            .world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(Cuboid::default());
+
+        let world = app.world_mut();
+        let scene = world.spawn(Transform::default()).id();
+        let volume = world.spawn((Transform::default(), ChildOf(scene))).id();
+        let volume_mesh = world
+            .spawn((Name::new("kill_Pit"), Mesh3d(mesh.clone()), ChildOf(volume)))
+            .id();
+        let lift = world.spawn((Transform::default(), ChildOf(scene))).id();
+        world.spawn((Name::new("lift_Cage"), Mesh3d(mesh), ChildOf(lift)));
+        world.trigger_targets(InsertScenePhysics, scene);
+        for _ in 0..100 {
+            app.update();
+            if app.world().resource::<ColliderProgress>().pending == 0 {
+                break;
+            }
+        }
+
+        let world = app.world();
+        assert_eq!(
+            world
+                .get::<SensorVolume>(volume)
+                .map(|sensor| sensor.label.as_str()),
+            Some("kill")
+        );
+        assert!(world.get::<Sensor>(volume).is_some());
+        assert_eq!(
+            world.get::<Visibility>(volume_mesh),
+            Some(&Visibility::Hidden)
+        );
+
+        assert_eq!(
+            world.get::<RigidBody>(lift),
+            Some(&RigidBody::KinematicVelocityBased)
+        );
+        assert!(world.get::<Collider>(lift).is_some());
+        assert!(world.get::<Sensor>(lift).is_none());
+        // It's moved by setting its velocity, not by an animation.
+        assert!(world.get::<PlatformMotion>(lift).is_none());
+    }
+}
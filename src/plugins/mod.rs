@@ -1 +1,55 @@
+pub mod achievement_plugin;
+pub mod ball_boost_plugin;
+pub mod ball_material_switch_plugin;
+pub mod ball_physics_plugin;
+pub mod ball_sound_plugin;
+pub mod ball_trail_plugin;
+pub mod boost_pad_plugin;
+pub mod bounce_pad_plugin;
+pub mod campaign_plugin;
+pub mod checkpoint_plugin;
+pub mod cinematic_camera_plugin;
+pub mod collider_debug_plugin;
+#[cfg(feature = "debug_overlay")]
+pub mod debug_overlay_plugin;
+pub mod dialogue_plugin;
+pub mod elevator_plugin;
 pub mod esc_exit_plugin;
+pub mod fps_counter_plugin;
+pub mod fragile_floor_plugin;
+pub mod game_state_plugin;
+pub mod goal_plugin;
+pub mod gravity_zone_plugin;
+pub mod health_plugin;
+pub mod ice_surface_plugin;
+pub mod input_map_plugin;
+pub mod kill_volume_plugin;
+pub mod launch_options_plugin;
+pub mod level_manifest_plugin;
+pub mod level_select_plugin;
+pub mod lives_plugin;
+pub mod loading_screen_plugin;
+pub mod magnet_zone_plugin;
+pub mod mesh_physics_plugin;
+pub mod moving_platform_plugin;
+pub mod notification_plugin;
+pub mod orbit_camera_plugin;
+pub mod particle_effect_plugin;
+pub mod pause_plugin;
+pub mod physics_layer_plugin;
+pub mod point_plugin;
+pub mod post_processing_plugin;
+pub mod pressure_plate_plugin;
+pub mod replay_plugin;
+pub mod respawn_plugin;
+pub mod save_game_plugin;
+pub mod screenshot_plugin;
+pub mod spawn_point_plugin;
+pub mod speed_strip_plugin;
+pub mod switch_door_plugin;
+pub mod teleporter_plugin;
+pub mod third_person_camera_plugin;
+pub mod trampoline_plugin;
+pub mod trigger_volume_plugin;
+pub mod tween_plugin;
+pub mod wind_force_plugin;
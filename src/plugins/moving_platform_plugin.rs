@@ -0,0 +1,169 @@
+//! Turns glTF meshes named `platform_*` into platforms that carry the ball, e.g. elevators or
+//! platforms patrolling between two ledges.
+//! The mesh's parent gets a trimesh collider and a velocity based kinematic body with a
+//! [`PlatformMotion`] from the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline, which
+//! this plugin adds if it's missing. Rapier moves the platform with the velocity of its animation
+//! and the ball rides it with friction instead of being left behind.
+//!
+//! The animations of the glTF whose names contain a platform's name, e.g. `LiftAction` for
+//! `platform_Lift`, are looped on the [`AnimationPlayer`] animating the platforms, so the other
+//! animations of the level aren't started. They follow virtual time, so they stop while the game
+//! is paused. A full restart, which sends [`ResetCheckpoints`], rewinds them and puts the
+//! platforms back where they started.
+
+use bevy::{
+    animation::AnimationTarget, gltf::Gltf, platform::collections::HashSet, prelude::*,
+    scene::SceneInstanceReady,
+};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    checkpoint_plugin::ResetCheckpoints,
+    mesh_physics_plugin::{ObjectCollider, PlatformMotion, register_object_collider},
+};
+
+const PREFIX: &str = "platform_";
+
+pub struct MovingPlatformPlugin;
+
+impl Plugin for MovingPlatformPlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                prefix: PREFIX.to_string(),
+                body: RigidBody::KinematicVelocityBased,
+                platform_motion: true,
+                ..default()
+            },
+        );
+        app.add_event::<ResetCheckpoints>()
+            .add_systems(Update, reset_platforms)
+            .add_observer(insert_moving_platforms);
+    }
+}
+
+/// A platform created from a `platform_` mesh, on the mesh's parent.
+#[derive(Component)]
+pub struct MovingPlatform {
+    /// Where the platform was when the scene was spawned.
+    pub initial: Transform,
+}
+
+/// The player of the glTF animations moving platforms.
+#[derive(Component)]
+struct PlatformAnimations(Vec<AnimationNodeIndex>);
+
+#[allow(clippy::too_many_arguments)]
+fn insert_moving_platforms(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    gltfs: Res<Assets<Gltf>>,
+    mut graphs: ResMut<Assets<AnimationGraph>>,
+    children: Query<&Children>,
+    scene_roots: Query<&SceneRoot>,
+    query: Query<(&Name, &ChildOf), With<Mesh3d>>,
+    parents: Query<(&Transform, Option<&AnimationTarget>)>,
+    mut players: Query<&mut AnimationPlayer>,
+) {
+    let mut animated_players = HashSet::new();
+    let mut platform_names = Vec::new();
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of)) = query.get(entity) else {
+            continue;
+        };
+        let Some(own_name) = name.strip_prefix(PREFIX) else {
+            continue;
+        };
+
+        let platform = child_of.parent();
+        let Ok((initial, target)) = parents.get(platform) else {
+            continue;
+        };
+        match target {
+            Some(target) => {
+                animated_players.insert(target.player);
+                platform_names.push(own_name.to_string());
+            }
+            None => warn!("`{name}` isn't animated, so the platform won't move."),
+        }
+
+        commands
+            .entity(platform)
+            .insert(MovingPlatform { initial: *initial });
+    }
+    if animated_players.is_empty() {
+        return;
+    }
+
+    // The animations are in the glTF the scene was loaded from.
+    let Some(gltf) = scene_roots
+        .get(trigger.target())
+        .ok()
+        .and_then(|scene_root| asset_server.get_path(scene_root.0.id()))
+        .map(|path| asset_server.load::<Gltf>(path.without_label().into_owned()))
+        .and_then(|handle| gltfs.get(&handle))
+    else {
+        error!("The glTF of the moving platforms isn't loaded, so they won't move.");
+        return;
+    };
+    let clips: Vec<_> = gltf
+        .named_animations
+        .iter()
+        .filter(|(clip_name, _)| {
+            platform_names
+                .iter()
+                .any(|platform_name| clip_name.contains(platform_name.as_str()))
+        })
+        .map(|(_, clip)| clip.clone())
+        .collect();
+    if clips.is_empty() {
+        warn!("No animation is named after the platforms {platform_names:?}, so they won't move.");
+        return;
+    }
+    let (graph, nodes) = AnimationGraph::from_clips(clips);
+    let graph = graphs.add(graph);
+
+    for entity in animated_players {
+        let Ok(mut player) = players.get_mut(entity) else {
+            continue;
+        };
+        for &node in &nodes {
+            player.play(node).repeat();
+        }
+        commands.entity(entity).insert((
+            AnimationGraphHandle(graph.clone()),
+            PlatformAnimations(nodes.clone()),
+        ));
+    }
+}
+
+fn reset_platforms(
+    mut reset_checkpoints: EventReader<ResetCheckpoints>,
+    mut players: Query<(&mut AnimationPlayer, &PlatformAnimations)>,
+    mut platforms: Query<(
+        &MovingPlatform,
+        &mut Transform,
+        &mut Velocity,
+        &mut PlatformMotion,
+    )>,
+) {
+    if reset_checkpoints.read().count() == 0 {
+        return;
+    }
+
+    for (mut player, animations) in players.iter_mut() {
+        for &node in &animations.0 {
+            if let Some(animation) = player.animation_mut(node) {
+                animation.rewind();
+            }
+        }
+    }
+    for (platform, mut transform, mut velocity, mut motion) in platforms.iter_mut() {
+        *transform = platform.initial;
+        *velocity = Velocity::zero();
+        motion.reset();
+    }
+}
@@ -0,0 +1,126 @@
+//! Shows short messages as toasts at the bottom of the screen, e.g. "Screenshot saved!".
+//! Send a [`ShowNotification`] event to show one. Toasts stack upwards and fade out after
+//! [`NotificationConfig::duration`] seconds with a [`Tween<Alpha>`] of the [`TweenPlugin`],
+//! which is added if it's missing. Like the tweens, toasts follow virtual time, so they stay
+//! while the game is paused.
+
+use bevy::{color::Alpha as _, prelude::*};
+
+use crate::plugins::tween_plugin::{Alpha, Tween, TweenComplete, TweenPlugin};
+
+/// The seconds a toast takes to fade out at the end.
+const FADE_TIME: f32 = 0.5;
+/// The alpha of a toast's background.
+const BACKGROUND_ALPHA: f32 = 0.7;
+
+#[derive(Default)]
+pub struct NotificationPlugin {
+    pub config: NotificationConfig,
+}
+
+impl Plugin for NotificationPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TweenPlugin>() {
+            app.add_plugins(TweenPlugin);
+        }
+        app.insert_resource(self.config.clone())
+            .add_event::<ShowNotification>()
+            .add_systems(Startup, spawn_notification_area)
+            .add_systems(Update, (spawn_toasts, fade_toasts).chain());
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct NotificationConfig {
+    /// How long a toast stays, in seconds.
+    pub duration: f32,
+    pub font_size: f32,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            duration: 2.5,
+            font_size: 20.0,
+        }
+    }
+}
+
+#[derive(Event)]
+pub struct ShowNotification(pub String);
+
+/// The column the toasts are stacked in.
+#[derive(Component)]
+struct NotificationArea;
+
+/// The seconds left until a toast starts fading out.
+#[derive(Component)]
+struct Toast(f32);
+
+fn spawn_notification_area(mut commands: Commands) {
+    commands.spawn((
+        NotificationArea,
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(24.0),
+            width: Val::Percent(100.0),
+            flex_direction: FlexDirection::ColumnReverse,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(6.0),
+            ..default()
+        },
+        Pickable::IGNORE,
+    ));
+}
+
+fn spawn_toasts(
+    mut commands: Commands,
+    mut notifications: EventReader<ShowNotification>,
+    config: Res<NotificationConfig>,
+    area: Single<Entity, With<NotificationArea>>,
+) {
+    for ShowNotification(message) in notifications.read() {
+        info!("Notification: {message}");
+        let toast = commands
+            .spawn((
+                Toast((config.duration - FADE_TIME).max(0.0)),
+                Node {
+                    padding: UiRect::axes(Val::Px(12.0), Val::Px(6.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::BLACK.with_alpha(BACKGROUND_ALPHA)),
+            ))
+            .with_child((
+                Text::new(message.clone()),
+                TextFont {
+                    font_size: config.font_size,
+                    ..default()
+                },
+            ))
+            .id();
+        commands.entity(*area).add_child(toast);
+    }
+}
+
+fn fade_toasts(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut toasts: Query<(Entity, &mut Toast, &Children)>,
+) {
+    for (entity, mut toast, children) in toasts.iter_mut() {
+        toast.0 -= time.delta_secs();
+        if toast.0 > 0.0 {
+            continue;
+        }
+
+        commands.entity(entity).remove::<Toast>().insert(
+            Tween::new(Alpha(BACKGROUND_ALPHA), Alpha(0.0), FADE_TIME)
+                .with_on_complete(TweenComplete::Despawn),
+        );
+        for child in children.iter() {
+            commands
+                .entity(child)
+                .insert(Tween::new(Alpha(1.0), Alpha(0.0), FADE_TIME));
+        }
+    }
+}
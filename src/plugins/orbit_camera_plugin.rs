@@ -0,0 +1,132 @@
+//! Orbits cameras around a fixed point with the mouse, e.g. to look around a level.
+//! Dragging with the left button orbits, dragging with the right button pans the point, and
+//! scrolling zooms.
+//!
+//! `O` toggles [`OrbitCameraEnabled`], so the orbit camera can be swapped with another camera
+//! controller, e.g. a third person camera following the ball. Other controllers should do
+//! nothing while it's `true` so they don't fight over the camera.
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseScrollUnit},
+    prelude::*,
+};
+
+use crate::plugins::game_state_plugin::playing;
+
+/// Radians per pixel of mouse movement.
+const ORBIT_SENSITIVITY: f32 = 0.005;
+/// The part of the distance the point pans per pixel of mouse movement.
+const PAN_SENSITIVITY: f32 = 0.002;
+/// How much one line of scrolling changes the distance, relative to the distance.
+const ZOOM_SENSITIVITY: f32 = 0.1;
+const MIN_DISTANCE: f32 = 0.5;
+const MAX_DISTANCE: f32 = 500.0;
+/// Keeps the camera from flipping over the poles.
+const MAX_ELEVATION: f32 = FRAC_PI_2 - 0.01;
+
+pub struct OrbitCameraPlugin;
+
+impl Plugin for OrbitCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<OrbitCameraEnabled>().add_systems(
+            Update,
+            (
+                toggle_orbit_camera.run_if(playing),
+                (
+                    control_orbit_camera.run_if(playing),
+                    update_orbit_transforms,
+                )
+                    .chain()
+                    .run_if(|enabled: Res<OrbitCameraEnabled>| enabled.0),
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Whether the [`OrbitCamera`]s are controlled by the mouse.
+#[derive(Resource, Default)]
+pub struct OrbitCameraEnabled(pub bool);
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct OrbitCamera {
+    /// The world space point the camera orbits around and looks at.
+    pub target: Vec3,
+    pub distance: f32,
+    /// The angle around the `Y` axis in radians, zero looking along `-Z`.
+    pub azimuth: f32,
+    /// The angle above the horizon in radians.
+    pub elevation: f32,
+}
+
+impl OrbitCamera {
+    /// The position of the camera.
+    pub fn position(&self) -> Vec3 {
+        let direction = Vec3::new(
+            self.elevation.cos() * self.azimuth.sin(),
+            self.elevation.sin(),
+            self.elevation.cos() * self.azimuth.cos(),
+        );
+        self.target + direction * self.distance
+    }
+}
+
+fn toggle_orbit_camera(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut enabled: ResMut<OrbitCameraEnabled>,
+    mut query: Query<&mut OrbitCamera>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyO) {
+        return;
+    }
+
+    enabled.0 = !enabled.0;
+    info!("Orbit camera enabled: {}.", enabled.0);
+    // Moves the cameras back from wherever the other controller left them.
+    if enabled.0 {
+        for mut orbit in query.iter_mut() {
+            orbit.set_changed();
+        }
+    }
+}
+
+fn control_orbit_camera(
+    mouse: Res<ButtonInput<MouseButton>>,
+    motion: Res<AccumulatedMouseMotion>,
+    scroll: Res<AccumulatedMouseScroll>,
+    mut query: Query<(&mut OrbitCamera, &Transform)>,
+) {
+    let scroll_lines = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        // Roughly the pixels of one line.
+        MouseScrollUnit::Pixel => scroll.delta.y / 16.0,
+    };
+
+    for (mut orbit, transform) in query.iter_mut() {
+        if mouse.pressed(MouseButton::Left) {
+            orbit.azimuth -= motion.delta.x * ORBIT_SENSITIVITY;
+            orbit.elevation = (orbit.elevation + motion.delta.y * ORBIT_SENSITIVITY)
+                .clamp(-MAX_ELEVATION, MAX_ELEVATION);
+        }
+        if mouse.pressed(MouseButton::Right) {
+            // Dragging moves the point with the cursor, so it pans against the mouse.
+            let pan = (-*transform.right() * motion.delta.x + *transform.up() * motion.delta.y)
+                * PAN_SENSITIVITY
+                * orbit.distance;
+            orbit.target += pan;
+        }
+        if scroll_lines != 0.0 {
+            orbit.distance = (orbit.distance * (1.0 - scroll_lines * ZOOM_SENSITIVITY))
+                .clamp(MIN_DISTANCE, MAX_DISTANCE);
+        }
+    }
+}
+
+fn update_orbit_transforms(mut query: Query<(&OrbitCamera, &mut Transform), Changed<OrbitCamera>>) {
+    for (orbit, mut transform) in query.iter_mut() {
+        *transform =
+            Transform::from_translation(orbit.position()).looking_at(orbit.target, Vec3::Y);
+    }
+}
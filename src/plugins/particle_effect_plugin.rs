@@ -0,0 +1,199 @@
+//! Spawns bursts of small spheres that fly apart and fade out, e.g. dust when the ball lands.
+//! Send a [`ParticleBurst`] event to spawn one. A [`Ball`] hitting something faster than
+//! [`ParticleEffectConfig::impact_speed`] sends one by itself.
+
+use bevy::prelude::*;
+use bevy_rapier3d::{prelude::*, rapier::geometry::CollisionEventFlags};
+
+use crate::plugins::{ball_physics_plugin::Ball, level_manifest_plugin::LevelEntity};
+
+/// The part of the lifetime at the end over which particles fade out.
+const FADE_FRACTION: f32 = 0.2;
+
+#[derive(Default)]
+pub struct ParticleEffectPlugin {
+    pub config: ParticleEffectConfig,
+}
+
+impl Plugin for ParticleEffectPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone())
+            .insert_resource(ParticleRng(0x2545_F491))
+            .add_event::<ParticleBurst>()
+            .add_systems(Startup, setup_particle_mesh)
+            .add_systems(
+                Update,
+                (burst_on_impact, spawn_particle_bursts, tick_particles).chain(),
+            );
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct ParticleEffectConfig {
+    /// The speed a ball needs when it hits something for a burst, `None` for no bursts.
+    pub impact_speed: Option<f32>,
+    /// The burst sent on impacts.
+    pub impact_count: u32,
+    pub impact_color: Color,
+}
+
+impl Default for ParticleEffectConfig {
+    fn default() -> Self {
+        Self {
+            impact_speed: Some(6.0),
+            impact_count: 12,
+            impact_color: Color::linear_rgb(0.6, 0.55, 0.5),
+        }
+    }
+}
+
+#[derive(Event, Clone, Copy)]
+pub struct ParticleBurst {
+    pub position: Vec3,
+    pub count: u32,
+    /// The speed of the particles, in meters per second.
+    pub speed: f32,
+    pub color: Color,
+    /// In seconds.
+    pub lifetime: f32,
+}
+
+/// The seconds left until a particle is despawned.
+#[derive(Component)]
+pub struct Lifetime(pub f32);
+
+#[derive(Component)]
+struct Particle {
+    velocity: Vec3,
+    /// The whole lifetime, to know when to start fading.
+    lifetime: f32,
+    material: Handle<StandardMaterial>,
+}
+
+#[derive(Resource)]
+struct ParticleMesh(Handle<Mesh>);
+
+/// The state of a xorshift generator for the particle directions.
+#[derive(Resource)]
+struct ParticleRng(u32);
+
+impl ParticleRng {
+    /// Returns a number in `[-1, 1]`.
+    fn next_signed(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+
+    /// Returns a random direction.
+    fn next_direction(&mut self) -> Vec3 {
+        // Rejection sampling keeps the directions uniform.
+        loop {
+            let point = Vec3::new(self.next_signed(), self.next_signed(), self.next_signed());
+            let length_squared = point.length_squared();
+            if length_squared > 0.0001 && length_squared <= 1.0 {
+                return point / length_squared.sqrt();
+            }
+        }
+    }
+}
+
+fn setup_particle_mesh(mut commands: Commands, mut meshes: ResMut<Assets<Mesh>>) {
+    commands.insert_resource(ParticleMesh(meshes.add(Sphere::new(0.04))));
+}
+
+fn burst_on_impact(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut bursts: EventWriter<ParticleBurst>,
+    config: Res<ParticleEffectConfig>,
+    balls: Query<(&GlobalTransform, &Velocity), With<Ball>>,
+) {
+    let Some(impact_speed) = config.impact_speed else {
+        collision_events.clear();
+        return;
+    };
+
+    for event in collision_events.read() {
+        let CollisionEvent::Started(entity1, entity2, flags) = *event else {
+            continue;
+        };
+        if flags.contains(CollisionEventFlags::SENSOR) {
+            continue;
+        }
+
+        for ball in [entity1, entity2] {
+            let Ok((transform, velocity)) = balls.get(ball) else {
+                continue;
+            };
+            let speed = velocity.linvel.length();
+            if speed > impact_speed {
+                bursts.write(ParticleBurst {
+                    position: transform.translation(),
+                    count: config.impact_count,
+                    speed: speed * 0.3,
+                    color: config.impact_color,
+                    lifetime: 0.8,
+                });
+            }
+        }
+    }
+}
+
+fn spawn_particle_bursts(
+    mut commands: Commands,
+    mut bursts: EventReader<ParticleBurst>,
+    mut rng: ResMut<ParticleRng>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mesh: Res<ParticleMesh>,
+) {
+    for burst in bursts.read() {
+        // All the particles of a burst fade together, so they share a material.
+        let material = materials.add(StandardMaterial {
+            base_color: burst.color,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        });
+        for _ in 0..burst.count {
+            commands.spawn((
+                LevelEntity,
+                Particle {
+                    velocity: rng.next_direction() * burst.speed,
+                    lifetime: burst.lifetime,
+                    material: material.clone(),
+                },
+                Lifetime(burst.lifetime),
+                Mesh3d(mesh.0.clone()),
+                MeshMaterial3d(material.clone()),
+                Transform::from_translation(burst.position),
+            ));
+        }
+    }
+}
+
+fn tick_particles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut query: Query<(Entity, &Particle, &mut Lifetime, &mut Transform)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, particle, mut lifetime, mut transform) in query.iter_mut() {
+        lifetime.0 -= dt;
+        if lifetime.0 <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        transform.translation += particle.velocity * dt;
+
+        let fade_time = particle.lifetime * FADE_FRACTION;
+        if lifetime.0 >= fade_time {
+            continue;
+        }
+        if let Some(material) = materials.get_mut(&particle.material) {
+            material.base_color.set_alpha(lifetime.0 / fade_time);
+        }
+    }
+}
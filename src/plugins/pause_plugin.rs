@@ -0,0 +1,340 @@
+//! Pauses the game with `P`: virtual time and rapier stop, the cursor is released and a menu
+//! with Resume, Restart and Quit is shown. The entries are picked with the arrow keys and
+//! `Enter`, or clicked. `P` or Resume continues where the game stopped.
+//!
+//! Pausing enters [`GameState::Paused`] and resuming goes back to [`GameState::Playing`], so the
+//! game can only be paused while it's being played, and a level that's loading or won isn't
+//! paused. Rapier is put back the way it was before pausing, so physics stopped by another plugin
+//! stays stopped.
+//!
+//! Restart sends a [`RestartRequested`] event and resumes, so the game decides what restarting
+//! means. Input systems like mouse look or ball control already stop with the
+//! [`playing`](crate::plugins::game_state_plugin::playing) condition.
+
+use bevy::{
+    prelude::*,
+    window::{CursorGrabMode, PrimaryWindow},
+};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::game_state_plugin::{GameState, GameStatePlugin};
+
+const ENTRIES: [PauseEntry; 3] = [PauseEntry::Resume, PauseEntry::Restart, PauseEntry::Quit];
+const SELECTED_COLOR: Color = Color::srgb(0.35, 0.35, 0.45);
+const UNSELECTED_COLOR: Color = Color::srgb(0.15, 0.15, 0.2);
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<GameStatePlugin>() {
+            app.add_plugins(GameStatePlugin);
+        }
+        app.init_resource::<PauseSelection>()
+            .add_event::<RestartRequested>()
+            .add_systems(OnEnter(GameState::Paused), (pause, spawn_pause_menu))
+            .add_systems(OnExit(GameState::Paused), (resume, despawn_pause_menu))
+            .add_systems(
+                Update,
+                (
+                    toggle_pause
+                        .run_if(in_state(GameState::Playing).or(in_state(GameState::Paused))),
+                    (navigate_pause_menu, highlight_pause_menu)
+                        .chain()
+                        .run_if(in_state(GameState::Paused)),
+                ),
+            );
+    }
+}
+
+/// Sent when Restart is picked in the pause menu.
+#[derive(Event)]
+pub struct RestartRequested;
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+enum PauseEntry {
+    Resume,
+    Restart,
+    Quit,
+}
+
+impl PauseEntry {
+    fn label(self) -> &'static str {
+        match self {
+            Self::Resume => "Resume",
+            Self::Restart => "Restart",
+            Self::Quit => "Quit",
+        }
+    }
+}
+
+/// The index of the selected entry in [`ENTRIES`].
+#[derive(Resource, Default)]
+struct PauseSelection(usize);
+
+#[derive(Component)]
+struct PauseMenu;
+
+/// The cursor options from before pausing.
+#[derive(Resource)]
+struct CursorBeforePause {
+    grab_mode: CursorGrabMode,
+    visible: bool,
+}
+
+/// Whether the physics pipeline of each rapier context was active before pausing.
+#[derive(Resource)]
+struct PhysicsBeforePause(Vec<(Entity, bool)>);
+
+fn toggle_pause(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if !keyboard.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    next_state.set(match state.get() {
+        GameState::Paused => GameState::Playing,
+        _ => GameState::Paused,
+    });
+}
+
+fn pause(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_configs: Query<(Entity, &mut RapierConfiguration)>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+) {
+    // Stopping virtual time also keeps rapier from catching up on the paused time when resuming.
+    time.pause();
+    let mut physics = Vec::new();
+    for (entity, mut config) in rapier_configs.iter_mut() {
+        physics.push((entity, config.physics_pipeline_active));
+        config.physics_pipeline_active = false;
+    }
+    commands.insert_resource(PhysicsBeforePause(physics));
+
+    commands.insert_resource(CursorBeforePause {
+        grab_mode: window.cursor_options.grab_mode,
+        visible: window.cursor_options.visible,
+    });
+    window.cursor_options.grab_mode = CursorGrabMode::None;
+    window.cursor_options.visible = true;
+}
+
+fn resume(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    mut rapier_configs: Query<&mut RapierConfiguration>,
+    mut window: Single<&mut Window, With<PrimaryWindow>>,
+    cursor: Option<Res<CursorBeforePause>>,
+    physics: Option<Res<PhysicsBeforePause>>,
+) {
+    time.unpause();
+    if let Some(physics) = physics {
+        for &(entity, active) in &physics.0 {
+            if let Ok(mut config) = rapier_configs.get_mut(entity) {
+                config.physics_pipeline_active = active;
+            }
+        }
+        commands.remove_resource::<PhysicsBeforePause>();
+    }
+
+    if let Some(cursor) = cursor {
+        window.cursor_options.grab_mode = cursor.grab_mode;
+        window.cursor_options.visible = cursor.visible;
+        commands.remove_resource::<CursorBeforePause>();
+    }
+}
+
+fn spawn_pause_menu(mut commands: Commands, mut selection: ResMut<PauseSelection>) {
+    selection.0 = 0;
+    commands
+        .spawn((
+            PauseMenu,
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Paused"),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+            ));
+            for entry in ENTRIES {
+                parent
+                    .spawn((
+                        entry,
+                        Button,
+                        Node {
+                            width: Val::Px(200.0),
+                            padding: UiRect::all(Val::Px(8.0)),
+                            justify_content: JustifyContent::Center,
+                            ..default()
+                        },
+                        BackgroundColor(UNSELECTED_COLOR),
+                    ))
+                    .with_child((
+                        Text::new(entry.label()),
+                        TextFont {
+                            font_size: 28.0,
+                            ..default()
+                        },
+                    ));
+            }
+        });
+}
+
+fn despawn_pause_menu(mut commands: Commands, menus: Query<Entity, With<PauseMenu>>) {
+    for menu in menus.iter() {
+        commands.entity(menu).despawn();
+    }
+}
+
+fn navigate_pause_menu(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut selection: ResMut<PauseSelection>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut restart: EventWriter<RestartRequested>,
+    mut exit: EventWriter<AppExit>,
+    buttons: Query<(&PauseEntry, &Interaction), Changed<Interaction>>,
+) {
+    if keyboard.just_pressed(KeyCode::ArrowDown) {
+        selection.0 = (selection.0 + 1) % ENTRIES.len();
+    }
+    if keyboard.just_pressed(KeyCode::ArrowUp) {
+        selection.0 = (selection.0 + ENTRIES.len() - 1) % ENTRIES.len();
+    }
+
+    let mut picked = keyboard
+        .just_pressed(KeyCode::Enter)
+        .then_some(ENTRIES[selection.0]);
+    for (entry, interaction) in buttons.iter() {
+        let index = ENTRIES.iter().position(|other| other == entry).unwrap_or(0);
+        match interaction {
+            Interaction::Hovered => selection.0 = index,
+            Interaction::Pressed => picked = Some(*entry),
+            Interaction::None => {}
+        }
+    }
+
+    match picked {
+        Some(PauseEntry::Resume) => next_state.set(GameState::Playing),
+        Some(PauseEntry::Restart) => {
+            restart.write(RestartRequested);
+            next_state.set(GameState::Playing);
+        }
+        Some(PauseEntry::Quit) => {
+            exit.write(AppExit::Success);
+        }
+        None => {}
+    }
+}
+
+fn highlight_pause_menu(
+    selection: Res<PauseSelection>,
+    mut buttons: Query<(&PauseEntry, &mut BackgroundColor)>,
+) {
+    for (entry, mut color) in buttons.iter_mut() {
+        color.0 = if *entry == ENTRIES[selection.0] {
+            SELECTED_COLOR
+        } else {
+            UNSELECTED_COLOR
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::state::app::StatesPlugin;
+
+    use super::*;
+    use crate::plugins::mesh_physics_plugin::PhysicsReady;
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, StatesPlugin, PausePlugin))
+            .init_resource::<ButtonInput<KeyCode>>();
+        app.world_mut().spawn((Window::default(), PrimaryWindow));
+        app
+    }
+
+    fn press_pause(app: &mut App) {
+        let mut keyboard = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keyboard.release(KeyCode::KeyP);
+        keyboard.clear();
+        keyboard.press(KeyCode::KeyP);
+        app.update();
+        // Applies the state change without pressing again.
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .clear();
+        app.update();
+    }
+
+    fn state(app: &App) -> GameState {
+        *app.world().resource::<State<GameState>>().get()
+    }
+
+    fn play(app: &mut App) {
+        app.world_mut().send_event(PhysicsReady {
+            scene: Entity::PLACEHOLDER,
+            colliders_inserted: 0,
+        });
+        app.update();
+        app.update();
+    }
+
+    #[test]
+    fn only_pauses_while_playing() {
+        let mut app = app();
+
+        press_pause(&mut app);
+        assert_eq!(state(&app), GameState::Loading);
+
+        play(&mut app);
+        press_pause(&mut app);
+        assert_eq!(state(&app), GameState::Paused);
+        press_pause(&mut app);
+        assert_eq!(state(&app), GameState::Playing);
+    }
+
+    #[test]
+    fn restores_the_physics_from_before_pausing() {
+        let mut app = app();
+        let stopped = app
+            .world_mut()
+            .spawn(RapierConfiguration {
+                physics_pipeline_active: false,
+                ..RapierConfiguration::new(1.0)
+            })
+            .id();
+        let running = app.world_mut().spawn(RapierConfiguration::new(1.0)).id();
+
+        play(&mut app);
+        press_pause(&mut app);
+        assert_eq!(state(&app), GameState::Paused);
+        press_pause(&mut app);
+        assert_eq!(state(&app), GameState::Playing);
+
+        let active = |entity| {
+            app.world()
+                .get::<RapierConfiguration>(entity)
+                .unwrap()
+                .physics_pipeline_active
+        };
+        assert!(!active(stopped));
+        assert!(active(running));
+    }
+}
@@ -0,0 +1,185 @@
+//! Collision layers for the meshes handled by `MeshPhysicsPlugin`.
+//! A `layer<name>` token in a collider name, e.g. `collider_layer2_Wall` or
+//! `collider_layerghost_Platform`, puts the collider in that layer only. The name is either a layer
+//! index or a name registered in [`LayerRegistry`].
+//!
+//! The first [`RESERVED_GROUPS`] bits are the groups the library itself uses, and the layers are
+//! counted after them, so `layer0` is the first free bit and no layer shares a bit with the ball
+//! or the sensors. A collider name can also contain one of these tokens to pick its groups from
+//! the table:
+//!
+//! | Token      | Memberships | Filters                   | Use                                     |
+//! |------------|-------------|---------------------------|-----------------------------------------|
+//! | `nocam`    | [`LEVEL`]   | everything but [`CAMERA`] | Decorations the camera sees through.    |
+//! | `ballonly` | [`LEVEL`]   | [`BALL`]                  | Walls that only stop the ball.          |
+//! | `camonly`  | [`CAMERA`]  | [`CAMERA`]                | Invisible walls for framing the camera. |
+//!
+//! Camera occlusion raycasts should use [`camera_ray_groups`] so they skip the ball and the
+//! colliders that don't block the camera.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+use bevy_rapier3d::prelude::*;
+
+#[derive(Default)]
+pub struct PhysicsLayerPlugin {
+    pub registry: LayerRegistry,
+}
+
+impl Plugin for PhysicsLayerPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.registry.clone());
+    }
+}
+
+/// The balls rolled by the player.
+pub const BALL: Group = Group::GROUP_1;
+/// The level geometry.
+pub const LEVEL: Group = Group::GROUP_2;
+/// Colliders that only block the camera.
+pub const CAMERA: Group = Group::GROUP_3;
+/// Sensor volumes.
+pub const SENSOR: Group = Group::GROUP_4;
+/// The number of groups above, which the layers are counted after.
+pub const RESERVED_GROUPS: u32 = 4;
+
+/// Returns the collision groups of a collider name token from the table in the module docs.
+pub fn parse_group_token(token: &str) -> Option<CollisionGroups> {
+    match token {
+        "nocam" => Some(CollisionGroups::new(LEVEL, Group::ALL.difference(CAMERA))),
+        "ballonly" => Some(CollisionGroups::new(LEVEL, BALL)),
+        "camonly" => Some(CollisionGroups::new(CAMERA, CAMERA)),
+        _ => None,
+    }
+}
+
+/// The collision groups of the ball, which interacts with everything.
+pub fn ball_groups() -> CollisionGroups {
+    CollisionGroups::new(BALL, Group::ALL)
+}
+
+/// The collision groups of a sensor volume, which detects everything.
+pub fn sensor_groups() -> CollisionGroups {
+    CollisionGroups::new(SENSOR, Group::ALL)
+}
+
+/// The collision groups for the raycasts checking if something is between the camera and the
+/// ball. They hit the level and the camera blockers, but not the ball or sensors.
+pub fn camera_ray_groups() -> CollisionGroups {
+    CollisionGroups::new(CAMERA, LEVEL | CAMERA)
+}
+
+/// Maps layer names to layer indices.
+#[derive(Resource, Clone, Default)]
+pub struct LayerRegistry {
+    pub layers: HashMap<String, u32>,
+}
+
+impl LayerRegistry {
+    /// Returns the bit index of the collision group of a layer name, which can also be the layer
+    /// index itself. The bit is [`RESERVED_GROUPS`] past the index.
+    pub fn bit(&self, layer: &str) -> Option<u32> {
+        let index: u32 = layer
+            .parse()
+            .ok()
+            .or_else(|| self.layers.get(layer).copied())?;
+        let bit = index.checked_add(RESERVED_GROUPS)?;
+        (bit < 32).then_some(bit)
+    }
+
+    /// Returns the collision groups of a collider in the layer.
+    /// The collider is only a member of its layer but still interacts with every group, so the
+    /// other colliders choose whether they collide with it through their filters.
+    pub fn collision_groups(&self, layer: &str) -> Option<CollisionGroups> {
+        let bit = self.bit(layer)?;
+        Some(CollisionGroups::new(
+            Group::from_bits_truncate(1 << bit),
+            Group::ALL,
+        ))
+    }
+}
+
+/// Returns the layer name of a `layer<name>` name token.
+pub fn parse_layer_token(token: &str) -> Option<&str> {
+    token
+        .strip_prefix("layer")
+        .filter(|layer| !layer.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin;
+
+    #[test]
+    fn parses_group_tokens() {
+        let nocam = parse_group_token("nocam").unwrap();
+        assert_eq!(nocam.memberships, LEVEL);
+        assert!(nocam.filters.contains(BALL | LEVEL | SENSOR));
+        assert!(!nocam.filters.contains(CAMERA));
+
+        assert_eq!(
+            parse_group_token("ballonly"),
+            Some(CollisionGroups::new(LEVEL, BALL))
+        );
+        assert_eq!(
+            parse_group_token("camonly"),
+            Some(CollisionGroups::new(CAMERA, CAMERA))
+        );
+        assert_eq!(parse_group_token("layer2"), None);
+    }
+
+    #[test]
+    fn camera_rays_skip_the_ball_and_sensors() {
+        let rays = camera_ray_groups();
+        let hits = |groups: CollisionGroups| {
+            rays.filters.intersects(groups.memberships)
+                && groups.filters.intersects(rays.memberships)
+        };
+
+        assert!(!hits(ball_groups()));
+        assert!(!hits(sensor_groups()));
+        assert!(hits(parse_group_token("camonly").unwrap()));
+        assert!(!hits(parse_group_token("nocam").unwrap()));
+        assert!(!hits(parse_group_token("ballonly").unwrap()));
+    }
+
+    #[test]
+    fn resolves_layer_names_and_indices() {
+        let registry = LayerRegistry {
+            layers: HashMap::from_iter([("ghost".to_string(), 5)]),
+        };
+        assert_eq!(registry.bit("ghost"), Some(9));
+        assert_eq!(registry.bit("7"), Some(11));
+        assert_eq!(registry.bit("27"), Some(31));
+        assert_eq!(registry.bit("28"), None);
+        assert_eq!(registry.bit("4294967295"), None);
+        assert_eq!(registry.bit("unknown"), None);
+        assert_eq!(
+            registry.collision_groups("ghost"),
+            Some(CollisionGroups::new(Group::GROUP_10, Group::ALL))
+        );
+        assert_eq!(parse_layer_token("layerghost"), Some("ghost"));
+        assert_eq!(parse_layer_token("layer"), None);
+    }
+
+    #[test]
+    fn layers_are_past_the_reserved_groups() {
+        let registry = LayerRegistry::default();
+        let reserved = BALL | LEVEL | CAMERA | SENSOR;
+        assert_eq!(
+            registry.collision_groups("2"),
+            Some(CollisionGroups::new(Group::GROUP_7, Group::ALL))
+        );
+        for layer in 0..32 - RESERVED_GROUPS {
+            let groups = registry.collision_groups(&layer.to_string()).unwrap();
+            assert!(!groups.memberships.intersects(reserved));
+        }
+    }
+
+    #[test]
+    fn parses_group_and_layer_tokens_of_collider_names() {
+        let parsed = MeshPhysicsPlugin::default().parse_name("collider_layer5_ballonly_Wall");
+        assert_eq!(parsed.layer.as_deref(), Some("5"));
+        assert_eq!(parsed.groups, Some(CollisionGroups::new(LEVEL, BALL)));
+    }
+}
@@ -0,0 +1,307 @@
+//! Turns glTF meshes named `point_*` into collectible points.
+//! When a [`Ball`] touches a point, it shrinks away with a sound, and [`CollectedPoints`] and the
+//! [`Score`] go up. The count is shown in the corner as "Points: n / total", where the total is
+//! counted when the scene is loaded. The points' sensors are built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline.
+//!
+//! Collected points stay collected when the ball restarts after a fall. A full restart, which
+//! sends [`ResetCheckpoints`], brings them back and takes them out of the [`Score`] again, so they
+//! can't be farmed by restarting. An [`UnloadLevel`] resets the count and keeps the score.
+
+use bevy::{prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    checkpoint_plugin::ResetCheckpoints,
+    level_manifest_plugin::{LevelEntity, UnloadLevel},
+    mesh_physics_plugin::{ObjectCollider, SensorTriggered, register_object_collider},
+    save_game_plugin::Score,
+    tween_plugin::{Tween, TweenPlugin, ease_in_out},
+};
+
+const PREFIX: &str = "point_";
+/// How long a collected point takes to shrink away, in seconds.
+const SHRINK_DURATION: f32 = 0.25;
+
+#[derive(Default)]
+pub struct PointPlugin {
+    pub config: PointConfig,
+}
+
+impl Plugin for PointPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<TweenPlugin>() {
+            app.add_plugins(TweenPlugin);
+        }
+        register_object_collider(
+            app,
+            ObjectCollider {
+                hide_mesh: false,
+                ..ObjectCollider::sensor(PREFIX)
+            },
+        );
+        app.insert_resource(self.config.clone())
+            .init_resource::<CollectedPoints>()
+            .add_event::<ResetCheckpoints>()
+            .add_event::<UnloadLevel>()
+            .add_systems(Startup, spawn_points_text)
+            .add_systems(
+                Update,
+                (
+                    collect_points,
+                    hide_collected_points,
+                    reset_points,
+                    update_points_text,
+                )
+                    .chain(),
+            )
+            .add_observer(insert_points);
+    }
+}
+
+#[derive(Resource, Clone, Default)]
+pub struct PointConfig {
+    /// The asset path of the sound played when a point is collected.
+    pub sound: Option<String>,
+}
+
+#[derive(Resource, Default)]
+pub struct CollectedPoints {
+    pub collected: u32,
+    /// The points in the level.
+    pub total: u32,
+}
+
+/// A point created from a `point_` mesh, on the mesh's parent.
+#[derive(Component)]
+pub struct Point {
+    pub collected: bool,
+    /// The scale from before it shrank away.
+    scale: Vec3,
+}
+
+#[derive(Component)]
+struct PointsText;
+
+fn spawn_points_text(mut commands: Commands) {
+    commands.spawn((
+        PointsText,
+        Text::default(),
+        TextFont {
+            font_size: 24.0,
+            ..default()
+        },
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(40.0),
+            right: Val::Px(12.0),
+            ..default()
+        },
+    ));
+}
+
+fn insert_points(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    mut points: ResMut<CollectedPoints>,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf), With<Mesh3d>>,
+    parents: Query<&Transform>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of)) = query.get(entity) else {
+            continue;
+        };
+        if !name.starts_with(PREFIX) {
+            continue;
+        }
+
+        let scale = parents
+            .get(child_of.parent())
+            .map_or(Vec3::ONE, |transform| transform.scale);
+        commands.entity(child_of.parent()).insert(Point {
+            collected: false,
+            scale,
+        });
+        points.total += 1;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn collect_points(
+    mut commands: Commands,
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    config: Res<PointConfig>,
+    asset_server: Res<AssetServer>,
+    mut collected: ResMut<CollectedPoints>,
+    score: Option<ResMut<Score>>,
+    mut points: Query<&mut Point>,
+    balls: Query<(), With<Ball>>,
+) {
+    let mut new_points = 0;
+    for event in sensor_triggered.read() {
+        if !event.started || !balls.contains(event.other) {
+            continue;
+        }
+        let Ok(mut point) = points.get_mut(event.sensor) else {
+            continue;
+        };
+        if point.collected {
+            continue;
+        }
+        point.collected = true;
+        new_points += 1;
+
+        // Uniform, so it only keeps its look if it wasn't scaled unevenly.
+        commands.entity(event.sensor).insert((
+            ColliderDisabled,
+            Tween::new(point.scale.x, 0.0, SHRINK_DURATION).with_easing(ease_in_out),
+        ));
+        if let Some(sound) = &config.sound {
+            commands.spawn((
+                LevelEntity,
+                AudioPlayer::new(asset_server.load(sound.clone())),
+                PlaybackSettings::DESPAWN,
+            ));
+        }
+    }
+
+    collected.collected += new_points;
+    if let Some(mut score) = score.filter(|_| new_points > 0) {
+        score.0 += new_points;
+    }
+}
+
+/// Hides the points that finished shrinking, so nothing is left of them at scale zero.
+fn hide_collected_points(mut points: Query<(&Point, &mut Visibility), Without<Tween<f32>>>) {
+    for (point, mut visibility) in points.iter_mut() {
+        if point.collected {
+            visibility.set_if_neq(Visibility::Hidden);
+        }
+    }
+}
+
+fn reset_points(
+    mut commands: Commands,
+    mut reset_checkpoints: EventReader<ResetCheckpoints>,
+    mut unload: EventReader<UnloadLevel>,
+    mut collected: ResMut<CollectedPoints>,
+    score: Option<ResMut<Score>>,
+    mut points: Query<(Entity, &mut Point, &mut Transform, &mut Visibility)>,
+) {
+    if unload.read().count() > 0 {
+        *collected = CollectedPoints::default();
+    }
+    if reset_checkpoints.read().count() == 0 {
+        return;
+    }
+
+    if let Some(mut score) = score {
+        score.0 = score.0.saturating_sub(collected.collected);
+    }
+    collected.collected = 0;
+    for (entity, mut point, mut transform, mut visibility) in points.iter_mut() {
+        if !point.collected {
+            continue;
+        }
+        point.collected = false;
+        transform.scale = point.scale;
+        *visibility = Visibility::Inherited;
+        commands
+            .entity(entity)
+            .remove::<(ColliderDisabled, Tween<f32>)>();
+    }
+}
+
+fn update_points_text(
+    collected: Res<CollectedPoints>,
+    mut text: Single<&mut Text, With<PointsText>>,
+) {
+    if collected.is_changed() {
+        text.0 = format!("Points: {} / {}", collected.collected, collected.total);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            PointPlugin::default(),
+        ))
+        .init_asset::<Mesh>()
+        .init_resource::<Score>()
+        .add_event::<CollisionEvent>();
+        app
+    }
+
+    fn collect(app: &mut App, point: Entity, ball: Entity) {
+        app.world_mut().send_event(SensorTriggered {
+            label: "point".to_string(),
+            sensor: point,
+            other: ball,
+            started: true,
+        });
+        app.update();
+    }
+
+    fn spawn_point(app: &mut App) -> Entity {
+        app.world_mut()
+            .spawn((
+                Point {
+                    collected: false,
+                    scale: Vec3::ONE,
+                },
+                Transform::default(),
+                Visibility::default(),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn a_full_restart_takes_the_level_points_out_of_the_score() {
+        let mut app = test_app();
+        app.world_mut().resource_mut::<Score>().0 = 10;
+        let points = [spawn_point(&mut app), spawn_point(&mut app)];
+        let ball = app.world_mut().spawn(Ball { radius: 0.5 }).id();
+        app.update();
+
+        collect(&mut app, points[0], ball);
+        // Touching a collected point again doesn't count.
+        collect(&mut app, points[0], ball);
+        collect(&mut app, points[1], ball);
+        assert_eq!(app.world().resource::<CollectedPoints>().collected, 2);
+        assert_eq!(app.world().resource::<Score>().0, 12);
+
+        app.world_mut().send_event(ResetCheckpoints);
+        app.update();
+        assert_eq!(app.world().resource::<CollectedPoints>().collected, 0);
+        assert_eq!(app.world().resource::<Score>().0, 10);
+        assert!(!app.world().get::<Point>(points[0]).unwrap().collected);
+
+        collect(&mut app, points[0], ball);
+        assert_eq!(app.world().resource::<Score>().0, 11);
+    }
+
+    #[test]
+    fn unloading_keeps_the_score() {
+        let mut app = test_app();
+        let point = spawn_point(&mut app);
+        let ball = app.world_mut().spawn(Ball { radius: 0.5 }).id();
+        app.update();
+
+        collect(&mut app, point, ball);
+        // The level cleanup sends both.
+        app.world_mut().send_event(UnloadLevel);
+        app.world_mut().send_event(ResetCheckpoints);
+        app.update();
+
+        assert_eq!(app.world().resource::<CollectedPoints>().collected, 0);
+        assert_eq!(app.world().resource::<Score>().0, 1);
+    }
+}
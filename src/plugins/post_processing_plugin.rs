@@ -0,0 +1,305 @@
+//! A full-screen post processing pass on every 3D camera, with a vignette darkening the corners
+//! and chromatic aberration splitting the color channels towards the edges.
+//! The strengths come from [`PostProcessingConfig`]. When a [`BallFell`] event is sent, the
+//! aberration jumps up and fades back for an impact effect.
+//!
+//! The pass runs after tonemapping, and its pipeline is specialized on the texture format of
+//! each camera's target, so it works with HDR and non-HDR cameras.
+
+use bevy::{
+    core_pipeline::{
+        core_3d::graph::{Core3d, Node3d},
+        fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    },
+    ecs::query::QueryItem,
+    image::BevyDefault,
+    prelude::*,
+    render::{
+        Render, RenderApp, RenderSet,
+        extract_component::{
+            ComponentUniforms, DynamicUniformIndex, ExtractComponentPlugin, UniformComponentPlugin,
+        },
+        render_graph::{
+            NodeRunError, RenderGraphApp, RenderGraphContext, RenderLabel, ViewNode, ViewNodeRunner,
+        },
+        render_resource::{
+            binding_types::{sampler, texture_2d, uniform_buffer},
+            *,
+        },
+        renderer::{RenderContext, RenderDevice},
+        view::{ExtractedView, ViewTarget},
+    },
+};
+
+use self::settings::PostProcessingSettings;
+use crate::plugins::kill_volume_plugin::BallFell;
+
+const SHADER_ASSET_PATH: &str = "shaders/post_processing.wgsl";
+/// The aberration offset right after the ball falls.
+const IMPACT_ABERRATION: f32 = 0.04;
+/// The seconds the impact aberration takes to fade.
+const IMPACT_DURATION: f32 = 0.6;
+
+#[derive(Default)]
+pub struct PostProcessingPlugin {
+    pub config: PostProcessingConfig,
+}
+
+impl Plugin for PostProcessingPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone())
+            .init_resource::<ImpactPulse>()
+            .add_event::<BallFell>()
+            .add_plugins((
+                ExtractComponentPlugin::<PostProcessingSettings>::default(),
+                UniformComponentPlugin::<PostProcessingSettings>::default(),
+            ))
+            .add_systems(
+                Update,
+                (start_impact_pulse, update_post_processing_settings).chain(),
+            );
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app
+            .init_resource::<SpecializedRenderPipelines<PostProcessingPipeline>>()
+            .add_systems(
+                Render,
+                prepare_post_processing_pipelines.in_set(RenderSet::Prepare),
+            )
+            .add_render_graph_node::<ViewNodeRunner<PostProcessingNode>>(
+                Core3d,
+                PostProcessingLabel,
+            )
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::Tonemapping,
+                    PostProcessingLabel,
+                    Node3d::EndMainPassPostProcessing,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        render_app.init_resource::<PostProcessingPipeline>();
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct PostProcessingConfig {
+    /// How dark the corners get, from 0 for no vignette to 1 for black.
+    pub vignette_strength: f32,
+    /// How far the red and blue channels are moved apart at the edges, in parts of the screen.
+    pub aberration_offset: f32,
+}
+
+impl Default for PostProcessingConfig {
+    fn default() -> Self {
+        Self {
+            vignette_strength: 0.4,
+            aberration_offset: 0.004,
+        }
+    }
+}
+
+/// The seconds left of the impact aberration.
+#[derive(Resource, Default)]
+struct ImpactPulse(f32);
+
+mod settings {
+    // The `ShaderType` derive checks the field types in functions that are never called.
+    #![allow(dead_code)]
+
+    use bevy::{
+        prelude::*,
+        render::{extract_component::ExtractComponent, render_resource::ShaderType},
+    };
+
+    /// The values sent to the shader, inserted on every 3D camera.
+    #[derive(Component, Default, Clone, Copy, ExtractComponent, ShaderType)]
+    pub(super) struct PostProcessingSettings {
+        pub(super) vignette_strength: f32,
+        pub(super) aberration_offset: f32,
+    }
+}
+
+fn start_impact_pulse(mut ball_fell: EventReader<BallFell>, mut pulse: ResMut<ImpactPulse>) {
+    if ball_fell.read().count() > 0 {
+        pulse.0 = IMPACT_DURATION;
+    }
+}
+
+fn update_post_processing_settings(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<PostProcessingConfig>,
+    mut pulse: ResMut<ImpactPulse>,
+    mut cameras: Query<(Entity, Option<&mut PostProcessingSettings>), With<Camera3d>>,
+) {
+    pulse.0 = (pulse.0 - time.delta_secs()).max(0.0);
+    let impact = pulse.0 / IMPACT_DURATION;
+    let settings = PostProcessingSettings {
+        vignette_strength: config.vignette_strength,
+        aberration_offset: config.aberration_offset.max(IMPACT_ABERRATION * impact),
+    };
+
+    for (entity, camera_settings) in cameras.iter_mut() {
+        match camera_settings {
+            Some(mut camera_settings) => *camera_settings = settings,
+            None => {
+                commands.entity(entity).insert(settings);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Hash, PartialEq, Eq, Clone, RenderLabel)]
+struct PostProcessingLabel;
+
+/// The pipeline specialized for the texture format of a view.
+#[derive(Component)]
+struct PostProcessingPipelineId(CachedRenderPipelineId);
+
+fn prepare_post_processing_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<PostProcessingPipeline>>,
+    post_processing_pipeline: Res<PostProcessingPipeline>,
+    views: Query<(Entity, &ExtractedView), With<PostProcessingSettings>>,
+) {
+    for (entity, view) in views.iter() {
+        let format = if view.hdr {
+            ViewTarget::TEXTURE_FORMAT_HDR
+        } else {
+            TextureFormat::bevy_default()
+        };
+        let pipeline_id = pipelines.specialize(&pipeline_cache, &post_processing_pipeline, format);
+        commands
+            .entity(entity)
+            .insert(PostProcessingPipelineId(pipeline_id));
+    }
+}
+
+#[derive(Default)]
+struct PostProcessingNode;
+
+impl ViewNode for PostProcessingNode {
+    type ViewQuery = (
+        &'static ViewTarget,
+        &'static PostProcessingPipelineId,
+        &'static DynamicUniformIndex<PostProcessingSettings>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (view_target, pipeline_id, settings_index): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let pipeline = world.resource::<PostProcessingPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        // The shader is still loading.
+        let Some(render_pipeline) = pipeline_cache.get_render_pipeline(pipeline_id.0) else {
+            return Ok(());
+        };
+        let settings_uniforms = world.resource::<ComponentUniforms<PostProcessingSettings>>();
+        let Some(settings_binding) = settings_uniforms.uniforms().binding() else {
+            return Ok(());
+        };
+
+        // Swaps the view's textures, so the pass reads the last result and writes the other one.
+        let post_process = view_target.post_process_write();
+        let bind_group = render_context.render_device().create_bind_group(
+            "post_processing_bind_group",
+            &pipeline.layout,
+            &BindGroupEntries::sequential((
+                post_process.source,
+                &pipeline.sampler,
+                settings_binding.clone(),
+            )),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("post_processing_pass"),
+            color_attachments: &[Some(RenderPassColorAttachment {
+                view: post_process.destination,
+                resolve_target: None,
+                ops: Operations::default(),
+            })],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+        render_pass.set_render_pipeline(render_pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[settings_index.index()]);
+        // One triangle covering the screen.
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct PostProcessingPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+    shader: Handle<Shader>,
+}
+
+impl FromWorld for PostProcessingPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let layout = render_device.create_bind_group_layout(
+            "post_processing_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    uniform_buffer::<PostProcessingSettings>(true),
+                ),
+            ),
+        );
+        let sampler = render_device.create_sampler(&SamplerDescriptor::default());
+
+        Self {
+            layout,
+            sampler,
+            shader: world.load_asset(SHADER_ASSET_PATH),
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for PostProcessingPipeline {
+    /// The format of the view's target.
+    type Key = TextureFormat;
+
+    fn specialize(&self, format: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("post_processing_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: self.shader.clone(),
+                shader_defs: vec![],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constant_ranges: vec![],
+            zero_initialize_workgroup_memory: false,
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Turns glTF meshes named `plate_<label>_*` into pressure plates, e.g. `plate_a_Stone`, which sink
+//! a few centimeters while weighed down.
+//! A plate is pressed while dynamic bodies resting on it weigh at least its mass threshold, the
+//! `m<mass>` token after the label, e.g. `plate_a_m5_Stone`, or
+//! [`PressurePlateConfig::mass_threshold`]. A [`Ball`] also presses the plates without a mass
+//! token on its own, whatever it weighs, while heavy plates need a heavy enough ball.
+//!
+//! A [`SwitchActivated`] event is sent with the label and `pressed` when a plate is pressed, and
+//! with `pressed: false` when it's released, so the doors of the
+//! [`SwitchDoorPlugin`](crate::plugins::switch_door_plugin::SwitchDoorPlugin) with the label are
+//! open while it's pressed. A full restart, which sends [`ResetCheckpoints`], raises the plates.
+//!
+//! Plates are kinematic bodies, so their colliders sink with them and carry the ball down. The
+//! colliders are built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline.
+
+use bevy::{prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    checkpoint_plugin::ResetCheckpoints,
+    mesh_physics_plugin::{
+        ColliderKind, ObjectCollider, parse_mass_token, register_object_collider,
+    },
+    switch_door_plugin::SwitchActivated,
+    tween_plugin::ease_in_out,
+};
+
+const PREFIX: &str = "plate_";
+
+#[derive(Default)]
+pub struct PressurePlatePlugin {
+    pub config: PressurePlateConfig,
+}
+
+impl Plugin for PressurePlatePlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                prefix: PREFIX.to_string(),
+                kind: ColliderKind::Hull,
+                body: RigidBody::KinematicPositionBased,
+                ..default()
+            },
+        );
+        app.insert_resource(self.config.clone())
+            .add_event::<SwitchActivated>()
+            .add_event::<ResetCheckpoints>()
+            .add_systems(Update, (weigh_plates, reset_plates, move_plates).chain())
+            .add_observer(insert_pressure_plates);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct PressurePlateConfig {
+    /// The weight, in kilograms, that presses the plates without a mass token.
+    pub mass_threshold: f32,
+    /// How far the plates sink.
+    pub depth: f32,
+    /// How long a plate takes to sink or rise, in seconds.
+    pub press_duration: f32,
+}
+
+impl Default for PressurePlateConfig {
+    fn default() -> Self {
+        Self {
+            mass_threshold: 1.0,
+            depth: 0.05,
+            press_duration: 0.15,
+        }
+    }
+}
+
+/// A plate created from a `plate_` mesh, on the mesh's parent.
+#[derive(Component)]
+pub struct PressurePlate {
+    pub label: String,
+    /// The weight that presses it, in kilograms.
+    pub mass_threshold: f32,
+    /// Whether it has its own mass threshold, so the ball has to be heavy enough too.
+    pub heavy: bool,
+    pub pressed: bool,
+    /// From 0 when up to 1 when down.
+    progress: f32,
+    up_translation: Vec3,
+    down_translation: Vec3,
+}
+
+/// Returns the label and the mass threshold, if it has one, of a mesh name like `plate_a_Stone`
+/// or `plate_a_m5_Stone`.
+pub fn parse_plate_name(name: &str) -> Option<(&str, Option<f32>)> {
+    let mut tokens = name.strip_prefix(PREFIX)?.split('_');
+    let label = tokens.next().filter(|label| !label.is_empty())?;
+    let mass = tokens.next().and_then(parse_mass_token).flatten();
+    Some((label, mass))
+}
+
+fn insert_pressure_plates(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    config: Res<PressurePlateConfig>,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf), With<Mesh3d>>,
+    parents: Query<&Transform>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of)) = query.get(entity) else {
+            continue;
+        };
+        let Some((label, mass)) = parse_plate_name(name) else {
+            continue;
+        };
+        let Ok(parent_transform) = parents.get(child_of.parent()) else {
+            continue;
+        };
+
+        let up_translation = parent_transform.translation;
+        commands.entity(child_of.parent()).insert(PressurePlate {
+            label: label.to_string(),
+            mass_threshold: mass.unwrap_or(config.mass_threshold),
+            heavy: mass.is_some(),
+            pressed: false,
+            progress: 0.0,
+            up_translation,
+            down_translation: up_translation + parent_transform.down() * config.depth,
+        });
+    }
+}
+
+fn weigh_plates(
+    mut commands: Commands,
+    rapier_context: ReadRapierContext,
+    mut activated: EventWriter<SwitchActivated>,
+    mut plates: Query<(Entity, &mut PressurePlate)>,
+    bodies: Query<(&RigidBody, Option<&ReadMassProperties>, Has<Ball>)>,
+) {
+    let Ok(context) = rapier_context.single() else {
+        return;
+    };
+
+    for (entity, mut plate) in plates.iter_mut() {
+        let mut weight = 0.0;
+        let mut ball_on = false;
+        for pair in context.contact_pairs_with(entity) {
+            if !pair.has_any_active_contact() {
+                continue;
+            }
+            let other = if pair.collider1() == Some(entity) {
+                pair.collider2()
+            } else {
+                pair.collider1()
+            };
+            let Some((other, (body, mass, is_ball))) =
+                other.and_then(|other| Some((other, bodies.get(other).ok()?)))
+            else {
+                continue;
+            };
+            if !matches!(body, RigidBody::Dynamic) {
+                continue;
+            }
+
+            ball_on |= is_ball;
+            match mass {
+                Some(mass) => weight += mass.get().mass,
+                // The mass is read from the next frame on.
+                None => {
+                    commands.entity(other).insert(ReadMassProperties::default());
+                }
+            }
+        }
+
+        let pressed = weight >= plate.mass_threshold || (ball_on && !plate.heavy);
+        if pressed != plate.pressed {
+            plate.pressed = pressed;
+            activated.write(SwitchActivated {
+                label: plate.label.clone(),
+                pressed,
+            });
+        }
+    }
+}
+
+fn reset_plates(
+    mut reset: EventReader<ResetCheckpoints>,
+    mut plates: Query<(&mut PressurePlate, &mut Transform)>,
+) {
+    if reset.read().count() == 0 {
+        return;
+    }
+
+    // The doors are shut by the switch reset, so no release is sent.
+    for (mut plate, mut transform) in plates.iter_mut() {
+        plate.pressed = false;
+        plate.progress = 0.0;
+        transform.translation = plate.up_translation;
+    }
+}
+
+fn move_plates(
+    time: Res<Time>,
+    config: Res<PressurePlateConfig>,
+    mut plates: Query<(&mut PressurePlate, &mut Transform)>,
+) {
+    let step = time.delta_secs() / config.press_duration;
+    for (mut plate, mut transform) in plates.iter_mut() {
+        let target = if plate.pressed { 1.0 } else { 0.0 };
+        if plate.progress == target {
+            continue;
+        }
+
+        plate.progress = if plate.pressed {
+            (plate.progress + step).min(1.0)
+        } else {
+            (plate.progress - step).max(0.0)
+        };
+        transform.translation = plate
+            .up_translation
+            .lerp(plate.down_translation, ease_in_out(plate.progress));
+    }
+}
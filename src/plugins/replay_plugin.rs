@@ -0,0 +1,200 @@
+//! Records the position and rotation of the [`Ball`] and replays the last run with a ghost. A run
+//! ends when the ball reaches an unlocked [`Goal`](crate::plugins::goal_plugin::Goal) or falls, and
+//! pressing `V` after that plays it back with a semi-transparent [`GhostBall`] that follows the
+//! recorded path, while the ball can still be controlled.
+//!
+//! The ghost is a kinematic body without a collider, so it doesn't get in the ball's way.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    goal_plugin::{GoalPlugin, GoalReached},
+    kill_volume_plugin::BallFell,
+    level_manifest_plugin::{LevelEntity, UnloadLevel},
+};
+
+#[derive(Default)]
+pub struct ReplayPlugin {
+    pub config: ReplayConfig,
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if !app.is_plugin_added::<GoalPlugin>() {
+            app.add_plugins(GoalPlugin::default());
+        }
+        app.insert_resource(self.config.clone())
+            .init_resource::<ReplayBuffer>()
+            .add_event::<BallFell>()
+            .add_event::<UnloadLevel>()
+            .add_systems(Startup, setup_ghost_assets)
+            .add_systems(
+                Update,
+                (record_ball, end_runs, start_replay, play_replay).chain(),
+            );
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct ReplayConfig {
+    /// The samples recorded per second.
+    pub sample_rate: f32,
+    pub key: KeyCode,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 30.0,
+            key: KeyCode::KeyV,
+        }
+    }
+}
+
+/// The `(time, position, rotation)` samples of the ball, with the time since the run started.
+#[derive(Resource, Default)]
+pub struct ReplayBuffer {
+    /// The run being recorded.
+    pub samples: Vec<(f32, Vec3, Quat)>,
+    /// The last run that ended, which is the one replayed.
+    pub last_run: Vec<(f32, Vec3, Quat)>,
+    /// The elapsed time when the run being recorded started.
+    started: Option<f32>,
+    since_sample: f32,
+}
+
+/// The ghost replaying [`ReplayBuffer::last_run`].
+#[derive(Component)]
+pub struct GhostBall {
+    /// The seconds since the replay started.
+    pub time: f32,
+}
+
+/// The mesh and material of the ghost.
+#[derive(Resource)]
+struct GhostAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
+}
+
+fn setup_ghost_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(GhostAssets {
+        mesh: meshes.add(Sphere::new(1.0)),
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgba(0.6, 0.8, 1.0, 0.4),
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }),
+    });
+}
+
+fn record_ball(
+    time: Res<Time>,
+    config: Res<ReplayConfig>,
+    mut buffer: ResMut<ReplayBuffer>,
+    ball: Option<Single<&GlobalTransform, With<Ball>>>,
+) {
+    let Some(ball) = ball else {
+        return;
+    };
+
+    let now = time.elapsed_secs();
+    let started = *buffer.started.get_or_insert(now);
+    buffer.since_sample += time.delta_secs();
+    if !buffer.samples.is_empty() && buffer.since_sample < 1.0 / config.sample_rate {
+        return;
+    }
+
+    buffer.since_sample = 0.0;
+    let (_, rotation, translation) = ball.to_scale_rotation_translation();
+    buffer.samples.push((now - started, translation, rotation));
+}
+
+fn end_runs(
+    mut goal_reached: EventReader<GoalReached>,
+    mut ball_fell: EventReader<BallFell>,
+    mut unload: EventReader<UnloadLevel>,
+    mut buffer: ResMut<ReplayBuffer>,
+) {
+    if unload.read().count() > 0 {
+        *buffer = ReplayBuffer::default();
+        return;
+    }
+
+    let reached_goal = goal_reached.read().count() > 0;
+    let fell = ball_fell.read().count() > 0;
+    if !reached_goal && !fell {
+        return;
+    }
+
+    buffer.last_run = std::mem::take(&mut buffer.samples);
+    buffer.started = None;
+}
+
+fn start_replay(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    config: Res<ReplayConfig>,
+    buffer: Res<ReplayBuffer>,
+    assets: Res<GhostAssets>,
+    ball: Option<Single<&Ball>>,
+    mut ghosts: Query<&mut GhostBall>,
+) {
+    if !keyboard_input.just_pressed(config.key) {
+        return;
+    }
+    let Some(&(_, translation, rotation)) = buffer.last_run.first() else {
+        info!("There's no run to replay yet.");
+        return;
+    };
+
+    // Replaying again restarts the ghost.
+    if let Ok(mut ghost) = ghosts.single_mut() {
+        ghost.time = 0.0;
+        return;
+    }
+
+    let radius = ball.map_or(0.5, |ball| ball.radius);
+    commands.spawn((
+        LevelEntity,
+        GhostBall { time: 0.0 },
+        RigidBody::KinematicPositionBased,
+        Mesh3d(assets.mesh.clone()),
+        MeshMaterial3d(assets.material.clone()),
+        Transform {
+            translation,
+            rotation,
+            scale: Vec3::splat(radius),
+        },
+    ));
+}
+
+fn play_replay(
+    mut commands: Commands,
+    time: Res<Time>,
+    buffer: Res<ReplayBuffer>,
+    mut ghosts: Query<(Entity, &mut GhostBall, &mut Transform)>,
+) {
+    for (entity, mut ghost, mut transform) in ghosts.iter_mut() {
+        ghost.time += time.delta_secs();
+
+        let samples = &buffer.last_run;
+        let next = samples.partition_point(|&(time, ..)| time <= ghost.time);
+        if next == 0 || next >= samples.len() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let (from_time, from_translation, from_rotation) = samples[next - 1];
+        let (to_time, to_translation, to_rotation) = samples[next];
+        let t = (ghost.time - from_time) / (to_time - from_time);
+        transform.translation = from_translation.lerp(to_translation, t);
+        transform.rotation = from_rotation.slerp(to_rotation, t);
+    }
+}
@@ -0,0 +1,177 @@
+//! Moves a respawning ball back to its restart position along an arc instead of teleporting it.
+//! Insert [`Respawning`] on the ball to start; it's removed when the ball arrives. While it's
+//! there, the ball is held still, so controls should skip balls with it.
+//!
+//! When a [`BallFell`] event is sent, a countdown is shown and the ball respawns by itself when
+//! it ends, or right away when `R` is pressed. The countdown runs on virtual time, so it stops
+//! while the game is paused. Controls should also skip balls with a [`RespawnCountdown`], so a
+//! fallen ball can't be flung further.
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{checkpoint_plugin::RestartPosition, kill_volume_plugin::BallFell};
+
+/// How high the ball floats above the straight line at the middle of the arc.
+const ARC_HEIGHT: f32 = 2.0;
+/// The seconds from falling to respawning.
+const COUNTDOWN: f32 = 3.0;
+/// The seconds the ball takes to float back.
+const RESPAWN_DURATION: f32 = 1.0;
+
+pub struct RespawnPlugin;
+
+impl Plugin for RespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                start_respawn_countdown,
+                tick_respawn_countdown,
+                respawn_animation,
+            )
+                .chain(),
+        );
+    }
+}
+
+#[derive(Component)]
+pub struct Respawning {
+    pub target: Vec3,
+    /// In seconds.
+    pub duration: f32,
+    pub elapsed: f32,
+    /// Where the ball was when the animation started.
+    start: Option<Vec3>,
+}
+
+impl Respawning {
+    pub fn new(target: Vec3, duration: f32) -> Self {
+        Self {
+            target,
+            duration,
+            elapsed: 0.0,
+            start: None,
+        }
+    }
+}
+
+/// The time left until a fallen ball respawns.
+#[derive(Component)]
+pub struct RespawnCountdown {
+    pub remaining: f32,
+    /// Where the ball respawns, or its [`RestartPosition`] if `None`.
+    pub target: Option<Vec3>,
+}
+
+/// The text showing the countdown of a ball.
+#[derive(Component)]
+struct CountdownText {
+    ball: Entity,
+}
+
+fn start_respawn_countdown(
+    mut commands: Commands,
+    mut ball_fell: EventReader<BallFell>,
+    balls: Query<(), (Without<RespawnCountdown>, Without<Respawning>)>,
+) {
+    for event in ball_fell.read() {
+        if !balls.contains(event.ball) {
+            continue;
+        }
+
+        commands.entity(event.ball).insert(RespawnCountdown {
+            remaining: COUNTDOWN,
+            target: event.respawn,
+        });
+        commands.spawn((
+            CountdownText { ball: event.ball },
+            Text::default(),
+            TextFont {
+                font_size: 32.0,
+                ..default()
+            },
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(55.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            TextLayout::new_with_justify(JustifyText::Center),
+        ));
+    }
+}
+
+fn tick_respawn_countdown(
+    mut commands: Commands,
+    time: Res<Time>,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut balls: Query<(Entity, &mut RespawnCountdown, Option<&RestartPosition>)>,
+    mut texts: Query<(Entity, &CountdownText, &mut Text)>,
+) {
+    let skip = keyboard.just_pressed(KeyCode::KeyR);
+    for (ball, mut countdown, restart_position) in balls.iter_mut() {
+        countdown.remaining -= time.delta_secs();
+        if countdown.remaining > 0.0 && !skip {
+            continue;
+        }
+
+        commands.entity(ball).remove::<RespawnCountdown>();
+        match countdown
+            .target
+            .or(restart_position.map(|restart_position| restart_position.0))
+        {
+            Some(target) => {
+                commands
+                    .entity(ball)
+                    .insert(Respawning::new(target, RESPAWN_DURATION));
+            }
+            None => warn!("Ball {ball} has nowhere to respawn."),
+        }
+    }
+
+    for (entity, countdown_text, mut text) in texts.iter_mut() {
+        match balls.get(countdown_text.ball) {
+            Ok((_, countdown, _)) if countdown.remaining > 0.0 && !skip => {
+                text.0 = format!("Respawning in {}…", countdown.remaining.ceil());
+            }
+            _ => commands.entity(entity).despawn(),
+        }
+    }
+}
+
+fn respawn_animation(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(
+        Entity,
+        &mut Respawning,
+        &mut Transform,
+        Option<&mut Velocity>,
+    )>,
+) {
+    for (entity, mut respawning, mut transform, velocity) in query.iter_mut() {
+        // Physics would pull the ball away from the arc.
+        if let Some(mut velocity) = velocity {
+            *velocity = Velocity::zero();
+        }
+
+        let start = *respawning.start.get_or_insert(transform.translation);
+        respawning.elapsed += time.delta_secs();
+        let t = if respawning.duration > 0.0 {
+            (respawning.elapsed / respawning.duration).min(1.0)
+        } else {
+            1.0
+        };
+
+        // Eased along the line, and a parabola on top that rises and comes back down.
+        let eased = t * t * (3.0 - 2.0 * t);
+        let lift = 4.0 * t * (1.0 - t) * ARC_HEIGHT;
+        transform.translation = start.lerp(respawning.target, eased) + Vec3::Y * lift;
+
+        if t >= 1.0 {
+            commands.entity(entity).remove::<Respawning>();
+        }
+    }
+}
@@ -0,0 +1,207 @@
+//! Keeps the player's progress in [`SaveGamePlugin::path`] as JSON.
+//!
+//! The game is saved when a checkpoint is activated, when a level is won, when the app exits,
+//! and on a [`SaveEvent`]. The save is loaded at startup if it exists, and on a [`LoadEvent`],
+//! which restores the level of the [`LevelManager`], the [`Score`], the [`Lives`] and the
+//! [`Leaderboard`].
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::plugins::{
+    campaign_plugin::CampaignState,
+    checkpoint_plugin::CheckpointActivated,
+    level_manifest_plugin::LevelManager,
+    lives_plugin::{Lives, PlayTime},
+};
+
+pub struct SaveGamePlugin {
+    pub path: PathBuf,
+}
+
+impl Default for SaveGamePlugin {
+    fn default() -> Self {
+        Self {
+            path: PathBuf::from("saves/save.json"),
+        }
+    }
+}
+
+impl Plugin for SaveGamePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(SavePath(self.path.clone()))
+            .init_resource::<SaveState>()
+            .init_resource::<Score>()
+            .init_resource::<Leaderboard>()
+            .add_event::<SaveEvent>()
+            .add_event::<LoadEvent>()
+            .add_event::<CheckpointActivated>()
+            .add_systems(Startup, read_save.pipe(restore_progress))
+            .add_systems(OnEnter(CampaignState::Won), record_best_time)
+            .add_systems(
+                Update,
+                read_save
+                    .pipe(restore_progress)
+                    .run_if(on_event::<LoadEvent>),
+            )
+            .add_systems(Last, save_game);
+    }
+}
+
+/// What's written to the save file.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct SaveState {
+    pub level_index: usize,
+    pub score: u32,
+    /// The best time of each level, by the asset path of its manifest.
+    pub best_times: HashMap<String, f32>,
+    pub lives: u32,
+    /// The IDs of the unlocked achievements.
+    pub unlocked_achievements: HashSet<String>,
+}
+
+#[derive(Resource, Default)]
+pub struct Score(pub u32);
+
+/// The best time of each level, by the asset path of its manifest.
+#[derive(Resource, Default)]
+pub struct Leaderboard(pub HashMap<String, f32>);
+
+impl Leaderboard {
+    /// Keeps the time if it's the level's best. Returns whether it was.
+    pub fn record(&mut self, level: String, time: f32) -> bool {
+        let best = self.0.entry(level).or_insert(f32::INFINITY);
+        let improved = time < *best;
+        *best = best.min(time);
+        improved
+    }
+
+    pub fn best_time(&self, level: &str) -> Option<f32> {
+        self.0.get(level).copied()
+    }
+}
+
+/// Writes the save file at the end of the frame.
+#[derive(Event)]
+pub struct SaveEvent;
+
+/// Reads the save file and restores the progress from it.
+#[derive(Event)]
+pub struct LoadEvent;
+
+#[derive(Resource)]
+struct SavePath(PathBuf);
+
+fn record_best_time(
+    manager: Res<LevelManager>,
+    play_time: Option<Res<PlayTime>>,
+    mut leaderboard: ResMut<Leaderboard>,
+    mut save: EventWriter<SaveEvent>,
+) {
+    let Some(level) = manager.current().and_then(|handle| handle.path()) else {
+        return;
+    };
+    let Some(play_time) = play_time else {
+        return;
+    };
+
+    if leaderboard.record(level.to_string(), play_time.0) {
+        info!("New best time on {level}: {:.1} s", play_time.0);
+    }
+    save.write(SaveEvent);
+}
+
+/// Reads the save file into the [`SaveState`]. Returns whether there was a save to read.
+fn read_save(path: Res<SavePath>, mut save_state: ResMut<SaveState>) -> bool {
+    let json = match std::fs::read_to_string(&path.0) {
+        Ok(json) => json,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return false,
+        Err(err) => {
+            error!("Failed to read the save from {}: {err}", path.0.display());
+            return false;
+        }
+    };
+    match serde_json::from_str(&json) {
+        Ok(loaded) => {
+            *save_state = loaded;
+            info!("Loaded the save from {}.", path.0.display());
+            true
+        }
+        Err(err) => {
+            error!("Failed to parse the save in {}: {err}", path.0.display());
+            false
+        }
+    }
+}
+
+fn restore_progress(
+    In(loaded): In<bool>,
+    save_state: Res<SaveState>,
+    mut score: ResMut<Score>,
+    mut leaderboard: ResMut<Leaderboard>,
+    manager: Option<ResMut<LevelManager>>,
+    lives: Option<ResMut<Lives>>,
+) {
+    if !loaded {
+        return;
+    }
+
+    score.0 = save_state.score;
+    leaderboard.0 = save_state.best_times.clone();
+    if let Some(mut manager) = manager {
+        manager.current_level = save_state.level_index;
+    }
+    // A save without lives, e.g. from before they were saved, keeps the full lives.
+    if let Some(mut lives) = lives.filter(|_| save_state.lives > 0) {
+        lives.current = save_state.lives.min(lives.max);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn save_game(
+    mut save: EventReader<SaveEvent>,
+    mut checkpoints: EventReader<CheckpointActivated>,
+    mut exit: EventReader<AppExit>,
+    path: Res<SavePath>,
+    mut save_state: ResMut<SaveState>,
+    score: Res<Score>,
+    leaderboard: Res<Leaderboard>,
+    manager: Option<Res<LevelManager>>,
+    lives: Option<Res<Lives>>,
+) {
+    let requests = save.read().count() + checkpoints.read().count() + exit.read().count();
+    if requests == 0 {
+        return;
+    }
+
+    save_state.score = score.0;
+    save_state.best_times = leaderboard.0.clone();
+    if let Some(manager) = manager {
+        save_state.level_index = manager.current_level;
+    }
+    if let Some(lives) = lives {
+        save_state.lives = lives.current;
+    }
+
+    let json = match serde_json::to_string_pretty(&*save_state) {
+        Ok(json) => json,
+        Err(err) => {
+            error!("Failed to serialize the save: {err}");
+            return;
+        }
+    };
+    let directory = path.0.parent().unwrap_or(std::path::Path::new(""));
+    if let Err(err) = std::fs::create_dir_all(directory) {
+        error!("Failed to create {}: {err}", directory.display());
+        return;
+    }
+    if let Err(err) = std::fs::write(&path.0, json) {
+        error!("Failed to save the game to {}: {err}", path.0.display());
+    }
+}
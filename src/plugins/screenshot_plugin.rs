@@ -0,0 +1,101 @@
+//! Saves a screenshot of the primary window as a PNG when [`ScreenshotConfig::key`] is pressed.
+//! The files are named by the time they're taken, e.g. `screenshot_1760000000.png`, and a
+//! [`ShowNotification`] is sent once one is saved.
+
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::{
+    prelude::*,
+    render::view::screenshot::{Screenshot, ScreenshotCaptured},
+};
+
+use crate::plugins::notification_plugin::ShowNotification;
+
+#[derive(Default)]
+pub struct ScreenshotPlugin {
+    pub config: ScreenshotConfig,
+}
+
+impl Plugin for ScreenshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.config.clone())
+            .add_event::<ShowNotification>()
+            .add_systems(Update, take_screenshot);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct ScreenshotConfig {
+    pub key: KeyCode,
+    /// Created if it doesn't exist. Relative paths are relative to the working directory.
+    pub directory: PathBuf,
+}
+
+impl Default for ScreenshotConfig {
+    fn default() -> Self {
+        Self {
+            key: KeyCode::F12,
+            directory: PathBuf::from("screenshots"),
+        }
+    }
+}
+
+/// Where a screenshot being captured is saved.
+#[derive(Component)]
+struct ScreenshotPath(PathBuf);
+
+fn take_screenshot(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    config: Res<ScreenshotConfig>,
+) {
+    if !keyboard.just_pressed(config.key) {
+        return;
+    }
+
+    if let Err(err) = std::fs::create_dir_all(&config.directory) {
+        error!(
+            "Failed to create the screenshot directory {}: {err}",
+            config.directory.display()
+        );
+        return;
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis());
+    let path = config.directory.join(format!("screenshot_{timestamp}.png"));
+
+    commands
+        .spawn((Screenshot::primary_window(), ScreenshotPath(path)))
+        .observe(save_screenshot);
+}
+
+/// Saves the captured image to the [`ScreenshotPath`] of the screenshot entity.
+fn save_screenshot(
+    trigger: Trigger<ScreenshotCaptured>,
+    mut notifications: EventWriter<ShowNotification>,
+    paths: Query<&ScreenshotPath>,
+) {
+    let Ok(ScreenshotPath(path)) = paths.get(trigger.target()) else {
+        return;
+    };
+    let image = match trigger.event().0.clone().try_into_dynamic() {
+        Ok(image) => image.to_rgb8(),
+        Err(err) => {
+            error!("Failed to convert the screenshot: {err:?}");
+            return;
+        }
+    };
+
+    match image.save(path) {
+        Ok(()) => {
+            info!("Saved a screenshot to {}.", path.display());
+            notifications.write(ShowNotification("Screenshot saved!".to_string()));
+        }
+        Err(err) => error!("Failed to save the screenshot to {}: {err}", path.display()),
+    }
+}
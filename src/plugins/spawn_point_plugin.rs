@@ -0,0 +1,103 @@
+//! Collects the player start positions from glTF objects named `spawn_*`,
+//! so levels don't need hard-coded positions in Rust.
+//!
+//! An object named `start` or `start_*` is where the ball starts: once the scene is ready,
+//! every [`Ball`] and its [`RestartPosition`] are moved there, and the object's transform is
+//! kept in [`PlayerStart`] so cameras can face the same way.
+
+use bevy::{prelude::*, scene::SceneInstanceReady, transform::helper::TransformHelper};
+
+use crate::plugins::{ball_physics_plugin::Ball, checkpoint_plugin::RestartPosition};
+
+pub struct SpawnPointPlugin;
+
+impl Plugin for SpawnPointPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpawnPoints>()
+            .init_resource::<PlayerStart>()
+            .add_observer(collect_spawn_points)
+            .add_observer(move_to_start);
+    }
+}
+
+/// World positions of the spawn points, sorted by object name.
+#[derive(Resource, Default)]
+pub struct SpawnPoints(pub Vec<Vec3>);
+
+impl SpawnPoints {
+    pub fn first(&self) -> Option<Vec3> {
+        self.0.first().copied()
+    }
+}
+
+/// The world transform of the `start` object of the last loaded scene, `None` if it had none.
+#[derive(Resource, Default)]
+pub struct PlayerStart(pub Option<Transform>);
+
+impl PlayerStart {
+    /// The yaw of the start's forward direction, for a camera that should look down the track.
+    pub fn yaw(&self) -> Option<f32> {
+        let forward = self.0?.forward();
+        Some(f32::atan2(-forward.x, -forward.z))
+    }
+}
+
+fn collect_spawn_points(
+    trigger: Trigger<SceneInstanceReady>,
+    mut spawn_points: ResMut<SpawnPoints>,
+    children: Query<&Children>,
+    names: Query<&Name>,
+    transform_helper: TransformHelper,
+) {
+    let mut points: Vec<_> = children
+        .iter_descendants(trigger.target())
+        .filter_map(|entity| Some((names.get(entity).ok()?, entity)))
+        .filter(|(name, _)| name.starts_with("spawn_"))
+        .collect();
+    points.sort_by_key(|(name, _)| name.as_str());
+
+    // Global transforms aren't propagated to the new scene entities yet, so they're computed
+    // from the hierarchy.
+    spawn_points.0 = points
+        .into_iter()
+        .filter_map(|(_, entity)| transform_helper.compute_global_transform(entity).ok())
+        .map(|transform| transform.translation())
+        .collect();
+    info!("Found {} spawn points.", spawn_points.0.len());
+}
+
+fn move_to_start(
+    trigger: Trigger<SceneInstanceReady>,
+    mut player_start: ResMut<PlayerStart>,
+    children: Query<&Children>,
+    names: Query<&Name>,
+    transform_helper: TransformHelper,
+    mut balls: Query<(&mut Transform, Option<&mut RestartPosition>), With<Ball>>,
+) {
+    let start = children
+        .iter_descendants(trigger.target())
+        .find(|&entity| {
+            names
+                .get(entity)
+                .is_ok_and(|name| name.as_str() == "start" || name.starts_with("start_"))
+        })
+        .and_then(|entity| transform_helper.compute_global_transform(entity).ok());
+    let Some(start) = start else {
+        warn!(
+            "Scene {} has no `start` object, the ball stays where it is.",
+            trigger.target()
+        );
+        player_start.0 = None;
+        return;
+    };
+
+    // Scaling the ball with the start object would change its collider.
+    let start = Transform::from_translation(start.translation()).with_rotation(start.rotation());
+    player_start.0 = Some(start);
+    for (mut transform, restart_position) in balls.iter_mut() {
+        transform.translation = start.translation;
+        if let Some(mut restart_position) = restart_position {
+            restart_position.0 = start.translation;
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! Turns glTF meshes named `speed_<magnitude>_*` into strips that boost the ball, e.g.
+//! `speed_20.0_Strip`.
+//! When a [`Ball`] touches a strip, it gets a [`SpeedStripEffect`] that pushes it in the strip's
+//! forward direction with the magnitude as the force, for [`SpeedStripConfig::duration`]
+//! seconds. The forward direction is the strip's local `-Z`, which is `+Y` in Blender.
+//!
+//! The strip's material scrolls along the texture's `V` axis, so the strip's UVs should be laid
+//! out with `V` pointing forward. The strips' sensors are built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline.
+
+use bevy::{math::Affine2, prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    mesh_physics_plugin::{ObjectCollider, SensorTriggered, register_object_collider},
+};
+
+const PREFIX: &str = "speed_";
+
+#[derive(Default)]
+pub struct SpeedStripPlugin {
+    pub config: SpeedStripConfig,
+}
+
+impl Plugin for SpeedStripPlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                hide_mesh: false,
+                ..ObjectCollider::sensor(PREFIX)
+            },
+        );
+        app.insert_resource(self.config.clone())
+            .add_systems(
+                Update,
+                (
+                    (start_speed_strip, tick_speed_strip, apply_speed_strip).chain(),
+                    scroll_strip_materials,
+                ),
+            )
+            .add_observer(insert_speed_strips);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct SpeedStripConfig {
+    /// How long the boost lasts after touching a strip, in seconds.
+    pub duration: f32,
+    /// How fast the strip's material scrolls, in UV units per second.
+    pub scroll_speed: f32,
+}
+
+impl Default for SpeedStripConfig {
+    fn default() -> Self {
+        Self {
+            duration: 1.0,
+            scroll_speed: 1.5,
+        }
+    }
+}
+
+/// A strip created from a `speed_` mesh, on the mesh's parent.
+#[derive(Component)]
+pub struct SpeedStrip {
+    pub force: f32,
+}
+
+/// A boost from a [`SpeedStrip`] on a ball. Touching a strip again restarts it.
+#[derive(Component)]
+pub struct SpeedStripEffect {
+    pub force: f32,
+    /// The strip's forward direction when it was touched.
+    pub direction: Vec3,
+    pub duration: f32,
+    pub elapsed: f32,
+}
+
+/// The boost force currently added to a ball's [`ExternalForce`], so it can be taken back when
+/// the effect ends without touching the forces from other systems.
+#[derive(Component)]
+struct AppliedSpeedStrip(Vec3);
+
+/// A strip mesh's own copy of its material, whose UVs are scrolled.
+#[derive(Component)]
+struct ScrollingMaterial(Handle<StandardMaterial>);
+
+/// Returns the force magnitude of a mesh name like `speed_20.0_Strip`.
+pub fn parse_speed_magnitude(name: &str) -> Option<f32> {
+    let rest = name.strip_prefix(PREFIX)?;
+    rest.split('_').next()?.parse().ok()
+}
+
+#[allow(clippy::type_complexity)]
+fn insert_speed_strips(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf, Option<&MeshMaterial3d<StandardMaterial>>), With<Mesh3d>>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of, material)) = query.get(entity) else {
+            continue;
+        };
+        if !name.starts_with(PREFIX) {
+            continue;
+        }
+        let Some(force) = parse_speed_magnitude(name) else {
+            warn!("`{name}` should be named like `speed_20.0_Strip`, it won't be a speed strip.");
+            continue;
+        };
+
+        commands
+            .entity(child_of.parent())
+            .insert(SpeedStrip { force });
+
+        // Copied so other meshes sharing the material don't scroll too.
+        if let Some(material) = material
+            .and_then(|material| materials.get(&material.0))
+            .cloned()
+        {
+            let material = materials.add(material);
+            commands.entity(entity).insert((
+                MeshMaterial3d(material.clone()),
+                ScrollingMaterial(material),
+            ));
+        }
+    }
+}
+
+fn start_speed_strip(
+    mut commands: Commands,
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    config: Res<SpeedStripConfig>,
+    strips: Query<(&SpeedStrip, &GlobalTransform)>,
+    balls: Query<(), With<Ball>>,
+) {
+    for event in sensor_triggered.read() {
+        if !event.started || !balls.contains(event.other) {
+            continue;
+        }
+        let Ok((strip, strip_transform)) = strips.get(event.sensor) else {
+            continue;
+        };
+
+        commands.entity(event.other).insert(SpeedStripEffect {
+            force: strip.force,
+            direction: *strip_transform.forward(),
+            duration: config.duration,
+            elapsed: 0.0,
+        });
+    }
+}
+
+fn tick_speed_strip(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut SpeedStripEffect)>,
+) {
+    for (entity, mut effect) in query.iter_mut() {
+        effect.elapsed += time.delta_secs();
+        if effect.elapsed >= effect.duration {
+            commands.entity(entity).remove::<SpeedStripEffect>();
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_speed_strip(
+    mut commands: Commands,
+    mut balls: Query<
+        (
+            Entity,
+            &mut ExternalForce,
+            Option<&SpeedStripEffect>,
+            Option<&mut AppliedSpeedStrip>,
+        ),
+        With<Ball>,
+    >,
+) {
+    for (entity, mut force, effect, applied) in balls.iter_mut() {
+        let boost = effect.map_or(Vec3::ZERO, |effect| effect.direction * effect.force);
+        match applied {
+            Some(mut applied) => {
+                force.force += boost - applied.0;
+                applied.0 = boost;
+                if effect.is_none() {
+                    commands.entity(entity).remove::<AppliedSpeedStrip>();
+                }
+            }
+            None if effect.is_some() => {
+                force.force += boost;
+                commands.entity(entity).insert(AppliedSpeedStrip(boost));
+            }
+            None => {}
+        }
+    }
+}
+
+fn scroll_strip_materials(
+    time: Res<Time>,
+    config: Res<SpeedStripConfig>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    query: Query<&ScrollingMaterial>,
+) {
+    let offset = (time.elapsed_secs() * config.scroll_speed).fract();
+    for material in query.iter() {
+        if let Some(material) = materials.get_mut(&material.0) {
+            material.uv_transform = Affine2::from_translation(Vec2::new(0.0, -offset));
+        }
+    }
+}
@@ -0,0 +1,323 @@
+//! Turns glTF meshes named `switch_<label>_*` into switches and `door_<label>_*` into doors, e.g.
+//! `switch_a_Plate` and `door_a_Gate`.
+//! When a [`Ball`] touches a switch, a [`SwitchActivated`] event is sent with its label, and every
+//! door with the label slides open along its local up by its height over a second. The event
+//! carries whether the switch is pressed, and the doors follow it, so several switches with the
+//! same label don't toggle a door back and forth.
+//!
+//! A switch triggers once, unless it's named `switch_toggle_<label>_*`, in which case every touch
+//! flips it, shutting the doors again on every other touch. A full restart, which sends [`ResetCheckpoints`], shuts the doors and
+//! re-arms the switches.
+//!
+//! Doors are kinematic bodies, so their colliders move with them. The colliders of both are built
+//! by the [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline.
+
+use bevy::{prelude::*, render::mesh::MeshAabb, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    checkpoint_plugin::ResetCheckpoints,
+    mesh_physics_plugin::{
+        ColliderKind, ObjectCollider, SensorTriggered, register_object_collider,
+    },
+    tween_plugin::ease_in_out,
+};
+
+const SWITCH_PREFIX: &str = "switch_";
+const DOOR_PREFIX: &str = "door_";
+
+/// How long a door takes to open or shut, in seconds.
+const DOOR_DURATION: f32 = 1.0;
+
+pub struct SwitchDoorPlugin;
+
+impl Plugin for SwitchDoorPlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                hide_mesh: false,
+                ..ObjectCollider::sensor(SWITCH_PREFIX)
+            },
+        );
+        register_object_collider(
+            app,
+            ObjectCollider {
+                prefix: DOOR_PREFIX.to_string(),
+                kind: ColliderKind::Hull,
+                body: RigidBody::KinematicPositionBased,
+                ..default()
+            },
+        );
+        app.add_event::<SwitchActivated>()
+            .add_event::<ResetCheckpoints>()
+            .add_systems(
+                Update,
+                (press_switches, open_doors, reset_switches, move_doors).chain(),
+            )
+            .add_observer(insert_switches_and_doors);
+    }
+}
+
+/// A switch created from a `switch_` mesh, on the mesh's parent.
+#[derive(Component)]
+pub struct Switch {
+    pub label: String,
+    /// Whether every touch flips it, instead of only the first pressing it.
+    pub toggle: bool,
+    /// Whether it's pressed, so its doors are open.
+    pub activated: bool,
+}
+
+/// A door created from a `door_` mesh, on the mesh's parent.
+#[derive(Component)]
+pub struct Door {
+    pub label: String,
+    pub open: bool,
+    /// From 0 when shut to 1 when open.
+    progress: f32,
+    shut_translation: Vec3,
+    open_translation: Vec3,
+}
+
+/// Sent when a switch or a
+/// [`PressurePlate`](crate::plugins::pressure_plate_plugin::PressurePlate) is pressed or released.
+/// The doors with the label are opened while it's pressed and shut once it's released.
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct SwitchActivated {
+    pub label: String,
+    pub pressed: bool,
+}
+
+/// Returns the label of a mesh name like `switch_a_Plate`, and whether it's a toggle switch like
+/// `switch_toggle_a`.
+pub fn parse_switch_name(name: &str) -> Option<(&str, bool)> {
+    let rest = name.strip_prefix(SWITCH_PREFIX)?;
+    let (rest, toggle) = match rest.strip_prefix("toggle_") {
+        Some(rest) => (rest, true),
+        None => (rest, false),
+    };
+    let label = rest.split('_').next()?;
+    (!label.is_empty()).then_some((label, toggle))
+}
+
+/// Returns the label of a mesh name like `door_a_Gate`.
+pub fn parse_door_label(name: &str) -> Option<&str> {
+    let label = name.strip_prefix(DOOR_PREFIX)?.split('_').next()?;
+    (!label.is_empty()).then_some(label)
+}
+
+fn insert_switches_and_doors(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    meshes: Res<Assets<Mesh>>,
+    children: Query<&Children>,
+    query: Query<(&Name, &Mesh3d, &ChildOf, Option<&Transform>)>,
+    parents: Query<&Transform>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, mesh3d, child_of, transform)) = query.get(entity) else {
+            continue;
+        };
+        let switch = parse_switch_name(name);
+        let door_label = parse_door_label(name);
+        if switch.is_none() && door_label.is_none() {
+            continue;
+        }
+
+        if let Some((label, toggle)) = switch {
+            commands.entity(child_of.parent()).insert(Switch {
+                label: label.to_string(),
+                toggle,
+                activated: false,
+            });
+            continue;
+        }
+
+        let Some(label) = door_label else {
+            continue;
+        };
+        let Ok(parent_transform) = parents.get(child_of.parent()) else {
+            continue;
+        };
+        let Some(mesh) = meshes.get(&mesh3d.0) else {
+            error!("The mesh of `{name}` isn't loaded.");
+            continue;
+        };
+        let height = mesh
+            .compute_aabb()
+            .map_or(0.0, |aabb| aabb.half_extents.y * 2.0)
+            * transform.map_or(1.0, |transform| transform.scale.y)
+            * parent_transform.scale.y;
+        let shut_translation = parent_transform.translation;
+        commands.entity(child_of.parent()).insert(Door {
+            label: label.to_string(),
+            open: false,
+            progress: 0.0,
+            shut_translation,
+            open_translation: shut_translation + parent_transform.up() * height,
+        });
+    }
+}
+
+fn press_switches(
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    mut activated: EventWriter<SwitchActivated>,
+    mut switches: Query<&mut Switch>,
+    balls: Query<(), With<Ball>>,
+) {
+    for event in sensor_triggered.read() {
+        if !event.started || !balls.contains(event.other) {
+            continue;
+        }
+        let Ok(mut switch) = switches.get_mut(event.sensor) else {
+            continue;
+        };
+        if switch.activated && !switch.toggle {
+            continue;
+        }
+
+        // Only toggle switches get here when they're pressed already.
+        switch.activated = !switch.activated;
+        activated.write(SwitchActivated {
+            label: switch.label.clone(),
+            pressed: switch.activated,
+        });
+    }
+}
+
+fn open_doors(mut activated: EventReader<SwitchActivated>, mut doors: Query<&mut Door>) {
+    for event in activated.read() {
+        for mut door in doors.iter_mut() {
+            if door.label == event.label {
+                door.open = event.pressed;
+            }
+        }
+    }
+}
+
+fn reset_switches(
+    mut reset: EventReader<ResetCheckpoints>,
+    mut switches: Query<&mut Switch>,
+    mut doors: Query<(&mut Door, &mut Transform)>,
+) {
+    if reset.read().count() == 0 {
+        return;
+    }
+
+    for mut switch in switches.iter_mut() {
+        switch.activated = false;
+    }
+    for (mut door, mut transform) in doors.iter_mut() {
+        door.open = false;
+        door.progress = 0.0;
+        transform.translation = door.shut_translation;
+    }
+}
+
+fn move_doors(time: Res<Time>, mut doors: Query<(&mut Door, &mut Transform)>) {
+    let step = time.delta_secs() / DOOR_DURATION;
+    for (mut door, mut transform) in doors.iter_mut() {
+        let target = if door.open { 1.0 } else { 0.0 };
+        if door.progress == target {
+            continue;
+        }
+
+        door.progress = if door.open {
+            (door.progress + step).min(1.0)
+        } else {
+            (door.progress - step).max(0.0)
+        };
+        transform.translation = door
+            .shut_translation
+            .lerp(door.open_translation, ease_in_out(door.progress));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), SwitchDoorPlugin))
+            .init_asset::<Mesh>()
+            .add_event::<CollisionEvent>();
+        app
+    }
+
+    fn spawn_door(app: &mut App, label: &str) -> Entity {
+        app.world_mut()
+            .spawn((
+                Door {
+                    label: label.to_string(),
+                    open: false,
+                    progress: 0.0,
+                    shut_translation: Vec3::ZERO,
+                    open_translation: Vec3::Y,
+                },
+                Transform::default(),
+            ))
+            .id()
+    }
+
+    fn is_open(app: &App, door: Entity) -> bool {
+        app.world().get::<Door>(door).unwrap().open
+    }
+
+    #[test]
+    fn doors_follow_the_pressed_state() {
+        let mut app = test_app();
+        let door = spawn_door(&mut app, "a");
+        let other_door = spawn_door(&mut app, "b");
+
+        for pressed in [true, true, false, false] {
+            app.world_mut().send_event(SwitchActivated {
+                label: "a".to_string(),
+                pressed,
+            });
+            app.update();
+            assert_eq!(is_open(&app, door), pressed);
+        }
+        assert!(!is_open(&app, other_door));
+    }
+
+    #[test]
+    fn toggle_switches_flip_their_doors() {
+        let mut app = test_app();
+        let door = spawn_door(&mut app, "a");
+        let ball = app.world_mut().spawn(Ball { radius: 0.5 }).id();
+        let [switch, toggle_switch] = [false, true].map(|toggle| {
+            app.world_mut()
+                .spawn(Switch {
+                    label: "a".to_string(),
+                    toggle,
+                    activated: false,
+                })
+                .id()
+        });
+        let touch = |app: &mut App, switch: Entity| {
+            app.world_mut().send_event(SensorTriggered {
+                label: "switch".to_string(),
+                sensor: switch,
+                other: ball,
+                started: true,
+            });
+            app.update();
+        };
+
+        touch(&mut app, toggle_switch);
+        assert!(is_open(&app, door));
+        touch(&mut app, toggle_switch);
+        assert!(!is_open(&app, door));
+
+        // A one-shot switch only opens the door the first time.
+        touch(&mut app, switch);
+        assert!(is_open(&app, door));
+        touch(&mut app, toggle_switch);
+        assert!(is_open(&app, door));
+        touch(&mut app, switch);
+        assert!(is_open(&app, door));
+    }
+}
@@ -0,0 +1,275 @@
+//! Pairs glTF meshes named `teleport_<GroupName>_In` and `teleport_<GroupName>_Out` into
+//! teleporters, where the ends can also be written `_in` and `_out`. When a [`Ball`] touches an
+//! `In` mesh, it's moved to the `Out` mesh of the same group, a bit above it along its up, with a
+//! [`ParticleBurst`] at both ends and an optional sound. The bursts are spawned by the
+//! [`ParticleEffectPlugin`](crate::plugins::particle_effect_plugin::ParticleEffectPlugin).
+//! A level can have several teleporters with different group names, and the ends that aren't
+//! paired are warned about when the scene is loaded.
+//!
+//! The ball is stopped, unless [`TeleporterConfig::preserve_velocity`] is set, in which case its
+//! velocity is rotated from the entrance's frame into the exit's frame. The 3D cameras are moved
+//! along with the ball, so a camera following it ends up behind it instead of staring at a wall,
+//! except while the orbit or cinematic cameras control them. A [`BallTeleported`] event is sent
+//! for other camera controllers.
+//!
+//! After teleporting, the ball can't teleport again for a short cooldown, so an exit that
+//! overlaps another teleporter doesn't send it straight back.
+//!
+//! Both ends get a visible sensor built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline, but
+//! only the `In` ends teleport.
+
+use bevy::{platform::collections::HashMap, prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    cinematic_camera_plugin::CinematicMode,
+    level_manifest_plugin::LevelEntity,
+    mesh_physics_plugin::{ObjectCollider, SensorTriggered, register_object_collider},
+    orbit_camera_plugin::OrbitCameraEnabled,
+    particle_effect_plugin::ParticleBurst,
+};
+
+const PREFIX: &str = "teleport_";
+
+const BURST_PARTICLES: u32 = 16;
+const BURST_SPEED: f32 = 3.0;
+/// How long the particles of a burst live, in seconds.
+const BURST_LIFETIME: f32 = 0.6;
+const BURST_COLOR: Color = Color::linear_rgb(0.4, 0.8, 1.0);
+
+#[derive(Default)]
+pub struct TeleporterPlugin {
+    pub config: TeleporterConfig,
+}
+
+impl Plugin for TeleporterPlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                hide_mesh: false,
+                ..ObjectCollider::sensor(PREFIX)
+            },
+        );
+        app.insert_resource(self.config.clone())
+            .add_event::<BallTeleported>()
+            .add_event::<ParticleBurst>()
+            .add_systems(Update, (tick_teleport_cooldowns, teleport_ball).chain())
+            .add_observer(insert_teleporters);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct TeleporterConfig {
+    /// Whether the ball keeps its velocity, rotated into the exit's frame, instead of stopping.
+    pub preserve_velocity: bool,
+    /// How far above the exit the ball is put, along the exit's up.
+    pub exit_offset: f32,
+    /// The seconds after teleporting during which the ball can't teleport again.
+    pub cooldown: f32,
+    /// The asset path of the sound played when the ball teleports.
+    pub sound: Option<String>,
+}
+
+impl Default for TeleporterConfig {
+    fn default() -> Self {
+        Self {
+            preserve_velocity: false,
+            exit_offset: 0.5,
+            cooldown: 0.5,
+            sound: None,
+        }
+    }
+}
+
+/// Sent when a ball teleports.
+#[derive(Event)]
+pub struct BallTeleported {
+    pub ball: Entity,
+    pub group: String,
+    pub from: Vec3,
+    pub to: Vec3,
+}
+
+/// Keeps a ball from teleporting until the timer finishes.
+#[derive(Component)]
+pub struct TeleportCooldown(pub Timer);
+
+/// The `In` end of a teleporter, on the parent of the `In` mesh.
+#[derive(Component)]
+pub struct Teleporter {
+    pub group: String,
+    /// The parent of the `Out` mesh.
+    pub exit: Entity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TeleporterEnd {
+    In,
+    Out,
+}
+
+/// Returns the group name and end of a mesh name like `teleport_Cave_In` or `teleport_a_out`.
+pub fn parse_teleporter_name(name: &str) -> Option<(&str, TeleporterEnd)> {
+    let rest = name.strip_prefix(PREFIX)?;
+    let (group, end) = rest.rsplit_once('_')?;
+    let end = match end {
+        "In" | "in" => TeleporterEnd::In,
+        "Out" | "out" => TeleporterEnd::Out,
+        _ => return None,
+    };
+    (!group.is_empty()).then_some((group, end))
+}
+
+fn insert_teleporters(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf), With<Mesh3d>>,
+) {
+    let mut entrances = Vec::new();
+    let mut exits = HashMap::new();
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of)) = query.get(entity) else {
+            continue;
+        };
+        let Some((group, end)) = parse_teleporter_name(name) else {
+            continue;
+        };
+
+        match end {
+            TeleporterEnd::In => entrances.push((group, name, child_of)),
+            TeleporterEnd::Out => {
+                if exits.insert(group, child_of.parent()).is_some() {
+                    warn!("Teleporter `{group}` has several exits, using `{name}`.");
+                }
+            }
+        }
+    }
+
+    for (group, _) in exits.iter() {
+        if !entrances.iter().any(|entrance| entrance.0 == *group) {
+            warn!("Teleporter `{group}` has no `teleport_{group}_In` mesh, so its exit is unused.");
+        }
+    }
+
+    for (group, name, child_of) in entrances {
+        let Some(&exit) = exits.get(group) else {
+            warn!("Teleporter `{group}` has no `teleport_{group}_Out` mesh, ignoring `{name}`.");
+            continue;
+        };
+
+        commands.entity(child_of.parent()).insert(Teleporter {
+            group: group.to_string(),
+            exit,
+        });
+    }
+}
+
+fn tick_teleport_cooldowns(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut TeleportCooldown)>,
+) {
+    for (entity, mut cooldown) in query.iter_mut() {
+        if cooldown.0.tick(time.delta()).finished() {
+            commands.entity(entity).remove::<TeleportCooldown>();
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn teleport_ball(
+    mut commands: Commands,
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    mut teleported: EventWriter<BallTeleported>,
+    mut bursts: EventWriter<ParticleBurst>,
+    config: Res<TeleporterConfig>,
+    asset_server: Res<AssetServer>,
+    orbit_camera: Option<Res<OrbitCameraEnabled>>,
+    cinematic_mode: Option<Res<CinematicMode>>,
+    teleporters: Query<(&Teleporter, &GlobalTransform)>,
+    exits: Query<&GlobalTransform>,
+    mut balls: Query<
+        (&mut Transform, Option<&mut Velocity>, Has<TeleportCooldown>),
+        (With<Ball>, Without<Camera3d>),
+    >,
+    mut cameras: Query<&mut Transform, With<Camera3d>>,
+) {
+    let snap_cameras = !orbit_camera.is_some_and(|enabled| enabled.0)
+        && !cinematic_mode.is_some_and(|mode| mode.0);
+
+    for event in sensor_triggered.read() {
+        if !event.started {
+            continue;
+        }
+        let Ok((teleporter, entrance)) = teleporters.get(event.sensor) else {
+            continue;
+        };
+        let ball = event.other;
+        let Ok((mut transform, velocity, cooling_down)) = balls.get_mut(ball) else {
+            continue;
+        };
+        if cooling_down {
+            continue;
+        }
+        let Ok(exit) = exits.get(teleporter.exit) else {
+            warn!("The exit of teleporter `{}` is gone.", teleporter.group);
+            continue;
+        };
+
+        // Turns the entrance's frame into the exit's frame.
+        let rotation = exit.rotation() * entrance.rotation().inverse();
+        let from = transform.translation;
+        let to = exit.translation() + exit.up() * config.exit_offset;
+        transform.translation = to;
+        if let Some(mut velocity) = velocity {
+            *velocity = if config.preserve_velocity {
+                Velocity {
+                    linvel: rotation * velocity.linvel,
+                    angvel: rotation * velocity.angvel,
+                }
+            } else {
+                Velocity::zero()
+            };
+        }
+        commands
+            .entity(ball)
+            .insert(TeleportCooldown(Timer::from_seconds(
+                config.cooldown,
+                TimerMode::Once,
+            )));
+
+        if snap_cameras {
+            for mut camera in cameras.iter_mut() {
+                camera.translation = to + rotation * (camera.translation - from);
+                camera.rotation = rotation * camera.rotation;
+            }
+        }
+
+        for position in [entrance.translation(), exit.translation()] {
+            bursts.write(ParticleBurst {
+                position,
+                count: BURST_PARTICLES,
+                speed: BURST_SPEED,
+                color: BURST_COLOR,
+                lifetime: BURST_LIFETIME,
+            });
+        }
+        if let Some(sound) = &config.sound {
+            commands.spawn((
+                LevelEntity,
+                AudioPlayer::new(asset_server.load(sound.clone())),
+                PlaybackSettings::DESPAWN,
+            ));
+        }
+        teleported.write(BallTeleported {
+            ball,
+            group: teleporter.group.clone(),
+            from,
+            to,
+        });
+    }
+}
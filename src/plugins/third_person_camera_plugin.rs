@@ -0,0 +1,257 @@
+//! A camera that follows an entity, e.g. the ball, and orbits around it with the mouse.
+//! Moving the mouse turns the camera around the followed entity, which it keeps at
+//! [`ThirdPersonCamera::distance`].
+//!
+//! The camera orbits around the followed entity's position plus
+//! [`ThirdPersonCamera::target_offset`], but looks at its position plus
+//! [`ThirdPersonCamera::look_at_offset`]. Raising the look at offset keeps the ball from ending up
+//! near the bottom of the screen on steep ramps, without moving the camera up with it.
+//!
+//! [`ThirdPersonCameraBuilder`] makes a camera with the defaults for the fields that aren't set,
+//! e.g. `ThirdPersonCameraBuilder::new(ball).distance(4.0).invert_y(true).build()`.
+//!
+//! The camera is moved after the physics writes back the followed entity's [`Transform`], so the
+//! entity should have no parent, like the ball.
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{input::mouse::AccumulatedMouseMotion, prelude::*};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::game_state_plugin::playing;
+
+/// Keeps the camera from flipping over the poles.
+const MAX_PITCH: f32 = FRAC_PI_2 - 0.01;
+
+pub struct ThirdPersonCameraPlugin;
+
+impl Plugin for ThirdPersonCameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, orbit_third_person_cameras.run_if(playing))
+            .add_systems(
+                PostUpdate,
+                follow_third_person_cameras
+                    .after(PhysicsSet::Writeback)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ThirdPersonCamera {
+    /// The entity the camera follows.
+    pub follow: Entity,
+    pub distance: f32,
+    /// Radians per pixel of mouse movement.
+    pub sensitivity: f32,
+    /// Moving the mouse up looks up instead of down.
+    pub invert_y: bool,
+    /// Added to the followed entity's position for the point the camera orbits around.
+    pub target_offset: Vec3,
+    /// Added to the followed entity's position for the point the camera looks at.
+    pub look_at_offset: Vec3,
+    /// The angle around the `Y` axis in radians, zero looking along `-Z`.
+    pub yaw: f32,
+    /// The angle above the horizon in radians.
+    pub pitch: f32,
+}
+
+impl ThirdPersonCamera {
+    /// The position of the camera when the followed entity is at `target`.
+    pub fn position(&self, target: Vec3) -> Vec3 {
+        let direction = Vec3::new(
+            self.pitch.cos() * self.yaw.sin(),
+            self.pitch.sin(),
+            self.pitch.cos() * self.yaw.cos(),
+        );
+        target + self.target_offset + direction * self.distance
+    }
+
+    /// The point the camera looks at when the followed entity is at `target`.
+    pub fn look_at(&self, target: Vec3) -> Vec3 {
+        target + self.look_at_offset
+    }
+}
+
+/// Builds a [`ThirdPersonCamera`], see the [module docs](self).
+#[derive(Clone, Copy, Debug)]
+pub struct ThirdPersonCameraBuilder {
+    camera: ThirdPersonCamera,
+}
+
+impl ThirdPersonCameraBuilder {
+    /// A camera following the entity from 5 units behind and a bit above it.
+    pub fn new(follow: Entity) -> Self {
+        Self {
+            camera: ThirdPersonCamera {
+                follow,
+                distance: 5.0,
+                sensitivity: 0.005,
+                invert_y: false,
+                target_offset: Vec3::ZERO,
+                look_at_offset: Vec3::ZERO,
+                yaw: 0.0,
+                pitch: 0.3,
+            },
+        }
+    }
+
+    pub fn distance(mut self, distance: f32) -> Self {
+        self.camera.distance = distance;
+        self
+    }
+
+    pub fn sensitivity(mut self, sensitivity: f32) -> Self {
+        self.camera.sensitivity = sensitivity;
+        self
+    }
+
+    pub fn invert_y(mut self, invert_y: bool) -> Self {
+        self.camera.invert_y = invert_y;
+        self
+    }
+
+    pub fn target_offset(mut self, target_offset: Vec3) -> Self {
+        self.camera.target_offset = target_offset;
+        self
+    }
+
+    pub fn look_at_offset(mut self, look_at_offset: Vec3) -> Self {
+        self.camera.look_at_offset = look_at_offset;
+        self
+    }
+
+    pub fn yaw(mut self, yaw: f32) -> Self {
+        self.camera.yaw = yaw;
+        self
+    }
+
+    pub fn pitch(mut self, pitch: f32) -> Self {
+        self.camera.pitch = pitch;
+        self
+    }
+
+    /// # Panics
+    ///
+    /// If the distance or the sensitivity isn't positive.
+    pub fn build(self) -> ThirdPersonCamera {
+        let camera = self.camera;
+        assert!(
+            camera.distance > 0.0,
+            "The distance of a ThirdPersonCamera must be positive, but it's {}.",
+            camera.distance
+        );
+        assert!(
+            camera.sensitivity > 0.0,
+            "The sensitivity of a ThirdPersonCamera must be positive, but it's {}.",
+            camera.sensitivity
+        );
+        camera
+    }
+}
+
+fn orbit_third_person_cameras(
+    motion: Res<AccumulatedMouseMotion>,
+    mut cameras: Query<&mut ThirdPersonCamera>,
+) {
+    if motion.delta == Vec2::ZERO {
+        return;
+    }
+
+    for mut camera in cameras.iter_mut() {
+        let delta_y = if camera.invert_y {
+            -motion.delta.y
+        } else {
+            motion.delta.y
+        };
+        camera.yaw -= motion.delta.x * camera.sensitivity;
+        camera.pitch = (camera.pitch + delta_y * camera.sensitivity).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+}
+
+fn follow_third_person_cameras(
+    targets: Query<&Transform, Without<ThirdPersonCamera>>,
+    mut cameras: Query<(&ThirdPersonCamera, &mut Transform)>,
+) {
+    for (camera, mut transform) in cameras.iter_mut() {
+        let Ok(target) = targets.get(camera.follow) else {
+            continue;
+        };
+        *transform = Transform::from_translation(camera.position(target.translation))
+            .looking_at(camera.look_at(target.translation), Vec3::Y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_at_the_offset_above_the_followed_entity() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, ThirdPersonCameraPlugin))
+            .init_resource::<AccumulatedMouseMotion>();
+        let ball = app
+            .world_mut()
+            .spawn(Transform::from_xyz(1.0, 2.0, 3.0))
+            .id();
+        let camera = app
+            .world_mut()
+            .spawn((
+                ThirdPersonCameraBuilder::new(ball)
+                    .distance(4.0)
+                    .target_offset(Vec3::new(0.0, 0.5, 0.0))
+                    .look_at_offset(Vec3::new(0.0, 1.5, 0.0))
+                    .pitch(0.0)
+                    .build(),
+                Transform::default(),
+            ))
+            .id();
+        app.update();
+
+        let transform = app.world().get::<Transform>(camera).unwrap();
+        // Orbits the target offset, 4 behind the ball along `+Z`.
+        assert!(
+            transform
+                .translation
+                .abs_diff_eq(Vec3::new(1.0, 2.5, 7.0), 1e-5)
+        );
+        let look_at = Vec3::new(1.0, 3.5, 3.0);
+        let forward = (look_at - transform.translation).normalize();
+        assert!(transform.forward().abs_diff_eq(forward, 1e-5));
+    }
+
+    #[test]
+    fn builds_with_the_defaults_of_unset_fields() {
+        let ball = Entity::from_raw(1);
+        let camera = ThirdPersonCameraBuilder::new(ball)
+            .distance(4.0)
+            .sensitivity(0.000002)
+            .invert_y(true)
+            .build();
+        let default = ThirdPersonCameraBuilder::new(ball).build();
+
+        assert_eq!(camera.follow, ball);
+        assert_eq!(camera.distance, 4.0);
+        assert_eq!(camera.sensitivity, 0.000002);
+        assert!(camera.invert_y);
+        assert_eq!(camera.pitch, default.pitch);
+        assert_eq!(camera.look_at_offset, default.look_at_offset);
+    }
+
+    #[test]
+    #[should_panic(expected = "distance of a ThirdPersonCamera must be positive")]
+    fn rejects_a_distance_that_isnt_positive() {
+        ThirdPersonCameraBuilder::new(Entity::from_raw(1))
+            .distance(0.0)
+            .build();
+    }
+
+    #[test]
+    #[should_panic(expected = "sensitivity of a ThirdPersonCamera must be positive")]
+    fn rejects_a_sensitivity_that_isnt_positive() {
+        ThirdPersonCameraBuilder::new(Entity::from_raw(1))
+            .sensitivity(-0.1)
+            .build();
+    }
+}
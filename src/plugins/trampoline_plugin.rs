@@ -0,0 +1,247 @@
+//! Turns glTF meshes named `trampoline_*` into trampolines, e.g. `trampoline_Pad`, or
+//! `trampoline_1.8_Pad` for a restitution of 1.8 instead of [`TrampolineConfig::restitution`].
+//! A trampoline is solid, and its restitution above 1 with [`CoefficientCombineRule::Max`] makes
+//! the ball gain height on each bounce. To launch the ball with an impulse instead, use a
+//! `bounce_` pad of the [`BouncePadPlugin`].
+//!
+//! The prefix is [`TrampolineConfig::prefix`]. Levels that name their trampolines `bounce_` can
+//! set it to that, as long as they don't use the [`BouncePadPlugin`] too, since both would turn
+//! the same meshes into their own pads.
+//!
+//! [`BouncePadPlugin`]: crate::plugins::bounce_pad_plugin::BouncePadPlugin
+//!
+//! When the ball hits a trampoline hard enough to send a contact force event, its speed along the
+//! trampoline's up is clamped to [`TrampolineConfig::max_bounce_speed`] so the bounces don't blow
+//! up. A sound is played with a pitch rising with the impact speed, and the trampoline's mesh
+//! squishes down, which only happens again once the squish is over.
+//!
+//! The colliders are built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline, and
+//! get their restitution once they're inserted.
+
+use bevy::{prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    level_manifest_plugin::LevelEntity,
+    mesh_physics_plugin::{ColliderKind, ObjectCollider, register_object_collider},
+};
+
+/// How much flatter the trampoline gets at the peak of the squish.
+const SQUISH_SCALE: f32 = 0.3;
+/// The impact speed played at the sound's normal pitch.
+const NORMAL_PITCH_SPEED: f32 = 10.0;
+
+#[derive(Default)]
+pub struct TrampolinePlugin {
+    pub config: TrampolineConfig,
+}
+
+impl Plugin for TrampolinePlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                prefix: self.config.prefix.clone(),
+                kind: ColliderKind::Hull,
+                restitution: self.config.restitution,
+                ..default()
+            },
+        );
+        app.insert_resource(self.config.clone())
+            .add_systems(Update, (bounce_on_trampolines, squish_trampolines).chain())
+            .add_observer(insert_trampolines)
+            .add_observer(set_trampoline_restitution);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct TrampolineConfig {
+    /// The start of the names of the meshes that become trampolines.
+    pub prefix: String,
+    /// The restitution of the trampolines that don't set their own.
+    pub restitution: f32,
+    /// The fastest the ball leaves a trampoline along its up.
+    pub max_bounce_speed: f32,
+    /// The smallest contact force that counts as hitting a trampoline.
+    pub force_threshold: f32,
+    /// How long a trampoline squishes and doesn't react to hits, in seconds.
+    pub squish_duration: f32,
+    /// The asset path of the sound played when the ball hits a trampoline.
+    pub sound: Option<String>,
+}
+
+impl Default for TrampolineConfig {
+    fn default() -> Self {
+        Self {
+            prefix: "trampoline_".to_string(),
+            restitution: 1.5,
+            max_bounce_speed: 20.0,
+            force_threshold: 10.0,
+            squish_duration: 0.25,
+            sound: None,
+        }
+    }
+}
+
+/// A trampoline created from a [`TrampolineConfig::prefix`] mesh, on the mesh's parent.
+#[derive(Component)]
+pub struct Trampoline {
+    pub restitution: f32,
+}
+
+/// The seconds left in the squish of a [`Trampoline`].
+#[derive(Component)]
+pub struct TrampolineSquish(pub f32);
+
+/// The mesh of a trampoline, which is scaled by the squish instead of the trampoline so the
+/// collider keeps its size.
+#[derive(Component)]
+struct TrampolineMesh {
+    entity: Entity,
+    scale: Vec3,
+}
+
+/// Returns the restitution of a mesh name like `trampoline_1.8_Pad` with the prefix
+/// `trampoline_`, if it has one.
+pub fn parse_trampoline_restitution(name: &str, prefix: &str) -> Option<f32> {
+    let rest = name.strip_prefix(prefix)?;
+    rest.split('_').next()?.parse().ok()
+}
+
+fn insert_trampolines(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    config: Res<TrampolineConfig>,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf, Option<&Transform>), With<Mesh3d>>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of, transform)) = query.get(entity) else {
+            continue;
+        };
+        if !name.starts_with(config.prefix.as_str()) {
+            continue;
+        }
+        let restitution =
+            parse_trampoline_restitution(name, &config.prefix).unwrap_or(config.restitution);
+
+        commands.entity(child_of.parent()).insert((
+            Trampoline { restitution },
+            TrampolineMesh {
+                entity,
+                scale: transform.map_or(Vec3::ONE, |transform| transform.scale),
+            },
+            ActiveEvents::CONTACT_FORCE_EVENTS,
+            ContactForceEventThreshold(config.force_threshold),
+        ));
+    }
+}
+
+/// Replaces the restitution inserted with the collider by the trampoline's own.
+fn set_trampoline_restitution(
+    trigger: Trigger<OnInsert, Collider>,
+    mut commands: Commands,
+    trampolines: Query<&Trampoline>,
+) {
+    let Ok(trampoline) = trampolines.get(trigger.target()) else {
+        return;
+    };
+    commands.entity(trigger.target()).insert(Restitution {
+        coefficient: trampoline.restitution,
+        combine_rule: CoefficientCombineRule::Max,
+    });
+}
+
+fn bounce_on_trampolines(
+    mut commands: Commands,
+    mut contact_force_events: EventReader<ContactForceEvent>,
+    config: Res<TrampolineConfig>,
+    asset_server: Res<AssetServer>,
+    trampolines: Query<(&GlobalTransform, Has<TrampolineSquish>), With<Trampoline>>,
+    mut balls: Query<&mut Velocity, With<Ball>>,
+) {
+    for event in contact_force_events.read() {
+        for (trampoline, ball) in [
+            (event.collider1, event.collider2),
+            (event.collider2, event.collider1),
+        ] {
+            let Ok((trampoline_transform, squishing)) = trampolines.get(trampoline) else {
+                continue;
+            };
+            let Ok(mut velocity) = balls.get_mut(ball) else {
+                continue;
+            };
+
+            let up = trampoline_transform.up();
+            let up_speed = velocity.linvel.dot(*up);
+            if up_speed > config.max_bounce_speed {
+                velocity.linvel -= up * (up_speed - config.max_bounce_speed);
+            }
+            if squishing {
+                continue;
+            }
+            commands
+                .entity(trampoline)
+                .insert(TrampolineSquish(config.squish_duration));
+
+            if let Some(sound) = &config.sound {
+                let pitch = (velocity.linvel.length() / NORMAL_PITCH_SPEED).clamp(0.5, 2.0);
+                commands.spawn((
+                    LevelEntity,
+                    AudioPlayer::new(asset_server.load(sound.clone())),
+                    PlaybackSettings::DESPAWN.with_speed(pitch),
+                ));
+            }
+        }
+    }
+}
+
+fn squish_trampolines(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<TrampolineConfig>,
+    mut trampolines: Query<(Entity, &TrampolineMesh, &mut TrampolineSquish)>,
+    mut transforms: Query<&mut Transform>,
+) {
+    for (entity, mesh, mut squish) in trampolines.iter_mut() {
+        squish.0 -= time.delta_secs();
+        if squish.0 <= 0.0 {
+            commands.entity(entity).remove::<TrampolineSquish>();
+        }
+        let Ok(mut transform) = transforms.get_mut(mesh.entity) else {
+            continue;
+        };
+
+        // Flattens and springs back over the squish.
+        let progress = (1.0 - squish.0 / config.squish_duration).min(1.0);
+        let squash = (progress * std::f32::consts::PI).sin() * SQUISH_SCALE;
+        transform.scale = mesh.scale * Vec3::new(1.0, 1.0 - squash, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_the_restitution_after_the_configured_prefix() {
+        assert_eq!(
+            parse_trampoline_restitution("trampoline_1.8_Pad", "trampoline_"),
+            Some(1.8)
+        );
+        assert_eq!(
+            parse_trampoline_restitution("trampoline_Pad", "trampoline_"),
+            None
+        );
+        assert_eq!(
+            parse_trampoline_restitution("bounce_2_Pad", "bounce_"),
+            Some(2.0)
+        );
+        assert_eq!(
+            parse_trampoline_restitution("bounce_2_Pad", "trampoline_"),
+            None
+        );
+    }
+}
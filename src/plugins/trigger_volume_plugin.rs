@@ -0,0 +1,139 @@
+//! Turns glTF meshes named `trigger_<TriggerName>_*` into sensor volumes.
+//! When something enters one of them, a [`TriggerEntered`] event is sent with the trigger name,
+//! so gameplay systems only need to listen for the names they care about, e.g. `Goal`.
+//!
+//! The volumes are `trigger` sensors of the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline, so
+//! their colliders are built like the others, and [`TriggerEntered`] is sent for the
+//! [`SensorTriggered`] events that start on them.
+
+use bevy::{prelude::*, scene::SceneInstanceReady};
+
+use crate::plugins::mesh_physics_plugin::{
+    ObjectCollider, SensorTriggered, register_object_collider,
+};
+
+const PREFIX: &str = "trigger_";
+
+pub struct TriggerVolumePlugin;
+
+impl Plugin for TriggerVolumePlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(app, ObjectCollider::sensor(PREFIX));
+        app.add_event::<TriggerEntered>()
+            .add_systems(Update, send_trigger_events)
+            .add_observer(insert_trigger_volumes);
+    }
+}
+
+/// A sensor volume created from a `trigger_` mesh.
+#[derive(Component)]
+pub struct TriggerVolume {
+    pub name: String,
+}
+
+#[derive(Event)]
+pub struct TriggerEntered {
+    /// The entity that entered the trigger.
+    pub entity: Entity,
+    /// The entity of the [`TriggerVolume`].
+    pub volume: Entity,
+    pub trigger_name: String,
+}
+
+/// Returns the trigger name of a mesh name like `trigger_Goal_ring`.
+pub fn parse_trigger_name(name: &str) -> Option<&str> {
+    let rest = name.strip_prefix(PREFIX)?;
+    let trigger_name = rest.split('_').next()?;
+    (!trigger_name.is_empty()).then_some(trigger_name)
+}
+
+fn insert_trigger_volumes(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf), With<Mesh3d>>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of)) = query.get(entity) else {
+            continue;
+        };
+        if let Some(trigger_name) = parse_trigger_name(name) {
+            commands.entity(child_of.parent()).insert(TriggerVolume {
+                name: trigger_name.to_string(),
+            });
+        }
+    }
+}
+
+fn send_trigger_events(
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    mut trigger_entered: EventWriter<TriggerEntered>,
+    volumes: Query<&TriggerVolume>,
+) {
+    for event in sensor_triggered.read() {
+        if !event.started {
+            continue;
+        }
+        if let Ok(volume) = volumes.get(event.sensor) {
+            trigger_entered.write(TriggerEntered {
+                entity: event.other,
+                volume: event.sensor,
+                trigger_name: volume.name.clone(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Resource, Default)]
+    struct Entered(Vec<(Entity, Entity, String)>);
+
+    #[test]
+    fn sends_trigger_entered_when_a_trigger_sensor_starts() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_event::<SensorTriggered>()
+            .add_event::<TriggerEntered>()
+            .init_resource::<Entered>()
+            .add_systems(Update, send_trigger_events)
+            .add_systems(
+                PostUpdate,
+                |mut trigger_entered: EventReader<TriggerEntered>, mut entered: ResMut<Entered>| {
+                    entered.0.extend(
+                        trigger_entered
+                            .read()
+                            .map(|event| (event.entity, event.volume, event.trigger_name.clone())),
+                    );
+                },
+            );
+        let ball = app.world_mut().spawn_empty().id();
+        let volume = app
+            .world_mut()
+            .spawn(TriggerVolume {
+                name: "Goal".to_string(),
+            })
+            .id();
+        let other_sensor = app.world_mut().spawn_empty().id();
+
+        for (sensor, started) in [(volume, true), (volume, false), (other_sensor, true)] {
+            app.world_mut().send_event(SensorTriggered {
+                label: "trigger".to_string(),
+                sensor,
+                other: ball,
+                started,
+            });
+        }
+        app.update();
+
+        assert_eq!(
+            app.world().resource::<Entered>().0,
+            [(ball, volume, "Goal".to_string())]
+        );
+        assert_eq!(parse_trigger_name("trigger_Goal_ring"), Some("Goal"));
+        assert_eq!(parse_trigger_name("trigger__ring"), None);
+    }
+}
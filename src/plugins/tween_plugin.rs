@@ -0,0 +1,261 @@
+//! Animates components from one value to another over time with a [`Tween`].
+//! The type of the tweened value picks what's animated:
+//! - `Vec3`: the translation of the [`Transform`].
+//! - `Quat`: the rotation of the [`Transform`].
+//! - `f32`: the uniform scale of the [`Transform`].
+//! - [`Color`]: the [`TextColor`], [`BackgroundColor`] or [`StandardMaterial`] base color.
+//! - [`Alpha`]: only the alpha of the same colors, e.g. to fade out text before despawning it.
+//!
+//! A material is changed in place, so entities sharing it are animated too.
+
+use bevy::{color::Alpha as _, prelude::*};
+
+pub struct TweenPlugin;
+
+impl Plugin for TweenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                (
+                    advance_tweens::<Vec3>,
+                    apply_translation,
+                    finish_tweens::<Vec3>,
+                )
+                    .chain(),
+                (
+                    advance_tweens::<Quat>,
+                    apply_rotation,
+                    finish_tweens::<Quat>,
+                )
+                    .chain(),
+                (advance_tweens::<f32>, apply_scale, finish_tweens::<f32>).chain(),
+                (advance_tweens::<Color>, apply_color, finish_tweens::<Color>).chain(),
+                (advance_tweens::<Alpha>, apply_alpha, finish_tweens::<Alpha>).chain(),
+            ),
+        );
+    }
+}
+
+/// Maps the linear progress in `[0, 1]` to the eased progress.
+pub type EasingFn = fn(f32) -> f32;
+
+pub fn linear(t: f32) -> f32 {
+    t
+}
+
+pub fn ease_in_out(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
+
+pub fn ease_out(t: f32) -> f32 {
+    1.0 - (1.0 - t) * (1.0 - t)
+}
+
+/// What a [`Tween`] does when it reaches its end.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TweenComplete {
+    /// Removes the tween, leaving the end value.
+    Remove,
+    /// Despawns the entity, e.g. after fading it out.
+    Despawn,
+    /// Plays back to the start, and forth again, forever.
+    Reverse,
+    /// Starts over from the start.
+    Loop,
+}
+
+/// The alpha of a color, tweened without touching its other channels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Alpha(pub f32);
+
+/// A value that can be tweened.
+pub trait TweenValue: Copy + Send + Sync + 'static {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self;
+}
+
+impl TweenValue for Vec3 {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        from.lerp(to, t)
+    }
+}
+
+impl TweenValue for Quat {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        from.slerp(to, t)
+    }
+}
+
+impl TweenValue for f32 {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        from + (to - from) * t
+    }
+}
+
+impl TweenValue for Color {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        // Mixed in linear space so the colors in between aren't too dark.
+        LinearRgba::from(from).mix(&LinearRgba::from(to), t).into()
+    }
+}
+
+impl TweenValue for Alpha {
+    fn interpolate(from: Self, to: Self, t: f32) -> Self {
+        Alpha(<f32 as TweenValue>::interpolate(from.0, to.0, t))
+    }
+}
+
+#[derive(Component, Clone)]
+pub struct Tween<T: TweenValue> {
+    pub from: T,
+    pub to: T,
+    /// In seconds.
+    pub duration: f32,
+    pub elapsed: f32,
+    pub easing: EasingFn,
+    pub on_complete: TweenComplete,
+}
+
+impl<T: TweenValue> Tween<T> {
+    /// A linear tween that's removed when it's done.
+    pub fn new(from: T, to: T, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            elapsed: 0.0,
+            easing: linear,
+            on_complete: TweenComplete::Remove,
+        }
+    }
+
+    pub fn with_easing(mut self, easing: EasingFn) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    pub fn with_on_complete(mut self, on_complete: TweenComplete) -> Self {
+        self.on_complete = on_complete;
+        self
+    }
+
+    /// The value at the current time.
+    pub fn value(&self) -> T {
+        let t = if self.duration > 0.0 {
+            (self.elapsed / self.duration).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        T::interpolate(self.from, self.to, (self.easing)(t))
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+fn advance_tweens<T: TweenValue>(time: Res<Time>, mut query: Query<&mut Tween<T>>) {
+    for mut tween in query.iter_mut() {
+        tween.elapsed += time.delta_secs();
+    }
+}
+
+/// Runs after the end value is applied, so a removed tween leaves it behind. The entity may be
+/// despawned by a parent finishing in the same frame, e.g. a faded out toast and its text.
+fn finish_tweens<T: TweenValue>(mut commands: Commands, mut query: Query<(Entity, &mut Tween<T>)>) {
+    for (entity, mut tween) in query.iter_mut() {
+        if !tween.is_finished() {
+            continue;
+        }
+
+        match tween.on_complete {
+            TweenComplete::Remove => {
+                commands.entity(entity).try_remove::<Tween<T>>();
+            }
+            TweenComplete::Despawn => {
+                commands.entity(entity).try_despawn();
+            }
+            TweenComplete::Reverse => {
+                let tween = &mut *tween;
+                std::mem::swap(&mut tween.from, &mut tween.to);
+                tween.elapsed = (tween.elapsed - tween.duration).max(0.0);
+            }
+            TweenComplete::Loop => {
+                tween.elapsed = (tween.elapsed - tween.duration).max(0.0);
+            }
+        }
+    }
+}
+
+fn apply_translation(mut query: Query<(&Tween<Vec3>, &mut Transform)>) {
+    for (tween, mut transform) in query.iter_mut() {
+        transform.translation = tween.value();
+    }
+}
+
+fn apply_rotation(mut query: Query<(&Tween<Quat>, &mut Transform)>) {
+    for (tween, mut transform) in query.iter_mut() {
+        transform.rotation = tween.value();
+    }
+}
+
+fn apply_scale(mut query: Query<(&Tween<f32>, &mut Transform)>) {
+    for (tween, mut transform) in query.iter_mut() {
+        transform.scale = Vec3::splat(tween.value());
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_color(
+    // Missing without a renderer, e.g. in headless apps only tweening transforms.
+    mut materials: Option<ResMut<Assets<StandardMaterial>>>,
+    mut query: Query<(
+        &Tween<Color>,
+        Option<&mut TextColor>,
+        Option<&mut BackgroundColor>,
+        Option<&MeshMaterial3d<StandardMaterial>>,
+    )>,
+) {
+    for (tween, text_color, background_color, material) in query.iter_mut() {
+        let color = tween.value();
+        if let Some(mut text_color) = text_color {
+            text_color.0 = color;
+        }
+        if let Some(mut background_color) = background_color {
+            background_color.0 = color;
+        }
+        if let Some(material) = material
+            .zip(materials.as_deref_mut())
+            .and_then(|(material, materials)| materials.get_mut(&material.0))
+        {
+            material.base_color = color;
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn apply_alpha(
+    mut materials: Option<ResMut<Assets<StandardMaterial>>>,
+    mut query: Query<(
+        &Tween<Alpha>,
+        Option<&mut TextColor>,
+        Option<&mut BackgroundColor>,
+        Option<&MeshMaterial3d<StandardMaterial>>,
+    )>,
+) {
+    for (tween, text_color, background_color, material) in query.iter_mut() {
+        let Alpha(alpha) = tween.value();
+        if let Some(mut text_color) = text_color {
+            text_color.0.set_alpha(alpha);
+        }
+        if let Some(mut background_color) = background_color {
+            background_color.0.set_alpha(alpha);
+        }
+        if let Some(material) = material
+            .zip(materials.as_deref_mut())
+            .and_then(|(material, materials)| materials.get_mut(&material.0))
+        {
+            material.base_color.set_alpha(alpha);
+        }
+    }
+}
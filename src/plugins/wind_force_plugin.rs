@@ -0,0 +1,259 @@
+//! Blows wind on every [`Ball`], e.g. as a hazard pushing the ball towards the edge of a level.
+//! The wind only blows when a [`WindConfig`] resource is added and [`WindEnabled`] is `true`.
+//! Its strength and direction wander around the base direction following Perlin noise over time,
+//! so it comes in smooth gusts.
+//!
+//! glTF meshes named `wind_*` are zones that push the balls inside them with a constant force
+//! along their local `+Y`, or `+Z` with a `z` token, e.g. `wind_Updraft` or `wind_z_25.0_Tunnel`.
+//! The strength is the number in the name, or the `wind_strength` number in the glTF extras of
+//! the mesh or its parent, or [`DEFAULT_ZONE_STRENGTH`]. While the
+//! [`ColliderDebug`](crate::plugins::collider_debug_plugin::ColliderDebug) overlays are shown,
+//! arrows show the direction of the zones. The zones' sensors are built by the
+//! [`MeshPhysicsPlugin`](crate::plugins::mesh_physics_plugin::MeshPhysicsPlugin) pipeline.
+//!
+//! Both winds only add their own part to the ball's [`ExternalForce`] and take it back when it
+//! changes, so the forces from other systems, like the player's torque, aren't overwritten.
+
+use bevy::{gltf::GltfExtras, prelude::*, scene::SceneInstanceReady};
+use bevy_rapier3d::prelude::*;
+
+use crate::plugins::{
+    ball_physics_plugin::Ball,
+    collider_debug_plugin::ColliderDebug,
+    mesh_physics_plugin::{ObjectCollider, SensorTriggered, register_object_collider},
+};
+
+const PREFIX: &str = "wind_";
+
+/// The force of the wind zones that don't set their own strength.
+pub const DEFAULT_ZONE_STRENGTH: f32 = 10.0;
+
+pub struct WindForcePlugin;
+
+impl Plugin for WindForcePlugin {
+    fn build(&self, app: &mut App) {
+        register_object_collider(
+            app,
+            ObjectCollider {
+                hide_mesh: false,
+                ..ObjectCollider::sensor(PREFIX)
+            },
+        );
+        app.insert_resource(WindEnabled(true))
+            .add_systems(
+                Update,
+                (
+                    apply_wind,
+                    (track_wind_zone_contacts, apply_wind_zones).chain(),
+                    draw_wind_zones.run_if(|debug: Option<Res<ColliderDebug>>| {
+                        debug.is_some_and(|debug| debug.enabled)
+                    }),
+                ),
+            )
+            .add_observer(insert_wind_zones);
+    }
+}
+
+#[derive(Resource, Clone)]
+pub struct WindConfig {
+    /// The direction and strength of the wind force without turbulence.
+    pub base_direction: Vec3,
+    /// How many gusts there are per second, roughly.
+    pub turbulence_frequency: f32,
+    /// The strength of the turbulence added to each axis of the force.
+    pub turbulence_amplitude: f32,
+}
+
+#[derive(Resource)]
+pub struct WindEnabled(pub bool);
+
+/// The wind force currently added to a ball's [`ExternalForce`], so it can be replaced next
+/// frame without touching the forces from other systems.
+#[derive(Component)]
+struct AppliedWind(Vec3);
+
+/// A zone created from a `wind_` mesh, on the mesh's parent.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct WindZone {
+    /// The local direction of the force.
+    pub direction: Vec3,
+    pub strength: f32,
+}
+
+/// The wind zones a ball is in and the force they currently add to its [`ExternalForce`].
+#[derive(Component, Default)]
+struct InWindZones {
+    zones: Vec<Entity>,
+    applied: Vec3,
+}
+
+/// Returns the strength and local direction in a mesh name like `wind_z_25.0_Tunnel`.
+pub fn parse_wind_name(name: &str) -> Option<(Option<f32>, Vec3)> {
+    let rest = name.strip_prefix(PREFIX)?;
+    let mut strength = None;
+    let mut direction = Vec3::Y;
+    for token in rest.split('_') {
+        if token == "z" {
+            direction = Vec3::Z;
+        } else if let Ok(value) = token.parse() {
+            strength.get_or_insert(value);
+        }
+    }
+    Some((strength, direction))
+}
+
+/// Parses the `wind_strength` from the extras JSON.
+fn parse_wind_strength(json: &str) -> Result<Option<f32>, String> {
+    let value: serde_json::Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+    value
+        .get("wind_strength")
+        .map(|strength| {
+            strength
+                .as_f64()
+                .map(|strength| strength as f32)
+                .ok_or_else(|| format!("`wind_strength` should be a number, got {strength}"))
+        })
+        .transpose()
+}
+
+fn insert_wind_zones(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    query: Query<(&Name, &ChildOf), With<Mesh3d>>,
+    extras_query: Query<&GltfExtras>,
+) {
+    for entity in children.iter_descendants(trigger.target()) {
+        let Ok((name, child_of)) = query.get(entity) else {
+            continue;
+        };
+        let Some((name_strength, direction)) = parse_wind_name(name) else {
+            continue;
+        };
+
+        let extras_strength = extras_query
+            .get(entity)
+            .or_else(|_| extras_query.get(child_of.parent()))
+            .map_or(Ok(None), |extras| parse_wind_strength(&extras.value))
+            .unwrap_or_else(|err| {
+                warn!("Skipping the extras of `{name}`: {err}");
+                None
+            });
+
+        commands.entity(child_of.parent()).insert(WindZone {
+            direction,
+            strength: name_strength
+                .or(extras_strength)
+                .unwrap_or(DEFAULT_ZONE_STRENGTH),
+        });
+    }
+}
+
+fn apply_wind(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Option<Res<WindConfig>>,
+    enabled: Res<WindEnabled>,
+    mut balls: Query<(Entity, &mut ExternalForce, Option<&mut AppliedWind>), With<Ball>>,
+) {
+    let wind = match config {
+        Some(config) if enabled.0 => {
+            let t = time.elapsed_secs() * config.turbulence_frequency;
+            // Offset the axes so they don't gust in sync.
+            let turbulence = Vec3::new(perlin(t), perlin(t + 31.4), perlin(t + 72.9));
+            config.base_direction + turbulence * config.turbulence_amplitude
+        }
+        _ => Vec3::ZERO,
+    };
+
+    for (entity, mut force, applied) in balls.iter_mut() {
+        match applied {
+            Some(mut applied) => {
+                force.force += wind - applied.0;
+                applied.0 = wind;
+            }
+            None => {
+                force.force += wind;
+                commands.entity(entity).insert(AppliedWind(wind));
+            }
+        }
+    }
+}
+
+fn track_wind_zone_contacts(
+    mut commands: Commands,
+    mut sensor_triggered: EventReader<SensorTriggered>,
+    zones: Query<(), With<WindZone>>,
+    mut balls: Query<Option<&mut InWindZones>, With<Ball>>,
+) {
+    for event in sensor_triggered.read() {
+        let zone = event.sensor;
+        if !zones.contains(zone) {
+            continue;
+        }
+        let Ok(in_zones) = balls.get_mut(event.other) else {
+            continue;
+        };
+
+        match (in_zones, event.started) {
+            (Some(mut in_zones), true) => in_zones.zones.push(zone),
+            (Some(mut in_zones), false) => in_zones.zones.retain(|&other| other != zone),
+            (None, true) => {
+                commands.entity(event.other).insert(InWindZones {
+                    zones: vec![zone],
+                    applied: Vec3::ZERO,
+                });
+            }
+            (None, false) => {}
+        }
+    }
+}
+
+fn apply_wind_zones(
+    zones: Query<(&WindZone, &GlobalTransform)>,
+    mut balls: Query<(&mut InWindZones, &mut ExternalForce)>,
+) {
+    for (mut in_zones, mut force) in balls.iter_mut() {
+        // Zones that were despawned don't send a stop event.
+        in_zones.zones.retain(|&zone| zones.contains(zone));
+
+        let wind: Vec3 = in_zones
+            .zones
+            .iter()
+            .filter_map(|&zone| zones.get(zone).ok())
+            .map(|(zone, transform)| transform.rotation() * zone.direction * zone.strength)
+            .sum();
+
+        force.force += wind - in_zones.applied;
+        in_zones.applied = wind;
+    }
+}
+
+fn draw_wind_zones(mut gizmos: Gizmos, zones: Query<(&WindZone, &GlobalTransform)>) {
+    for (zone, transform) in zones.iter() {
+        let start = transform.translation();
+        let direction = transform.rotation() * zone.direction;
+        gizmos.arrow(start, start + direction * 2.0, Color::srgb(0.4, 0.9, 1.0));
+    }
+}
+
+/// 1D Perlin noise in about `[-0.5, 0.5]`, which is zero at integers.
+fn perlin(x: f32) -> f32 {
+    let cell = x.floor();
+    let t = x - cell;
+    // A smooth step so the gusts change without kinks.
+    let fade = t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+
+    let left = gradient(cell as i32) * t;
+    let right = gradient(cell as i32 + 1) * (t - 1.0);
+    left + (right - left) * fade
+}
+
+/// A pseudo-random gradient in `[-1, 1]` for an integer.
+fn gradient(i: i32) -> f32 {
+    let mut hash = (i as u32).wrapping_mul(0x9E37_79B9);
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85EB_CA6B);
+    hash ^= hash >> 13;
+    hash as f32 / u32::MAX as f32 * 2.0 - 1.0
+}